@@ -1,12 +1,36 @@
-use std::{path::PathBuf, time::Instant};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, atomic::Ordering},
+    time::Instant,
+};
 
 use futures::{StreamExt, stream};
 use tokio::sync::watch;
 
 use crate::{
-    comic_info::ComicInfo,
-    ui::list::Chapter,
-    zip_util::{derive_comic_info, modify_comic_info, replace_comic_info, volume_comic_info},
+    comic_info::{ComicInfo, ComicInfoField},
+    config::{SpecialNumbering, SpecialsConfig},
+    index::{FileKey, LibraryIndex},
+    lint::{self, LintContext, LintRule},
+    managers::{
+        details::ChapterDetails,
+        jobs::{JobEvent, JobRunner},
+        preview::{BatchPreview, ChapterPreview},
+        rename::{RenameKind, RenamePreview, RenameRow},
+        report::{SeriesReport, analyze},
+        stats::{ChapterStats, LibraryStats},
+    },
+    series_json::SeriesJson,
+    template::{self, TemplateValues},
+    transform::Transform,
+    ui::list::{Chapter, ChapterKind, Series, SeriesFolder},
+    volume_map::VolumeMap,
+    zip_util::{
+        derive_updater, legacy_entry_names, read_comic_info, read_comic_summary,
+        update_shared_updater, volume_updater,
+    },
 };
 
 fn get_title(chapter: &Chapter) -> String {
@@ -26,184 +50,929 @@ fn get_parent_series(chapters: &[Chapter]) -> String {
         .unwrap_or_default()
 }
 
-async fn process_chapter_info<F>(
-    chapter: Chapter,
-    info: ComicInfo,
+/// Read the current [`ComicInfo`] of every chapter and compute what `updater` would change
+/// without writing anything to disk
+async fn build_preview<F>(
+    title: &str,
+    items: Vec<(Chapter, ComicInfo)>,
+    updater: F,
     status_tx: watch::Sender<String>,
-    i: usize,
-    chapters_len: usize,
-    process_fn: F,
-) -> anyhow::Result<()>
+) -> anyhow::Result<BatchPreview>
 where
-    F: FnOnce(&PathBuf, &ComicInfo) -> anyhow::Result<()> + std::marker::Send + 'static,
+    F: Fn(ComicInfo, &ComicInfo) -> ComicInfo + Clone + Send + 'static,
 {
-    let title = get_title(&chapter);
-    let _ = status_tx.send(format!("Processing {}/{}: {}", i + 1, chapters_len, title));
-
-    tokio::task::spawn_blocking(move || process_fn(&chapter.path, &info)).await??;
-    Ok(())
+    let updater = move |_: &Chapter, old: ComicInfo, new: &ComicInfo| updater(old, new);
+    build_chapter_preview(title, items, updater, status_tx).await
 }
 
-/// Save the inputs to the [`ComicInfo`]
-pub async fn save_chapter_info(
-    chapter: Chapter,
-    info: ComicInfo,
+/// [`build_preview`] with an updater that also gets the chapter being updated
+async fn build_chapter_preview<F>(
+    title: &str,
+    items: Vec<(Chapter, ComicInfo)>,
+    updater: F,
     status_tx: watch::Sender<String>,
-) -> anyhow::Result<()> {
-    let total_start = Instant::now();
-    let path = chapter.path.clone();
-    process_chapter_info(chapter, info, status_tx.clone(), 0, 1, replace_comic_info).await?;
+) -> anyhow::Result<BatchPreview>
+where
+    F: Fn(&Chapter, ComicInfo, &ComicInfo) -> ComicInfo + Clone + Send + 'static,
+{
+    let chapters_len = items.len();
+    // TODO: Make this in config
+    let concurrency_limit = num_cpus::get();
 
-    let total_duration = total_start.elapsed();
+    let chapters = stream::iter(items.into_iter().enumerate())
+        .map(|(i, (chapter, info))| {
+            let status_tx = status_tx.clone();
+            let updater = updater.clone();
+            async move {
+                let _ = status_tx.send(format!(
+                    "Previewing {}/{}: {}",
+                    i + 1,
+                    chapters_len,
+                    get_title(&chapter)
+                ));
+                tokio::task::spawn_blocking(move || {
+                    let old = read_comic_info(&chapter.path)?;
+                    let new = updater(&chapter, old.clone(), &info);
+                    Ok::<_, anyhow::Error>(ChapterPreview::new(chapter, old, new))
+                })
+                .await?
+            }
+        })
+        .buffered(concurrency_limit)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let preview = BatchPreview::new(title, chapters);
     let _ = status_tx.send(format!(
-        "All done~ processed chapter in {total_duration:.2?} 🎉"
+        "Preview ready: {} changes in {}/{chapters_len} chapters",
+        preview.rows.len(),
+        preview.changed_chapters()
     ));
-    info!(
-        "Processed chapter({}) in {total_duration:.2?}",
-        path.file_name().unwrap_or(path.as_os_str()).display()
-    );
-    Ok(())
+
+    Ok(preview)
 }
 
-/// Save the inputs to the [`ComicInfo`]
-async fn update_info(
-    chapter: Chapter,
-    info: ComicInfo,
+/// Preview the inputs saved to the [`ComicInfo`] of every chapter. Values from `series.json`
+/// win over the inputs, data from the series folder name fills empty fields.
+pub async fn preview_series_info(
+    chapters: Vec<Chapter>,
+    mut comic_info: ComicInfo,
+    folder: SeriesFolder,
+    series_json: Option<SeriesJson>,
     status_tx: watch::Sender<String>,
-    i: usize,
-    chapters_len: usize,
-) -> anyhow::Result<()> {
-    process_chapter_info(chapter, info, status_tx, i, chapters_len, modify_comic_info).await
+) -> anyhow::Result<BatchPreview> {
+    if let Some(json) = &series_json {
+        json.apply(&mut comic_info);
+    }
+    let volume_map = series_json
+        .as_ref()
+        .and_then(|json| json.volume_map.clone());
+    // Volumes named after their year like `v2019` only give the year
+    let year = series_json
+        .and_then(|json| json.metadata.year)
+        .or(folder.year)
+        .or_else(|| folder.volume_year.and_then(|year| u16::try_from(year).ok()));
+    if comic_info.series.is_empty() {
+        comic_info.series.clone_from(&folder.title);
+    }
+    if comic_info.publisher.as_deref().is_none_or(str::is_empty) {
+        comic_info.publisher.clone_from(&folder.publisher);
+    }
+    if comic_info.web.as_deref().is_none_or(str::is_empty) {
+        comic_info.web = folder.web();
+    }
+
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, comic_info.clone()))
+        .collect();
+
+    // Year and volume differ per chapter, so they only fill chapters without one
+    let updater = move |chapter: &Chapter, old: ComicInfo, new: &ComicInfo| {
+        let mut info = update_shared_updater(old, new);
+        info.year = info.year.or(year);
+        info.volume = info
+            .volume
+            .or_else(|| volume_map.as_ref()?.volume_of_chapter(chapter));
+        info
+    };
+
+    build_chapter_preview("Save series info", items, updater, status_tx).await
 }
 
-/// Save the inputs to the [`ComicInfo`]
-async fn update_derived(
-    chapter: Chapter,
-    info: ComicInfo,
+/// Preview copying the chosen fields of the inputs to the [`ComicInfo`] of every chapter
+pub async fn preview_field_update(
+    chapters: Vec<Chapter>,
+    comic_info: ComicInfo,
+    fields: Vec<ComicInfoField>,
+    title: String,
+    status_tx: watch::Sender<String>,
+) -> anyhow::Result<BatchPreview> {
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, comic_info.clone()))
+        .collect();
+
+    let updater = move |mut info: ComicInfo, new: &ComicInfo| {
+        info.update_fields(new, &fields);
+        info
+    };
+
+    build_preview(&title, items, updater, status_tx).await
+}
+
+/// Preview transforming a field of every chapter
+pub async fn preview_transform(
+    chapters: Vec<Chapter>,
+    transform: Transform,
     status_tx: watch::Sender<String>,
-    i: usize,
-    chapters_len: usize,
-) -> anyhow::Result<()> {
-    process_chapter_info(chapter, info, status_tx, i, chapters_len, derive_comic_info).await
+) -> anyhow::Result<BatchPreview> {
+    let title = transform.label();
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, ComicInfo::default()))
+        .collect();
+
+    let updater =
+        move |chapter: &Chapter, info: ComicInfo, _: &ComicInfo| transform.apply(chapter, info);
+
+    build_chapter_preview(&title, items, updater, status_tx).await
 }
 
-/// Save the inputs to the [`ComicInfo`]
-async fn update_volume(
-    chapter: Chapter,
-    info: ComicInfo,
+/// Preview writing the volumes the series volume map gave. Chapters the map doesn't cover are
+/// left alone, and a volume in the filename still wins over the map.
+pub async fn preview_volume_map(
+    chapters: Vec<Chapter>,
+    map: VolumeMap,
     status_tx: watch::Sender<String>,
-    i: usize,
-    chapters_len: usize,
-) -> anyhow::Result<()> {
-    process_chapter_info(chapter, info, status_tx, i, chapters_len, volume_comic_info).await
+) -> anyhow::Result<BatchPreview> {
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, ComicInfo::default()))
+        .collect();
+
+    let updater = move |chapter: &Chapter, mut info: ComicInfo, _: &ComicInfo| {
+        if map.volume_of_chapter(chapter).is_some() {
+            info.volume = chapter.volume;
+        }
+        info
+    };
+
+    build_chapter_preview("Save volume map", items, updater, status_tx).await
 }
 
-/// Save the inputs to the [`ComicInfo`]
-pub async fn save_series_info(
+/// Preview derived info
+pub async fn preview_chapter_numbering(
+    chapters: Vec<Chapter>,
+    specials: SpecialsConfig,
+    warn_mixed_languages: bool,
+    status_tx: watch::Sender<String>,
+) -> anyhow::Result<BatchPreview> {
+    let mut title = String::from("Save chapter numberings");
+    let mut languages: Vec<&str> = chapters
+        .iter()
+        .filter_map(|c| c.language.as_deref())
+        .collect();
+    languages.sort_unstable();
+    languages.dedup();
+    if warn_mixed_languages && languages.len() > 1 {
+        let languages = languages.join(", ");
+        warn!(
+            "Chapters of {} disagree on language: {languages}",
+            get_parent_series(&chapters)
+        );
+        title = format!("{title} (mixed languages: {languages})");
+    }
+
+    let fractional = match specials.numbering {
+        SpecialNumbering::Format => HashMap::new(),
+        SpecialNumbering::Fractional => fractional_numbers(&chapters, specials.fraction_step),
+    };
+
+    // Series made only of volume archives count their volumes
+    let volume_count = chapters
+        .iter()
+        .filter(|c| c.kind == ChapterKind::Regular)
+        .all(Chapter::is_volume)
+        .then(|| chapters.iter().filter_map(|c| c.volume).max())
+        .flatten();
+
+    let items = chapters
+        .into_iter()
+        .map(|chapter| {
+            #[allow(clippy::cast_precision_loss)]
+            let number = if chapter.is_volume() {
+                chapter.volume.map(|v| v as f32)
+            } else {
+                chapter
+                    .chapter
+                    .or_else(|| fractional.get(&chapter.path).copied())
+            };
+
+            let mut info = ComicInfo {
+                series: chapter.series.clone().unwrap_or_default(),
+                volume: chapter.volume,
+                number,
+                count: volume_count.filter(|_| chapter.is_volume()),
+                format: chapter.kind.format().map(String::from),
+                year: chapter.year,
+                language_iso: chapter.language.clone(),
+                translator: Some(chapter.translators.join(",")).filter(|t| !t.is_empty()),
+                ..Default::default()
+            };
+            match (&chapter.title, chapter.chapter, chapter.chapter_end) {
+                (Some(title), _, _) => info.title.clone_from(title),
+                (None, Some(start), Some(end)) => info.title = format!("Chapters {start}-{end}"),
+                _ => {}
+            }
+            (chapter, info)
+        })
+        .collect();
+
+    build_preview(&title, items, derive_updater, status_tx).await
+}
+
+/// `count` numbers strictly between `low` and `high`, at most `step` apart and skipping the
+/// `taken` ones. The step shrinks so they all fit, going down from `high` when `descending`.
+fn fill_gap(
+    low: f32,
+    high: f32,
+    count: usize,
+    step: f32,
+    taken: &[f32],
+    descending: bool,
+) -> Vec<f32> {
+    let is_taken = |number: f32, numbers: &[f32]| numbers.iter().any(|n| (n - number).abs() < 1e-4);
+    let inside = taken.iter().filter(|n| **n > low && **n < high).count();
+    #[allow(clippy::cast_precision_loss)]
+    let step = step.min((high - low) / (count + inside + 1) as f32);
+    let precision = if step >= 0.01 { 100.0 } else { 10000.0 };
+
+    let mut numbers: Vec<f32> = Vec::new();
+    let mut k = 1.0;
+    while numbers.len() < count {
+        let offset = step * k;
+        let number = if descending {
+            high - offset
+        } else {
+            low + offset
+        };
+        let number = (number * precision).round() / precision;
+        k += 1.0;
+
+        if number <= low || number >= high {
+            break;
+        }
+        if !is_taken(number, taken) && !is_taken(number, &numbers) {
+            numbers.push(number);
+        }
+    }
+
+    if descending {
+        numbers.reverse();
+    }
+    numbers
+}
+
+/// Numbers for unnumbered specials where the chapters list sorts them: prologues right before
+/// the first chapter of their volume, the other specials right after its last one. Chapters
+/// without a volume count as the last volume. Every special fits before the next whole number
+/// and numbers other chapters already have are skipped. Numbers don't go below 0, so prologues
+/// before chapter 0 get none and keep only their Format.
+fn fractional_numbers(chapters: &[Chapter], step: f32) -> HashMap<PathBuf, f32> {
+    let numbered: Vec<&Chapter> = chapters
+        .iter()
+        .filter(|c| c.chapter.is_some() && !c.is_volume())
+        .collect();
+    let mut taken: Vec<f32> = numbered
+        .iter()
+        .flat_map(|c| [c.chapter, c.chapter_end])
+        .flatten()
+        .collect();
+
+    // Specials placed in the same gap, by whether they are prologues and their volume
+    let mut groups: BTreeMap<(bool, u32), Vec<&Chapter>> = BTreeMap::new();
+    let mut unnumbered: Vec<&Chapter> = chapters
+        .iter()
+        .filter(|c| c.chapter.is_none() && c.kind != ChapterKind::Regular)
+        .collect();
+    unnumbered.sort();
+    for chapter in unnumbered {
+        let prologue = chapter.kind == ChapterKind::Prologue;
+        let volume = match chapter.volume {
+            // Prologues without a volume open the series
+            None if prologue => 0,
+            _ => chapter.sort_volume(),
+        };
+        groups.entry((prologue, volume)).or_default().push(chapter);
+    }
+
+    let mut numbers = HashMap::new();
+    for ((prologue, volume), specials) in groups {
+        let (low, high) = if prologue {
+            let first = numbered
+                .iter()
+                .filter(|c| c.sort_volume() >= volume)
+                .filter_map(|c| c.chapter)
+                .reduce(f32::min)
+                .unwrap_or(1.0);
+            ((first.ceil() - 1.0).max(0.0), first)
+        } else {
+            let last = numbered
+                .iter()
+                .filter(|c| c.sort_volume() <= volume)
+                .filter_map(|c| c.chapter_end.or(c.chapter))
+                .reduce(f32::max)
+                .unwrap_or_default();
+            (last, last.floor() + 1.0)
+        };
+
+        let gap = fill_gap(low, high, specials.len(), step, &taken, prologue);
+        for (chapter, number) in specials.into_iter().zip(gap) {
+            numbers.insert(chapter.path.clone(), number);
+            taken.push(number);
+        }
+    }
+
+    numbers
+}
+
+/// Preview the volume number
+pub async fn preview_volume_numbering(
     chapters: Vec<Chapter>,
     comic_info: ComicInfo,
     status_tx: watch::Sender<String>,
-) -> anyhow::Result<()> {
-    let chapters_len = chapters.len();
+) -> anyhow::Result<BatchPreview> {
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, comic_info.clone()))
+        .collect();
+
+    build_preview("Save volume numbering", items, volume_updater, status_tx).await
+}
+
+/// Runs a queued job once the jobs before it are over. Archives are written concurrently and
+/// each result is sent as an event, a cancel skips the archives not started yet.
+pub async fn run_job(runner: JobRunner, status_tx: watch::Sender<String>) {
+    let JobRunner {
+        id,
+        title,
+        tasks,
+        cancel,
+        queue,
+        events,
+    } = runner;
+    let Ok(_permit) = queue.acquire().await else {
+        return;
+    };
+    let _ = events.send(JobEvent::Started(id));
+
+    let chapters_len = tasks.len();
     // TODO: Make this in config
     let concurrency_limit = num_cpus::get();
     let total_start = Instant::now();
-    let series = get_parent_series(&chapters);
 
-    stream::iter(chapters.into_iter().enumerate())
-        .map(|(i, chapter)| {
+    stream::iter(tasks.into_iter().enumerate())
+        .map(|(i, task)| {
             let status_tx = status_tx.clone();
-            let info = comic_info.clone();
-            update_info(chapter, info, status_tx, i, chapters_len)
+            let events = events.clone();
+            let cancel = cancel.clone();
+            let title = &title;
+            async move {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+
+                let chapter_title = get_title(task.chapter());
+                let _ =
+                    status_tx.send(format!("{title} {}/{chapters_len}: {chapter_title}", i + 1));
+                let path = task.chapter().path.clone();
+                let result = tokio::task::spawn_blocking(move || task.run())
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|result| result);
+
+                let event = match result {
+                    Ok(()) => JobEvent::Done(id),
+                    Err(e) => {
+                        error!("{title} failed ({}): {e}", path.display());
+                        JobEvent::Failed(id, i, e.to_string())
+                    }
+                };
+                let _ = events.send(event);
+            }
         })
         .buffer_unordered(concurrency_limit)
-        .collect::<Vec<_>>()
-        .await
-        .into_iter()
-        .collect::<Result<Vec<_>, _>>()?;
+        .collect::<Vec<()>>()
+        .await;
 
     let total_duration = total_start.elapsed();
+    info!("{title} (job {id}) over {chapters_len} chapters in {total_duration:.2?}");
+    let _ = events.send(JobEvent::Ended(id, total_duration));
+}
+
+/// List legacy encoded entry names and what they would be renamed to
+pub async fn preview_entry_names(
+    chapters: Vec<Chapter>,
+    encoding: String,
+    status_tx: watch::Sender<String>,
+) -> anyhow::Result<RenamePreview> {
+    let chapters_len = chapters.len();
+    let _ = status_tx.send(format!("Checking entry names in {chapters_len} chapters"));
+
+    let rows = tokio::task::spawn_blocking(move || {
+        let mut rows = Vec::new();
+        for chapter in chapters {
+            match legacy_entry_names(&chapter.path, &encoding) {
+                Ok(names) => rows.extend(names.into_iter().map(|name| RenameRow {
+                    chapter: chapter.clone(),
+                    old: name.current,
+                    new: name.decoded,
+                    conflict: name.conflict,
+                    ambiguous: name.ambiguous,
+                    apply: false,
+                })),
+                Err(e) => error!(
+                    "Failed to read entry names of ({}): {e}",
+                    chapter.path.display()
+                ),
+            }
+        }
+        rows
+    })
+    .await?;
 
+    let preview = RenamePreview::new("Repair entry names", RenameKind::EntryNames, rows);
     let _ = status_tx.send(format!(
-        "All done~ processed {chapters_len} chapters in {total_duration:.2?} 🎉"
+        "Found {} legacy encoded entries in {}/{chapters_len} chapters, {} conflicts, {} ambiguous",
+        preview.rows.len(),
+        preview.chapters().len(),
+        preview.conflicts(),
+        preview.ambiguous()
     ));
-    info!("Processed {chapters_len} chapters from ({series}) in {total_duration:.2?}");
-
-    Ok(())
+    Ok(preview)
 }
 
-/// Updates derived info
-pub async fn update_chapter_numbering(
+/// Preview renaming archives with a filename template
+pub async fn preview_file_names(
     chapters: Vec<Chapter>,
+    template: String,
     status_tx: watch::Sender<String>,
-) -> anyhow::Result<()> {
+) -> anyhow::Result<RenamePreview> {
+    if let Err(e) = template::validate(&template) {
+        let _ = status_tx.send(format!("Invalid rename template: {e}"));
+        return Err(e);
+    }
+
     let chapters_len = chapters.len();
-    // TODO: Make this in config
-    let concurrency_limit = num_cpus::get();
+    let _ = status_tx.send(format!("Building names for {chapters_len} chapters"));
+
+    let rows = tokio::task::spawn_blocking(move || {
+        let mut rows = Vec::new();
+        for chapter in chapters {
+            let info = read_comic_info(&chapter.path).unwrap_or_else(|e| {
+                warn!("Failed to read ({}): {e}", chapter.path.display());
+                ComicInfo::default()
+            });
+            let new = template::render(&template, &TemplateValues::new(&chapter, &info))?;
+            let old = chapter
+                .path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+
+            if old != new {
+                rows.push(RenameRow {
+                    chapter,
+                    old,
+                    new,
+                    conflict: false,
+                    ambiguous: false,
+                    apply: false,
+                });
+            }
+        }
+
+        mark_conflicts(&mut rows);
+        Ok::<_, anyhow::Error>(rows)
+    })
+    .await??;
+
+    let preview = RenamePreview::new("Rename files", RenameKind::Files, rows);
+    let _ = status_tx.send(format!(
+        "{} of {chapters_len} chapters would be renamed, {} conflicts",
+        preview.rows.len(),
+        preview.conflicts()
+    ));
+    Ok(preview)
+}
+
+/// Flags renames onto the same name or onto a file that stays in place
+fn mark_conflicts(rows: &mut [RenameRow]) {
+    let target = |row: &RenameRow| row.chapter.path.with_file_name(&row.new);
+    // Compared in lowercase to stay safe on case insensitive filesystems
+    let key = |path: &Path| path.to_string_lossy().to_lowercase();
+
+    let moved: HashSet<String> = rows.iter().map(|r| key(&r.chapter.path)).collect();
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for row in rows.iter() {
+        *targets.entry(key(&target(row))).or_default() += 1;
+    }
+
+    for row in rows.iter_mut() {
+        let target = target(row);
+        let target_key = key(&target);
+        let occupied = target.exists() && !moved.contains(&target_key);
+
+        row.conflict = row.new == ".cbz" || targets[&target_key] > 1 || occupied;
+    }
+}
+
+/// Rename archives, all or nothing per series
+pub async fn rename_files(
+    renames: Vec<(PathBuf, PathBuf)>,
+    status_tx: watch::Sender<String>,
+) -> Vec<(PathBuf, PathBuf)> {
     let total_start = Instant::now();
-    let series = get_parent_series(&chapters);
 
-    stream::iter(chapters.into_iter().enumerate())
-        .map(|(i, chapter)| {
-            let status_tx = status_tx.clone();
-            let mut info = ComicInfo {
-                volume: chapter.volume,
-                number: chapter.chapter,
-                translator: Some(chapter.translators.join(",")),
-                ..Default::default()
-            };
-            if let Some(title) = &chapter.title {
-                info.title.clone_from(title);
+    let mut by_series: HashMap<PathBuf, Vec<(PathBuf, PathBuf)>> = HashMap::new();
+    for (from, to) in renames {
+        let series = from.parent().map(Path::to_path_buf).unwrap_or_default();
+        by_series.entry(series).or_default().push((from, to));
+    }
+
+    let mut moved = Vec::new();
+    for (series, renames) in by_series {
+        let _ = status_tx.send(format!(
+            "Renaming {} files in ({})",
+            renames.len(),
+            series.display()
+        ));
+        let result =
+            tokio::task::spawn_blocking(move || rename_atomically(&renames).map(|()| renames))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|result| result);
+
+        match result {
+            Ok(renames) => moved.extend(renames),
+            Err(e) => {
+                error!("Failed to rename files in ({}): {e}", series.display());
+                let _ = status_tx.send(format!("Failed to rename files: {e}"));
+            }
+        }
+    }
+
+    let moved_len = moved.len();
+    let total_duration = total_start.elapsed();
+    let _ = status_tx.send(format!(
+        "All done~ renamed {moved_len} files in {total_duration:.2?} 🎉"
+    ));
+    info!("Renamed {moved_len} files in {total_duration:.2?}");
+
+    moved
+}
+
+/// Moves every file to a temporary name first so renames can swap names, rolling everything
+/// back when any step fails
+fn rename_atomically(renames: &[(PathBuf, PathBuf)]) -> anyhow::Result<()> {
+    let temp = |path: &Path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.with_file_name(format!(".{name}.renaming"))
+    };
+
+    let mut done: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let steps = renames
+        .iter()
+        .map(|(from, _)| (from.clone(), temp(from)))
+        .chain(renames.iter().map(|(from, to)| (temp(from), to.clone())));
+
+    for (from, to) in steps {
+        let result = if to.exists() {
+            Err(anyhow::anyhow!("{} already exists", to.display()))
+        } else {
+            fs::rename(&from, &to).map_err(anyhow::Error::from)
+        };
+
+        if let Err(e) = result {
+            for (from, to) in done.iter().rev() {
+                if let Err(e) = fs::rename(to, from) {
+                    error!("Failed to roll back ({}): {e}", to.display());
+                }
             }
+            return Err(e.context(format!("Failed to rename ({})", from.display())));
+        }
+        done.push((from, to));
+    }
+
+    Ok(())
+}
+
+/// Analyze the numbering of every series, with numbers from `ComicInfo.xml` taking precedence
+/// over the parsed ones
+pub async fn build_reports(
+    series: Vec<Series>,
+    status_tx: watch::Sender<String>,
+) -> anyhow::Result<Vec<SeriesReport>> {
+    let series_len = series.len();
+    let total_start = Instant::now();
 
-            update_derived(chapter, info, status_tx, i, chapters_len)
+    let mut reports = stream::iter(series.into_iter().enumerate())
+        .map(|(i, series)| {
+            let status_tx = status_tx.clone();
+            async move {
+                let _ = status_tx.send(format!(
+                    "Analyzing {}/{series_len}: {}",
+                    i + 1,
+                    series.title()
+                ));
+                tokio::task::spawn_blocking(move || {
+                    let chapters: Vec<Chapter> = series
+                        .chapters
+                        .items_state
+                        .iter()
+                        .map(|chapter| {
+                            let mut chapter = chapter.clone();
+                            if let Ok(info) = read_comic_info(&chapter.path) {
+                                chapter.chapter = info.number.or(chapter.chapter);
+                                chapter.volume = info.volume.or(chapter.volume);
+                            }
+                            chapter
+                        })
+                        .collect();
+                    analyze(series.title(), &chapters)
+                })
+                .await
+            }
         })
-        .buffer_unordered(concurrency_limit)
+        .buffer_unordered(num_cpus::get())
         .collect::<Vec<_>>()
         .await
         .into_iter()
         .collect::<Result<Vec<_>, _>>()?;
+    reports.sort_by_key(|r| r.series.to_lowercase());
 
     let total_duration = total_start.elapsed();
-
+    let issues: usize = reports.iter().map(|r| r.issues.len()).sum();
     let _ = status_tx.send(format!(
-        "All done~ processed {chapters_len} chapters in {total_duration:.2?} 🎉"
+        "Found {issues} issues in {series_len} series in {total_duration:.2?}"
     ));
+    info!("Analyzed {series_len} series in {total_duration:.2?}, found {issues} issues");
 
-    info!("Processed {chapters_len} chapters from ({series}) in {total_duration:.2?}");
-
-    Ok(())
+    Ok(reports)
 }
 
-pub async fn update_volume_numbering(
-    chapters: Vec<Chapter>,
-    comic_info: ComicInfo,
+/// Gathers the numbers of the stats dashboard. Archives that changed since they were indexed
+/// are read again and indexed.
+pub async fn build_stats(
+    series: Vec<Series>,
+    encoding: String,
+    index: Arc<Mutex<LibraryIndex>>,
     status_tx: watch::Sender<String>,
-) -> anyhow::Result<()> {
-    let chapters_len = chapters.len();
-    // TODO: Make this in config
-    let concurrency_limit = num_cpus::get();
+) -> anyhow::Result<LibraryStats> {
+    let series_len = series.len();
     let total_start = Instant::now();
-    let series = get_parent_series(&chapters);
 
-    stream::iter(chapters.into_iter().enumerate())
-        .map(|(i, chapter)| {
+    let series = stream::iter(series.into_iter().enumerate())
+        .map(|(i, series)| {
             let status_tx = status_tx.clone();
-            let info = comic_info.clone();
-            update_volume(chapter, info, status_tx, i, chapters_len)
+            let encoding = encoding.clone();
+            let index = index.clone();
+            async move {
+                let _ = status_tx.send(format!(
+                    "Counting {}/{series_len}: {}",
+                    i + 1,
+                    series.title()
+                ));
+                tokio::task::spawn_blocking(move || {
+                    let chapters = series
+                        .chapters
+                        .items_state
+                        .iter()
+                        .map(|chapter| chapter_stats(chapter, &encoding, &index))
+                        .collect();
+                    (series.title().to_string(), chapters)
+                })
+                .await
+            }
         })
-        .buffer_unordered(concurrency_limit)
+        .buffer_unordered(num_cpus::get())
         .collect::<Vec<_>>()
         .await
         .into_iter()
         .collect::<Result<Vec<_>, _>>()?;
 
-    let total_duration = total_start.elapsed();
+    let stats = tokio::task::spawn_blocking(move || LibraryStats::compute(&series)).await?;
 
+    let total_duration = total_start.elapsed();
     let _ = status_tx.send(format!(
-        "All done~ processed {chapters_len} chapters in {total_duration:.2?} 🎉"
+        "Counted {} chapters in {series_len} series in {total_duration:.2?}",
+        stats.chapters
     ));
-    info!("Processed {chapters_len} chapters from ({series}) in {total_duration:.2?}");
+    info!("Counted library stats in {total_duration:.2?}");
 
-    Ok(())
+    Ok(stats)
+}
+
+/// `ComicInfo` and page count of a chapter, from the index when the archive didn't change
+fn indexed_summary(
+    chapter: &Chapter,
+    encoding: &str,
+    index: &Mutex<LibraryIndex>,
+) -> (ComicInfo, u32) {
+    let cached = index
+        .lock()
+        .ok()
+        .and_then(|index| index.summary(&chapter.path));
+
+    cached.unwrap_or_else(|| {
+        let key = fs::metadata(&chapter.path).map(|metadata| FileKey::of(&metadata));
+        match read_comic_summary(&chapter.path, encoding) {
+            Ok((info, pages)) => {
+                if let Ok(key) = key
+                    && let Ok(mut index) = index.lock()
+                {
+                    index.store_comic(&chapter.path, key, &info, pages, None);
+                }
+                (info, pages)
+            }
+            Err(e) => {
+                warn!("Failed to read archive ({}): {e}", chapter.path.display());
+                (ComicInfo::default(), 0)
+            }
+        }
+    })
+}
+
+/// Size, pages and `ComicInfo` of a chapter
+fn chapter_stats(chapter: &Chapter, encoding: &str, index: &Mutex<LibraryIndex>) -> ChapterStats {
+    let size = fs::metadata(&chapter.path)
+        .map(|m| m.len())
+        .unwrap_or_default();
+    let (info, pages) = indexed_summary(chapter, encoding, index);
+
+    ChapterStats {
+        chapter: chapter.clone(),
+        info,
+        size,
+        pages,
+    }
+}
+
+/// Reads the archives of a series' chapters and checks them against the lint rules
+pub async fn read_series_details(
+    series: Series,
+    rules: Vec<LintRule>,
+    encoding: String,
+    index: Arc<Mutex<LibraryIndex>>,
+) -> anyhow::Result<HashMap<PathBuf, ChapterDetails>> {
+    tokio::task::spawn_blocking(move || {
+        let context = LintContext::of(&series);
+        series
+            .chapters
+            .items_state
+            .iter()
+            .map(|chapter| {
+                let size = fs::metadata(&chapter.path)
+                    .map(|m| m.len())
+                    .unwrap_or_default();
+                let (info, pages) = indexed_summary(chapter, &encoding, &index);
+                let issues = lint::lint(&rules, &info, chapter, &context);
+                let details = ChapterDetails {
+                    info,
+                    size,
+                    pages,
+                    issues,
+                };
+                (chapter.path.clone(), details)
+            })
+            .collect()
+    })
+    .await
+    .map_err(Into::into)
+}
+
+/// Preview fixing the chapters with the lint rules that have a fix
+pub async fn preview_lint_fixes(
+    chapters: Vec<Chapter>,
+    rules: Vec<LintRule>,
+    context: LintContext,
+    status_tx: watch::Sender<String>,
+) -> anyhow::Result<BatchPreview> {
+    let items = chapters
+        .into_iter()
+        .map(|chapter| (chapter, ComicInfo::default()))
+        .collect();
+    let updater = move |chapter: &Chapter, old: ComicInfo, _: &ComicInfo| {
+        lint::fix(&rules, old, chapter, &context)
+    };
+
+    build_chapter_preview("Fix lint issues", items, updater, status_tx).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(path: &str, kind: ChapterKind, volume: Option<u32>, number: Option<f32>) -> Chapter {
+        Chapter {
+            path: PathBuf::from(path),
+            kind,
+            volume,
+            chapter: number,
+            ..Default::default()
+        }
+    }
+
+    fn number_of(numbers: &HashMap<PathBuf, f32>, path: &str) -> f32 {
+        numbers[&PathBuf::from(path)]
+    }
+
+    #[test]
+    fn test_fractional_numbers_fit() {
+        let mut chapters = vec![chapter("12", ChapterKind::Regular, None, Some(12.0))];
+        for i in 0..12 {
+            chapters.push(chapter(
+                &format!("omake {i:02}"),
+                ChapterKind::Omake,
+                None,
+                None,
+            ));
+        }
+        chapters.push(chapter("13", ChapterKind::Regular, None, Some(13.0)));
+
+        let numbers = fractional_numbers(&chapters, 0.1);
+        let mut sorted: Vec<f32> = numbers.values().copied().collect();
+        sorted.sort_by(f32::total_cmp);
+        sorted.dedup();
+        assert_eq!(sorted.len(), 12);
+        assert!(sorted.iter().all(|n| *n > 13.0 && *n < 14.0));
+        // Sorted specials keep their order
+        assert!(number_of(&numbers, "omake 00") < number_of(&numbers, "omake 11"));
+    }
+
+    #[test]
+    fn test_fractional_numbers_per_volume() {
+        let chapters = vec![
+            chapter("v1 prologue", ChapterKind::Prologue, Some(1), None),
+            chapter("v1 ch0", ChapterKind::Regular, Some(1), Some(0.0)),
+            chapter("v1 ch1", ChapterKind::Regular, Some(1), Some(1.0)),
+            chapter("v1 omake", ChapterKind::Omake, Some(1), None),
+            chapter("v2 prologue", ChapterKind::Prologue, Some(2), None),
+            chapter("v2 ch2", ChapterKind::Regular, Some(2), Some(2.0)),
+            chapter("v2 ch2.5", ChapterKind::Regular, Some(2), Some(2.5)),
+            chapter("v2 ch3", ChapterKind::Regular, Some(2), Some(3.0)),
+            chapter("extra a", ChapterKind::Extra, None, None),
+            chapter("extra b", ChapterKind::Extra, None, None),
+        ];
+
+        let numbers = fractional_numbers(&chapters, 0.5);
+        // No number fits before chapter 0
+        assert!(!numbers.contains_key(&PathBuf::from("v1 prologue")));
+        assert!((number_of(&numbers, "v1 omake") - 1.5).abs() < 1e-4);
+        // Before chapter 2, distinct from the prologue of volume 1
+        assert!((number_of(&numbers, "v2 prologue") - 1.5).abs() > 1e-4);
+        assert!(number_of(&numbers, "v2 prologue") > 1.0);
+        assert!(number_of(&numbers, "v2 prologue") < 2.0);
+        // 3.5 isn't taken, but the step shrinks to fit both
+        assert!(number_of(&numbers, "extra a") > 3.0);
+        assert!(number_of(&numbers, "extra a") < number_of(&numbers, "extra b"));
+        assert!(number_of(&numbers, "extra b") < 4.0);
+    }
+
+    #[test]
+    fn test_fractional_numbers_above_zero() {
+        let chapters = vec![
+            chapter("prologue", ChapterKind::Prologue, None, None),
+            chapter("1", ChapterKind::Regular, None, Some(1.0)),
+        ];
+        let numbers = fractional_numbers(&chapters, 0.1);
+        assert!((number_of(&numbers, "prologue") - 0.9).abs() < 1e-4);
+
+        let chapters = vec![
+            chapter("prologue", ChapterKind::Prologue, None, None),
+            chapter("0.5", ChapterKind::Regular, None, Some(0.5)),
+        ];
+        let numbers = fractional_numbers(&chapters, 0.5);
+        assert!(number_of(&numbers, "prologue") > 0.0);
+        assert!(number_of(&numbers, "prologue") < 0.5);
+    }
+
+    #[test]
+    fn test_fractional_numbers_skip_taken() {
+        let chapters = vec![
+            chapter("1", ChapterKind::Regular, None, Some(1.0)),
+            chapter("1.1", ChapterKind::Regular, None, Some(1.1)),
+            chapter("1.2", ChapterKind::Bonus, None, Some(1.2)),
+            chapter("omake a", ChapterKind::Omake, None, None),
+            chapter("omake b", ChapterKind::Omake, None, None),
+        ];
+
+        let numbers = fractional_numbers(&chapters, 0.1);
+        assert!((number_of(&numbers, "omake a") - 1.3).abs() < 1e-4);
+        assert!((number_of(&numbers, "omake b") - 1.4).abs() < 1e-4);
+    }
 }
@@ -234,6 +234,10 @@ pub struct ComicInfo {
     #[serde(rename = "LanguageISO", skip_serializing_if = "Option::is_none")]
     pub language_iso: Option<String>,
 
+    /// A book format, e.g. Special, Omake, One-Shot, Prologue or Epilogue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+
     /// Whether the book is a manga. This also defines the reading direction as right-to-left when set to `YesAndRightToLeft`.
     #[serde(default)]
     pub manga: ComicInfoManga,
@@ -247,7 +251,206 @@ pub struct ComicInfo {
     pub count: Option<u32>,
 }
 
+/// A single editable field of [`ComicInfo`], named like in `ComicInfo.xml` in the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ComicInfoField {
+    Title,
+    Series,
+    Number,
+    Volume,
+    Summary,
+    Year,
+    Month,
+    Day,
+    Writer,
+    Penciller,
+    Translator,
+    Publisher,
+    Genre,
+    Tags,
+    Web,
+    PageCount,
+    #[serde(rename = "LanguageISO")]
+    LanguageIso,
+    Format,
+    Manga,
+    AgeRating,
+    Count,
+}
+
+impl ComicInfoField {
+    /// All fields in the order they appear in `ComicInfo.xml`
+    pub const ALL: [Self; 21] = [
+        Self::Title,
+        Self::Series,
+        Self::Number,
+        Self::Volume,
+        Self::Summary,
+        Self::Year,
+        Self::Month,
+        Self::Day,
+        Self::Writer,
+        Self::Penciller,
+        Self::Translator,
+        Self::Publisher,
+        Self::Genre,
+        Self::Tags,
+        Self::Web,
+        Self::PageCount,
+        Self::LanguageIso,
+        Self::Format,
+        Self::Manga,
+        Self::AgeRating,
+        Self::Count,
+    ];
+
+    /// Fields that are the same across all chapters in the series
+    pub const SHARED: [Self; 12] = [
+        Self::Series,
+        Self::Summary,
+        Self::Writer,
+        Self::Penciller,
+        Self::Publisher,
+        Self::Genre,
+        Self::Tags,
+        Self::Web,
+        Self::LanguageIso,
+        Self::Manga,
+        Self::AgeRating,
+        Self::Count,
+    ];
+
+    /// Name of the field as used in `ComicInfo.xml`
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Series => "Series",
+            Self::Number => "Number",
+            Self::Volume => "Volume",
+            Self::Summary => "Summary",
+            Self::Year => "Year",
+            Self::Month => "Month",
+            Self::Day => "Day",
+            Self::Writer => "Writer",
+            Self::Penciller => "Penciller",
+            Self::Translator => "Translator",
+            Self::Publisher => "Publisher",
+            Self::Genre => "Genre",
+            Self::Tags => "Tags",
+            Self::Web => "Web",
+            Self::PageCount => "PageCount",
+            Self::LanguageIso => "LanguageISO",
+            Self::Format => "Format",
+            Self::Manga => "Manga",
+            Self::AgeRating => "AgeRating",
+            Self::Count => "Count",
+        }
+    }
+
+    /// Get the value of the field as a string, `None` if it's not set
+    pub fn get(self, info: &ComicInfo) -> Option<String> {
+        let value = match self {
+            Self::Title => Some(info.title.clone()),
+            Self::Series => Some(info.series.clone()),
+            Self::Number => info.number.map(|v| v.to_string()),
+            Self::Volume => info.volume.map(|v| v.to_string()),
+            Self::Summary => info.summary.clone(),
+            Self::Year => info.year.map(|v| v.to_string()),
+            Self::Month => info.month.map(|v| v.to_string()),
+            Self::Day => info.day.map(|v| v.to_string()),
+            Self::Writer => info.writer.clone(),
+            Self::Penciller => info.penciller.clone(),
+            Self::Translator => info.translator.clone(),
+            Self::Publisher => info.publisher.clone(),
+            Self::Genre => info.genre.clone(),
+            Self::Tags => info.tags.clone(),
+            Self::Web => info.web.clone(),
+            Self::PageCount => info.page_count.map(|v| v.to_string()),
+            Self::LanguageIso => info.language_iso.clone(),
+            Self::Format => info.format.clone(),
+            Self::Manga => match info.manga {
+                ComicInfoManga::Unknown => None,
+                manga => Some(manga.to_string()),
+            },
+            Self::AgeRating => match info.age_rating {
+                ComicInfoAgeRating::Unknown => None,
+                rating => Some(rating.to_string()),
+            },
+            Self::Count => info.count.map(|v| v.to_string()),
+        };
+
+        value.filter(|v| !v.is_empty())
+    }
+
+    /// Copy the value of the field from `from` into `to`
+    pub fn copy(self, from: &ComicInfo, to: &mut ComicInfo) {
+        match self {
+            Self::Title => to.title.clone_from(&from.title),
+            Self::Series => to.series.clone_from(&from.series),
+            Self::Number => to.number = from.number,
+            Self::Volume => to.volume = from.volume,
+            Self::Summary => to.summary.clone_from(&from.summary),
+            Self::Year => to.year = from.year,
+            Self::Month => to.month = from.month,
+            Self::Day => to.day = from.day,
+            Self::Writer => to.writer.clone_from(&from.writer),
+            Self::Penciller => to.penciller.clone_from(&from.penciller),
+            Self::Translator => to.translator.clone_from(&from.translator),
+            Self::Publisher => to.publisher.clone_from(&from.publisher),
+            Self::Genre => to.genre.clone_from(&from.genre),
+            Self::Tags => to.tags.clone_from(&from.tags),
+            Self::Web => to.web.clone_from(&from.web),
+            Self::PageCount => to.page_count = from.page_count,
+            Self::LanguageIso => to.language_iso.clone_from(&from.language_iso),
+            Self::Format => to.format.clone_from(&from.format),
+            Self::Manga => to.manga = from.manga,
+            Self::AgeRating => to.age_rating = from.age_rating,
+            Self::Count => to.count = from.count,
+        }
+    }
+
+    /// Set the field from a string, `None` or an empty string clears it
+    pub fn set(self, info: &mut ComicInfo, value: Option<&str>) -> anyhow::Result<()> {
+        let value = value.map(str::trim).filter(|v| !v.is_empty());
+        let text = value.map(String::from);
+
+        match self {
+            Self::Title => info.title = text.unwrap_or_default(),
+            Self::Series => info.series = text.unwrap_or_default(),
+            Self::Number => info.number = value.map(str::parse).transpose()?,
+            Self::Volume => info.volume = value.map(str::parse).transpose()?,
+            Self::Summary => info.summary = text,
+            Self::Year => info.year = value.map(str::parse).transpose()?,
+            Self::Month => info.month = value.map(str::parse).transpose()?,
+            Self::Day => info.day = value.map(str::parse).transpose()?,
+            Self::Writer => info.writer = text,
+            Self::Penciller => info.penciller = text,
+            Self::Translator => info.translator = text,
+            Self::Publisher => info.publisher = text,
+            Self::Genre => info.genre = text,
+            Self::Tags => info.tags = text,
+            Self::Web => info.web = text,
+            Self::PageCount => info.page_count = value.map(str::parse).transpose()?,
+            Self::LanguageIso => info.language_iso = text,
+            Self::Format => info.format = text,
+            Self::Manga => info.manga = text.map(ComicInfoManga::from).unwrap_or_default(),
+            Self::AgeRating => {
+                info.age_rating = value.map(ComicInfoAgeRating::from).unwrap_or_default();
+            }
+            Self::Count => info.count = value.map(str::parse).transpose()?,
+        }
+        Ok(())
+    }
+}
+
+impl Display for ComicInfoField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 impl ComicInfo {
+    #[allow(dead_code)]
     pub fn new(title: String) -> Self {
         Self {
             title: title.clone(),
@@ -258,18 +461,14 @@ impl ComicInfo {
 
     /// Updates fields that are the same across all chapters in the series
     pub fn update_shared_fields(&mut self, comic_info: &Self) {
-        self.series.clone_from(&comic_info.series);
-        self.summary.clone_from(&comic_info.summary);
-        self.writer.clone_from(&comic_info.writer);
-        self.penciller.clone_from(&comic_info.penciller);
-        self.publisher.clone_from(&comic_info.publisher);
-        self.genre.clone_from(&comic_info.genre);
-        self.tags.clone_from(&comic_info.tags);
-        self.web.clone_from(&comic_info.web);
-        self.language_iso.clone_from(&comic_info.language_iso);
-        self.manga = comic_info.manga;
-        self.age_rating = comic_info.age_rating;
-        self.count = comic_info.count;
+        self.update_fields(comic_info, &ComicInfoField::SHARED);
+    }
+
+    /// Updates the chosen fields
+    pub fn update_fields(&mut self, comic_info: &Self, fields: &[ComicInfoField]) {
+        for field in fields {
+            field.copy(comic_info, self);
+        }
     }
 
     /// Updates fields that can be derived from filename
@@ -278,6 +477,23 @@ impl ComicInfo {
         self.translator.clone_from(&comic_info.translator);
         self.number = comic_info.number;
         self.volume = comic_info.volume;
+
+        // Only known for some chapters or when a filename pattern captured them
+        if comic_info.format.is_some() {
+            self.format.clone_from(&comic_info.format);
+        }
+        if !comic_info.series.is_empty() {
+            self.series.clone_from(&comic_info.series);
+        }
+        if comic_info.year.is_some() {
+            self.year = comic_info.year;
+        }
+        if comic_info.count.is_some() {
+            self.count = comic_info.count;
+        }
+        if comic_info.language_iso.is_some() {
+            self.language_iso.clone_from(&comic_info.language_iso);
+        }
     }
 
     /// Updates the volume number
@@ -285,6 +501,14 @@ impl ComicInfo {
         self.volume = comic_info.volume;
     }
 
+    /// Returns the fields which differ between `self` and `other`
+    pub fn diff(&self, other: &Self) -> Vec<ComicInfoField> {
+        ComicInfoField::ALL
+            .into_iter()
+            .filter(|field| field.get(self) != field.get(other))
+            .collect()
+    }
+
     // pub fn update_from_komga_metadata(&mut self, metadata: &KomgaSeriesMetadata) {
     //     self.title.clone_from(&metadata.title);
     //     self.summary = Some(metadata.summary.clone());
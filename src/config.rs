@@ -1,10 +1,19 @@
 use std::{
+    collections::BTreeMap,
     fs,
     path::{Path, PathBuf},
 };
 
 use anyhow::bail;
 use serde::{Deserialize, Serialize};
+use toml_edit::{DocumentMut, Item, Table as TomlTable};
+
+use crate::{
+    comic_info::ComicInfoField,
+    lint::LintRule,
+    managers::{chapter_view::ChapterColumn, registers::MetadataClip},
+    ui::list::ParseReading,
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct KomfConfig {
@@ -51,14 +60,173 @@ impl Default for KomgaConfig {
     }
 }
 
+/// User defined filename pattern, either a regex or a template with named captures for
+/// `series`, `volume`, `chapter`, `title`, `group`, `language`, `year` and `kind`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilenamePattern {
+    pub name: String,
+    /// Regex like `^(?P<series>.+) - (?P<chapter>\d+)$`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub regex: Option<String>,
+    /// Template like `{series} - Ch.{chapter} [{group}]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+}
+
+/// Regexes for data in series folder names like "Berserk (1989) [Dark Horse] {anilist-30002}",
+/// matches are removed from the shown name
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SeriesFolderConfig {
+    /// Captures `year`
+    #[serde(default = "folder_year")]
+    pub year: String,
+    /// Captures `volume_year`
+    #[serde(default = "folder_volume_year")]
+    pub volume_year: String,
+    /// Captures `publisher`
+    #[serde(default = "folder_publisher")]
+    pub publisher: String,
+    /// Captures `source` and `id`, may match multiple times
+    #[serde(default = "folder_ids")]
+    pub ids: String,
+}
+
+fn folder_year() -> String {
+    String::from(r"\((?P<year>\d{4})\)")
+}
+
+fn folder_volume_year() -> String {
+    String::from(r"\b[vV](?:ol\.? ?)?(?P<volume_year>\d{4})\b")
+}
+
+fn folder_publisher() -> String {
+    String::from(r"\[(?P<publisher>[^\]]+)\]")
+}
+
+fn folder_ids() -> String {
+    String::from(r"\{(?P<source>[A-Za-z]+)-(?P<id>[^}]+)\}")
+}
+
+impl Default for SeriesFolderConfig {
+    fn default() -> Self {
+        Self {
+            year: folder_year(),
+            volume_year: folder_volume_year(),
+            publisher: folder_publisher(),
+            ids: folder_ids(),
+        }
+    }
+}
+
+/// How chapters without a number (specials, extras, omake, ...) are written to `ComicInfo.xml`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpecialNumbering {
+    /// Leave `Number` empty and only set `Format`
+    #[default]
+    Format,
+    /// Number them right after the last regular chapter of their volume, like `12.1`
+    Fractional,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct SpecialsConfig {
+    #[serde(default)]
+    pub numbering: SpecialNumbering,
+    /// Largest step between fractional numbers, smaller when more specials share a gap
+    #[serde(default = "fraction_step")]
+    pub fraction_step: f32,
+}
+
+fn fraction_step() -> f32 {
+    0.1
+}
+
+impl Default for SpecialsConfig {
+    fn default() -> Self {
+        Self {
+            numbering: SpecialNumbering::default(),
+            fraction_step: fraction_step(),
+        }
+    }
+}
+
+/// Folder holding series, scanned recursively for folders that directly contain archives
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LibraryRoot {
+    pub path: String,
+    /// How many folders deep series are looked for, unlimited when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<usize>,
+    /// Folder of oneshots, falls back to `komga.oneshots_dir`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oneshots_dir: Option<String>,
+    /// Language of chapters whose filename doesn't have one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+}
+
+/// Fields copied together from the form to the selected chapters
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldPreset {
+    pub name: String,
+    pub fields: Vec<ComicInfoField>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Only library when `libraries` is empty
+    #[serde(default)]
     pub manga_dir: String,
+    /// Library roots, possibly on different disks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub libraries: Vec<LibraryRoot>,
+    /// Encoding of archive entry names without the UTF-8 flag (`auto`, `cp437`, `shift_jis`, ...)
+    #[serde(default = "archive_encoding")]
+    pub archive_encoding: String,
+    /// Filename patterns tried in order before the built-in parser
+    #[serde(default)]
+    pub filename_patterns: Vec<FilenamePattern>,
+    #[serde(default)]
+    pub specials: SpecialsConfig,
+    #[serde(default)]
+    pub series_folder: SeriesFolderConfig,
+    /// Template used to rename archives, see [`crate::template`]
+    #[serde(default = "rename_template")]
+    pub rename_template: String,
+    /// Warn when saving chapter numberings of a series whose chapters disagree on language
+    #[serde(default = "warn_mixed_languages")]
+    pub warn_mixed_languages: bool,
+    /// House rules for metadata, see [`crate::lint`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub lint_rules: Vec<LintRule>,
+    /// Columns of the chapters list
+    #[serde(default = "chapter_columns")]
+    pub chapter_columns: Vec<ChapterColumn>,
+    /// Named sets of fields for batch updates
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub field_presets: Vec<FieldPreset>,
     pub komga: KomgaConfig,
     #[serde(default)]
     pub komf: KomfConfig,
 }
 
+fn rename_template() -> String {
+    String::from("<Vol.{volume:02} ><Ch.{number:04}>< - {title}>< ({lang})>< [{translator}]>.cbz")
+}
+
+fn chapter_columns() -> Vec<ChapterColumn> {
+    vec![ChapterColumn::Number, ChapterColumn::Title]
+}
+
+fn warn_mixed_languages() -> bool {
+    true
+}
+
+fn archive_encoding() -> String {
+    String::from("auto")
+}
+
 impl Default for Config {
     fn default() -> Self {
         let manga_dir = dirs::document_dir().map_or_else(
@@ -68,6 +236,16 @@ impl Default for Config {
 
         Self {
             manga_dir,
+            libraries: Vec::new(),
+            archive_encoding: archive_encoding(),
+            filename_patterns: Vec::new(),
+            specials: SpecialsConfig::default(),
+            series_folder: SeriesFolderConfig::default(),
+            rename_template: rename_template(),
+            warn_mixed_languages: warn_mixed_languages(),
+            lint_rules: Vec::new(),
+            chapter_columns: chapter_columns(),
+            field_presets: Vec::new(),
             komga: KomgaConfig::default(),
             komf: KomfConfig::default(),
         }
@@ -104,7 +282,202 @@ impl Config {
         }
     }
 
+    /// Writes the config back to `config.toml`, only the keys whose value changed are touched
+    /// so comments and formatting stay
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Config::get_path()?.join("config.toml");
+        let text = fs::read_to_string(&path).unwrap_or_default();
+        fs::write(path, self.edit(&text)?)?;
+        Ok(())
+    }
+
+    /// `text` of a config file with the values of this config
+    fn edit(&self, text: &str) -> anyhow::Result<String> {
+        let mut doc: DocumentMut = text.parse()?;
+        // What the file held with the defaults filled in, like when it was read
+        let old = match toml::from_str::<Config>(text) {
+            Ok(config) => toml::Table::try_from(config)?,
+            Err(_) => toml::Table::new(),
+        };
+        let new = toml::Table::try_from(self)?;
+        let items: DocumentMut = toml::to_string_pretty(self)?.parse()?;
+
+        edit_table(doc.as_table_mut(), items.as_table(), &old, &new);
+        Ok(doc.to_string())
+    }
+
+    /// Library roots with the oneshots dir filled in, `manga_dir` when none are set
+    pub fn libraries(&self) -> Vec<LibraryRoot> {
+        let roots = if self.libraries.is_empty() {
+            vec![LibraryRoot {
+                path: self.manga_dir.clone(),
+                depth: Some(1),
+                oneshots_dir: None,
+                language: None,
+            }]
+        } else {
+            self.libraries.clone()
+        };
+
+        roots
+            .into_iter()
+            .map(|mut root| {
+                root.oneshots_dir
+                    .get_or_insert_with(|| self.komga.oneshots_dir.clone());
+                root
+            })
+            .collect()
+    }
+
     pub fn get_log_path() -> anyhow::Result<PathBuf> {
         Config::get_path().map(|v| v.join("cbz.log"))
     }
+
+    /// Where library stats are exported to
+    pub fn get_stats_path() -> anyhow::Result<PathBuf> {
+        Config::get_path().map(|v| v.join("stats.json"))
+    }
+}
+
+/// Writes the keys of `new` whose value isn't the one in `old` into `doc` from `items`, the
+/// same values formatted by `toml_edit`
+fn edit_table(doc: &mut TomlTable, items: &TomlTable, old: &toml::Table, new: &toml::Table) {
+    for key in old.keys().filter(|key| !new.contains_key(*key)) {
+        doc.remove(key);
+    }
+
+    for (key, value) in new {
+        if old.get(key) == Some(value) {
+            continue;
+        }
+
+        match (doc.get_mut(key), items.get(key), old.get(key), value) {
+            (
+                Some(Item::Table(doc)),
+                Some(Item::Table(items)),
+                Some(toml::Value::Table(old)),
+                toml::Value::Table(new),
+            ) => edit_table(doc, items, old, new),
+            // Comments next to the value stay
+            (Some(Item::Value(current)), Some(Item::Value(item)), _, _) => {
+                let decor = current.decor().clone();
+                *current = item.clone();
+                *current.decor_mut() = decor;
+            }
+            (_, Some(item), _, _) => {
+                doc.insert(key, item.clone());
+            }
+            (_, None, _, _) => {}
+        }
+    }
+}
+
+/// Manual filename readings per archive, kept in `overrides.toml` next to the config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ParseOverrides {
+    #[serde(default)]
+    pub files: BTreeMap<String, ParseReading>,
+}
+
+impl ParseOverrides {
+    pub fn read() -> anyhow::Result<Self> {
+        let path = Config::get_path()?.join("overrides.toml");
+        if path.exists() {
+            Ok(toml::from_str(&fs::read_to_string(&path)?)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Config::get_path()?.join("overrides.toml");
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&ParseReading> {
+        self.files.get(path.to_string_lossy().as_ref())
+    }
+
+    /// Moves the reading of a renamed file, `false` when it had none
+    pub fn rename(&mut self, from: &Path, to: &Path) -> bool {
+        let Some(reading) = self.files.remove(from.to_string_lossy().as_ref()) else {
+            return false;
+        };
+        self.files.insert(to.to_string_lossy().to_string(), reading);
+        true
+    }
+
+    /// Sets or with `None` removes the reading of a file
+    pub fn set(&mut self, path: &Path, reading: Option<ParseReading>) {
+        let key = path.to_string_lossy().to_string();
+        match reading {
+            Some(reading) => self.files.insert(key, reading),
+            None => self.files.remove(&key),
+        };
+    }
+}
+
+/// Named field values pasted like registers, kept in `presets.toml` next to the config
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataPresets {
+    #[serde(default)]
+    pub presets: BTreeMap<String, MetadataClip>,
+}
+
+impl MetadataPresets {
+    pub fn read() -> anyhow::Result<Self> {
+        let path = Config::get_path()?.join("presets.toml");
+        if path.exists() {
+            Ok(toml::from_str(&fs::read_to_string(&path)?)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn save(&self) -> anyhow::Result<()> {
+        let path = Config::get_path()?.join("presets.toml");
+        fs::write(path, toml::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_keeps_comments() {
+        let text = r#"# Where the library lives
+manga_dir = "/manga" # next to the anime
+
+[komga]
+# Generated in the account settings
+api_key = "secret"
+"#;
+        let mut config: Config = toml::from_str(text).unwrap();
+        let edited = config.edit(text).unwrap();
+        // Nothing changed, not even the defaults are written
+        assert_eq!(edited, text);
+
+        config.manga_dir = String::from("/library");
+        config.rename_template = String::from("{title}.cbz");
+        config.filename_patterns.push(FilenamePattern {
+            name: String::from("episode"),
+            regex: None,
+            template: Some(String::from("E{chapter}")),
+        });
+        let edited = config.edit(text).unwrap();
+
+        assert!(edited.contains(r#"manga_dir = "/library" # next to the anime"#));
+        assert!(edited.contains("# Where the library lives"));
+        assert!(edited.contains("# Generated in the account settings"));
+        assert!(edited.contains(r#"rename_template = "{title}.cbz""#));
+        assert!(!edited.contains("chapter_columns"));
+
+        let read: Config = toml::from_str(&edited).unwrap();
+        assert_eq!(read.manga_dir, "/library");
+        assert_eq!(read.komga.api_key, "secret");
+        assert_eq!(read.filename_patterns.len(), 1);
+    }
 }
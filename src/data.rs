@@ -1,93 +1,689 @@
 //! Data management
 
 use std::{
-    fs, io,
+    fs,
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
     path::{Path, PathBuf},
+    sync::{Mutex, PoisonError, mpsc},
 };
 
-use crate::ui::list::{Chapter, ChapterList, Series};
+use regex::{Captures, Regex};
+use walkdir::WalkDir;
 
-pub fn get_series_list<P: AsRef<Path>>(path: P) -> io::Result<Vec<Series>> {
-    let mut folders = Vec::new();
+use crate::{
+    config::{FilenamePattern, LibraryRoot, ParseOverrides, SeriesFolderConfig},
+    index::LibraryIndex,
+    series_json::SeriesJson,
+    ui::list::{Chapter, ChapterKind, ChapterList, ParseReading, Series, SeriesFolder},
+};
 
-    for entry in fs::read_dir(path)? {
-        let entry = entry?;
-        let metadata = entry.metadata()?;
-        if metadata.is_dir() {
-            let mut chapters = crate::data::get_cbz_list(entry.path())?;
-            chapters.sort();
-            folders.push(Series {
-                name: entry.file_name().into_string().unwrap_or_default(),
-                path: entry.path(),
-                chapters: ChapterList::from_iter(chapters),
-            });
+/// What a library scan reports while it runs
+pub enum ScanEvent {
+    /// A folder was looked at, with its series if it directly contains archives
+    Folder(Vec<Series>),
+    /// A path couldn't be read
+    Warning(String),
+    /// Every root was scanned
+    Done,
+}
+
+/// Scans every library root, a series being any folder that directly contains archives.
+/// Series are sent as they're found, unreadable paths are sent as warnings and skipped.
+pub fn scan_library(
+    roots: &[LibraryRoot],
+    parser: &FilenameParser,
+    folder_parser: &SeriesFolderParser,
+    index: &Mutex<LibraryIndex>,
+    events: &mpsc::Sender<ScanEvent>,
+) {
+    for root in roots {
+        for folder in series_folders(root, Path::new(&root.path)) {
+            let event = match folder {
+                Ok(folder) => {
+                    // Locked per folder so the UI can read the index in between
+                    let mut index = index.lock().unwrap_or_else(PoisonError::into_inner);
+                    match load_series(root, &folder, parser, folder_parser, &mut index) {
+                        Ok(series) => ScanEvent::Folder(series),
+                        Err(e) => ScanEvent::Warning(format!("{}: {e}", folder.display())),
+                    }
+                }
+                Err(e) => ScanEvent::Warning(e.to_string()),
+            };
+            if events.send(event).is_err() {
+                return;
+            }
         }
     }
 
-    folders.sort();
+    let _ = events.send(ScanEvent::Done);
+}
+
+/// Series in `folder` and below it, unreadable paths are logged and skipped
+pub fn scan_folder(
+    root: &LibraryRoot,
+    folder: &Path,
+    parser: &FilenameParser,
+    folder_parser: &SeriesFolderParser,
+    index: &mut LibraryIndex,
+) -> Vec<Series> {
+    let mut found = Vec::new();
+    for path in series_folders(root, folder) {
+        match path
+            .map_err(io::Error::from)
+            .and_then(|path| load_series(root, &path, parser, folder_parser, index))
+        {
+            Ok(series) => found.extend(series),
+            Err(e) => warn!("Failed to scan library ({}): {e}", folder.display()),
+        }
+    }
+    found
+}
+
+/// Folders in `folder` and below it, down to the depth of the root it's in.
+/// The folder itself only counts when it isn't the root.
+fn series_folders(
+    root: &LibraryRoot,
+    folder: &Path,
+) -> impl Iterator<Item = walkdir::Result<PathBuf>> {
+    let depth = folder
+        .strip_prefix(&root.path)
+        .map(|relative| relative.components().count())
+        .ok();
+    let max_depth = depth.and_then(|depth| root.depth.unwrap_or(usize::MAX).checked_sub(depth));
+
+    let walker = max_depth.map(|max_depth| {
+        WalkDir::new(folder)
+            .min_depth(usize::from(depth == Some(0)))
+            .max_depth(max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(|e| !e.file_name().to_string_lossy().starts_with('.'))
+    });
+
+    walker
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| match entry {
+            Ok(entry) if entry.file_type().is_dir() => Some(Ok(entry.into_path())),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+}
+
+/// Series of a folder of a library root, if it directly contains archives. Each archive of the
+/// oneshots folder is its own series.
+pub fn load_series(
+    root: &LibraryRoot,
+    path: &Path,
+    parser: &FilenameParser,
+    folder_parser: &SeriesFolderParser,
+    index: &mut LibraryIndex,
+) -> io::Result<Vec<Series>> {
+    let language = root.language.as_deref().and_then(normalize_language);
+    let mut chapters = get_cbz_list(path, parser, language.as_deref(), index)?;
+    chapters.sort();
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+
+    if root.oneshots_dir.as_deref() == Some(name.as_str()) {
+        return Ok(chapters
+            .into_iter()
+            .map(|chapter| {
+                let name = chapter
+                    .path
+                    .file_stem()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                Series {
+                    folder: folder_parser.parse(&name),
+                    name,
+                    path: chapter.path.clone(),
+                    chapters: ChapterList::from_iter([chapter]),
+                    language: language.clone(),
+                    oneshot: true,
+                    series_json: None,
+                }
+            })
+            .collect());
+    }
+
+    if chapters.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let series_json = SeriesJson::read(path)
+        .inspect_err(|e| warn!("Failed to read series.json ({}): {e}", path.display()))
+        .ok()
+        .flatten();
+    if let Some(map) = series_json
+        .as_ref()
+        .and_then(|json| json.volume_map.as_ref())
+    {
+        map.assign(&mut chapters);
+        chapters.sort();
+    }
 
-    Ok(folders)
+    Ok(vec![Series {
+        folder: folder_parser.parse(&name),
+        name,
+        path: path.to_path_buf(),
+        chapters: ChapterList::from_iter(chapters),
+        language,
+        oneshot: false,
+        series_json,
+    }])
 }
 
-pub fn get_cbz_list<P: AsRef<Path>>(path: P) -> io::Result<Vec<Chapter>> {
+/// Archives directly in a folder, `language` is used for filenames without one.
+/// Readings of unchanged files come from the index.
+pub fn get_cbz_list<P: AsRef<Path>>(
+    path: P,
+    parser: &FilenameParser,
+    language: Option<&str>,
+    index: &mut LibraryIndex,
+) -> io::Result<Vec<Chapter>> {
     let mut cbz_files = Vec::new();
 
     for entry in fs::read_dir(path)? {
         let entry = entry?;
         let path = entry.path();
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
 
-        if path.is_file()
+        if metadata.is_file()
             && let Some(ext) = path.extension()
             && ext.eq_ignore_ascii_case("cbz")
-            && let Some(name) = path.file_name()
         {
-            cbz_files.push(parse_filename(
-                path.clone(),
-                name.to_string_lossy().as_ref(),
-            ));
+            let mut chapter = index.chapter(&path, &metadata, parser);
+            parser.apply_override(&mut chapter);
+            if chapter.language.is_none() {
+                chapter.language = language.map(String::from);
+            }
+            cbz_files.push(chapter);
         }
     }
 
     Ok(cbz_files)
 }
 
-fn is_chapter_prefix(token: &str) -> bool {
-    let low = token.to_lowercase();
+/// Names of the captures understood in filename patterns
+const PATTERN_CAPTURES: [&str; 8] = [
+    "series", "volume", "chapter", "title", "group", "language", "year", "kind",
+];
 
-    // pure chapter prefixes
-    let prefixes = [
-        "ch", "ch.", "chap", "chap.", "chapter", "chapter.", "ep", "ep.", "episode", "episode.",
-    ];
+/// User defined filename patterns tried in priority order, falling back to [`parse_filename`].
+/// Manual overrides win over both.
+#[derive(Debug, Clone, Default)]
+pub struct FilenameParser {
+    patterns: Vec<(String, Regex)>,
+    pub overrides: ParseOverrides,
+    fingerprint: u64,
+}
 
-    // If token is exactly a prefix OR prefix + number
-    for p in &prefixes {
-        if low == *p {
-            return true;
+impl FilenameParser {
+    /// Compiles the patterns, invalid ones are logged and skipped
+    pub fn new(patterns: &[FilenamePattern], overrides: ParseOverrides) -> Self {
+        let patterns: Vec<(String, Regex)> = patterns
+            .iter()
+            .filter_map(|pattern| match compile_pattern(pattern) {
+                Ok(regex) => Some((pattern.name.clone(), regex)),
+                Err(e) => {
+                    error!("Invalid filename pattern ({}): {e}", pattern.name);
+                    None
+                }
+            })
+            .collect();
+
+        let mut hasher = DefaultHasher::new();
+        env!("CARGO_PKG_VERSION").hash(&mut hasher);
+        for (name, regex) in &patterns {
+            (name, regex.as_str()).hash(&mut hasher);
         }
-        if low.starts_with(p) {
-            // ensure next char is digit or dot
-            if let Some(rest) = low.strip_prefix(*p)
-                && rest.starts_with(|c: char| c.is_ascii_digit())
-            {
-                return true;
+
+        Self {
+            patterns,
+            overrides,
+            fingerprint: hasher.finish(),
+        }
+    }
+
+    /// Replaces the reading with the manual override of the file, if there is one
+    pub fn apply_override(&self, chapter: &mut Chapter) {
+        if let Some(reading) = self.overrides.get(&chapter.path) {
+            reading.apply(chapter);
+            chapter.confidence = 100;
+            chapter.overridden = true;
+        }
+    }
+
+    /// Identifies the patterns, cached readings made with other patterns are stale
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Parses the filename without overrides, returning the name of the pattern that matched if any
+    pub fn parse_with_name(&self, path: PathBuf, filename: &str) -> (Chapter, Option<&str>) {
+        let name = filename.trim_end_matches(".cbz");
+
+        for (pattern_name, regex) in &self.patterns {
+            if let Some(caps) = regex.captures(name) {
+                return (
+                    chapter_from_captures(path, &caps),
+                    Some(pattern_name.as_str()),
+                );
+            }
+        }
+
+        (parse_filename(path, filename), None)
+    }
+}
+
+/// Parses series folder names with the regexes from [`SeriesFolderConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct SeriesFolderParser {
+    year: Option<Regex>,
+    volume_year: Option<Regex>,
+    publisher: Option<Regex>,
+    ids: Option<Regex>,
+}
+
+impl SeriesFolderParser {
+    /// Compiles the patterns, invalid ones are logged and skipped
+    pub fn new(config: &SeriesFolderConfig) -> Self {
+        let compile = |name: &str, pattern: &str| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                error!("Invalid series folder pattern ({name}): {e}");
+                None
+            }
+        };
+
+        Self {
+            year: compile("year", &config.year),
+            volume_year: compile("volume_year", &config.volume_year),
+            publisher: compile("publisher", &config.publisher),
+            ids: compile("ids", &config.ids),
+        }
+    }
+
+    pub fn parse(&self, name: &str) -> SeriesFolder {
+        let mut rest = name.to_string();
+        let mut folder = SeriesFolder::default();
+
+        if let Some(regex) = &self.ids {
+            folder.ids = regex
+                .captures_iter(&rest)
+                .filter_map(|caps| {
+                    Some((
+                        caps.name("source")?.as_str().to_lowercase(),
+                        caps.name("id")?.as_str().trim().to_string(),
+                    ))
+                })
+                .collect();
+            rest = regex.replace_all(&rest, " ").into_owned();
+        }
+
+        folder.volume_year = take_capture(self.volume_year.as_ref(), &mut rest, "volume_year")
+            .and_then(|v| v.parse().ok());
+        folder.year =
+            take_capture(self.year.as_ref(), &mut rest, "year").and_then(|v| v.parse().ok());
+        folder.publisher = take_capture(self.publisher.as_ref(), &mut rest, "publisher");
+
+        folder.title = rest
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+            .trim_matches(|c: char| c == '-' || c == '_' || c.is_whitespace())
+            .to_string();
+        folder
+    }
+}
+
+/// Takes the first match out of `text`, returning its named capture
+fn take_capture(regex: Option<&Regex>, text: &mut String, name: &str) -> Option<String> {
+    let caps = regex?.captures(text)?;
+    let value = caps
+        .name(name)
+        .map(|m| m.as_str().trim().to_string())
+        .filter(|v| !v.is_empty());
+    let range = caps.get(0)?.range();
+
+    text.replace_range(range, " ");
+    value
+}
+
+/// Compiles either the regex or the template of a pattern
+pub fn compile_pattern(pattern: &FilenamePattern) -> anyhow::Result<Regex> {
+    let source = match (&pattern.regex, &pattern.template) {
+        (Some(regex), _) => regex.clone(),
+        (None, Some(template)) => template_to_regex(template)?,
+        (None, None) => anyhow::bail!("Pattern needs either a regex or a template"),
+    };
+
+    let regex = Regex::new(&source)?;
+    if let Some(name) = regex
+        .capture_names()
+        .flatten()
+        .find(|name| !PATTERN_CAPTURES.contains(name))
+    {
+        anyhow::bail!("Unknown capture `{name}`");
+    }
+
+    Ok(regex)
+}
+
+/// Turns a template like `{series} - Ch.{chapter} [{group}]` into an anchored regex
+pub fn template_to_regex(template: &str) -> anyhow::Result<String> {
+    let mut regex = String::from("^");
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        regex.push_str(&regex::escape(&rest[..start]));
+
+        let Some(end) = rest[start..].find('}') else {
+            anyhow::bail!("Unclosed `{{` in template");
+        };
+        let name = &rest[start + 1..start + end];
+        let capture = match name {
+            "volume" | "year" => r"\d+",
+            "chapter" => r"\d+(?:\.\d+)?(?:-\d+(?:\.\d+)?)?",
+            "language" => r"[A-Za-z]{2,3}(?:-[A-Za-z]{2,4})?",
+            "series" | "title" | "group" => ".+?",
+            "kind" => r"\p{L}+(?: \p{L}+)?",
+            _ => anyhow::bail!("Unknown placeholder `{{{name}}}`"),
+        };
+        regex.push_str("(?P<");
+        regex.push_str(name);
+        regex.push('>');
+        regex.push_str(capture);
+        regex.push(')');
+
+        rest = &rest[start + end + 1..];
+    }
+
+    regex.push_str(&regex::escape(rest));
+    regex.push('$');
+    Ok(regex)
+}
+
+fn chapter_from_captures(path: PathBuf, caps: &Captures) -> Chapter {
+    let get = |name: &str| {
+        caps.name(name)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+
+    let (chapter, chapter_end) = get("chapter")
+        .and_then(|v| parse_chapter_number(&v))
+        .unzip();
+
+    Chapter {
+        path,
+        volume: get("volume").and_then(|v| v.parse().ok()),
+        chapter,
+        chapter_end: chapter_end.flatten(),
+        title: get("title"),
+        translators: get("group")
+            .map(|group| {
+                group
+                    .split(',')
+                    .map(|w| w.trim().to_string())
+                    .filter(|w| !w.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        series: get("series"),
+        year: get("year").and_then(|v| v.parse().ok()),
+        language: get("language").and_then(|tag| normalize_language(&tag)),
+        kind: get("kind")
+            .or_else(|| get("title"))
+            .and_then(|marker| chapter_kind_of(&marker))
+            .unwrap_or_default(),
+        ..Default::default()
+    }
+}
+
+/// ISO 639-1 language codes
+const ISO_639_1: [&str; 183] = [
+    "aa", "ab", "ae", "af", "ak", "am", "an", "ar", "as", "av", "ay", "az", "ba", "be", "bg", "bi",
+    "bm", "bn", "bo", "br", "bs", "ca", "ce", "ch", "co", "cr", "cs", "cu", "cv", "cy", "da", "de",
+    "dv", "dz", "ee", "el", "en", "eo", "es", "et", "eu", "fa", "ff", "fi", "fj", "fo", "fr", "fy",
+    "ga", "gd", "gl", "gn", "gu", "gv", "ha", "he", "hi", "ho", "hr", "ht", "hu", "hy", "hz", "ia",
+    "id", "ie", "ig", "ii", "ik", "io", "is", "it", "iu", "ja", "jv", "ka", "kg", "ki", "kj", "kk",
+    "kl", "km", "kn", "ko", "kr", "ks", "ku", "kv", "kw", "ky", "la", "lb", "lg", "li", "ln", "lo",
+    "lt", "lu", "lv", "mg", "mh", "mi", "mk", "ml", "mn", "mr", "ms", "mt", "my", "na", "nb", "nd",
+    "ne", "ng", "nl", "nn", "no", "nr", "nv", "ny", "oc", "oj", "om", "or", "os", "pa", "pi", "pl",
+    "ps", "pt", "qu", "rm", "rn", "ro", "ru", "rw", "sa", "sc", "sd", "se", "sg", "si", "sk", "sl",
+    "sm", "sn", "so", "sq", "sr", "ss", "st", "su", "sv", "sw", "ta", "te", "tg", "th", "ti", "tk",
+    "tl", "tn", "to", "tr", "ts", "tt", "tw", "ty", "ug", "uk", "ur", "uz", "ve", "vi", "vo", "wa",
+    "wo", "xh", "yi", "yo", "za", "zh", "zu",
+];
+
+/// Normalizes a language tag to BCP-47 with an ISO 639-1 language, `None` for other tags:
+/// "jp" → "ja", "kr" → "ko", "pt-br" → "pt-BR"
+pub fn normalize_language(tag: &str) -> Option<String> {
+    let tag = tag.trim().replace('_', "-").to_lowercase();
+    let mut parts = tag.split('-');
+
+    let language = parts.next()?;
+    if !(2..=3).contains(&language.len()) || !language.chars().all(|c| c.is_ascii_lowercase()) {
+        return None;
+    }
+
+    // Country codes and ISO 639-2 codes commonly used instead of the language
+    let language = match language {
+        "jp" | "jpn" => "ja",
+        "kr" | "kor" => "ko",
+        "cn" | "chi" | "zho" => "zh",
+        "eng" => "en",
+        "spa" => "es",
+        "por" | "br" => "pt",
+        "fre" | "fra" => "fr",
+        "ger" | "deu" => "de",
+        "ita" => "it",
+        "pol" => "pl",
+        "rus" => "ru",
+        "ua" | "ukr" => "uk",
+        "vn" | "vie" => "vi",
+        "ind" => "id",
+        "tha" => "th",
+        "ara" => "ar",
+        "tur" => "tr",
+        "gr" | "gre" | "ell" => "el",
+        "cz" | "cze" | "ces" => "cs",
+        "se" | "swe" => "sv",
+        "dk" | "dan" => "da",
+        other if ISO_639_1.contains(&other) => other,
+        _ => return None,
+    };
+
+    let mut normalized = language.to_string();
+    for subtag in parts {
+        if subtag.is_empty() || !subtag.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return None;
+        }
+
+        normalized.push('-');
+        match subtag.len() {
+            // Region: "BR", "419"
+            2 | 3 => normalized.push_str(&subtag.to_uppercase()),
+            // Script: "Hant"
+            4 => {
+                let (first, rest) = subtag.split_at(1);
+                normalized.push_str(&first.to_uppercase());
+                normalized.push_str(rest);
             }
+            _ => return None,
         }
     }
 
+    // "br" alone means Brazilian Portuguese
+    if tag == "br" {
+        normalized.push_str("-BR");
+    }
+
+    Some(normalized)
+}
+
+/// Parses a chapter number like "12" or "12.5", or a range like "001-005".
+/// A part that is not ascending, like "12-2", keeps only the chapter number.
+fn parse_chapter_number(s: &str) -> Option<(f32, Option<f32>)> {
+    if let Some((start, end)) = s.split_once('-') {
+        let start: f32 = start.parse().ok()?;
+        let end: f32 = end.parse().ok()?;
+        return Some((start, (end > start).then_some(end)));
+    }
+
+    s.parse().ok().map(|n| (n, None))
+}
+
+fn is_chapter_prefix(token: &str) -> bool {
+    let low = token.to_lowercase();
+
+    // pure chapter prefixes: English, Polish, Spanish, Portuguese, Italian, German,
+    // Russian, Ukrainian and Vietnamese
+    let prefixes = [
+        "ch",
+        "ch.",
+        "chap",
+        "chap.",
+        "chapter",
+        "chapter.",
+        "ep",
+        "ep.",
+        "episode",
+        "episode.",
+        "rozdział",
+        "rozdzial",
+        "rozdz.",
+        "capítulo",
+        "capitulo",
+        "cap",
+        "cap.",
+        "capitolo",
+        "kapitel",
+        "kap.",
+        "глава",
+        "гл.",
+        "розділ",
+        "chương",
+        "chuong",
+    ];
+
     // '#' chapter tokens: "#12"
     if low.starts_with('#') && low[1..].chars().all(|c| c.is_ascii_digit()) {
         return true;
     }
 
-    false
+    matches_prefix(&low, &prefixes)
+}
+
+fn is_volume_prefix(token: &str) -> bool {
+    let low = token.to_lowercase();
+
+    // English, Spanish, Polish, Russian, Ukrainian, Portuguese, French, German and Vietnamese
+    let prefixes = [
+        "vol", "vol.", "volume", "volumen", "tom", "tom.", "том", "т.", "tomo", "tome", "bd.",
+        "tập",
+    ];
+
+    matches_prefix(&low, &prefixes)
+}
+
+/// Whether `low` is exactly one of `prefixes` or a prefix directly followed by a number
+fn matches_prefix(low: &str, prefixes: &[&str]) -> bool {
+    prefixes.iter().any(|p| {
+        low == *p
+            || low
+                .strip_prefix(p)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+    })
+}
+
+/// A CJK number-plus-suffix marker
+#[derive(Debug, PartialEq)]
+enum CjkMarker {
+    Volume(u32),
+    Chapter(f32),
+}
+
+impl CjkMarker {
+    /// Whether the filename doesn't have the number this marker sets yet
+    fn is_free(&self, volume: Option<u32>, chapter: Option<f32>) -> bool {
+        match self {
+            Self::Volume(_) => volume.is_none(),
+            Self::Chapter(_) => chapter.is_none(),
+        }
+    }
+}
+
+/// Parses CJK markers like "第12話", "第3巻", "제5화" or "12话", returning the rest of the token
+fn parse_cjk_marker(token: &str) -> Option<(CjkMarker, &str)> {
+    let body = token
+        .strip_prefix('第')
+        .or_else(|| token.strip_prefix('제'))
+        .unwrap_or(token);
+
+    // Fullwidth digits are common in Japanese names
+    let number_len = body
+        .find(|c: char| !(c.is_ascii_digit() || c == '.' || ('０'..='９').contains(&c)))
+        .unwrap_or(body.len());
+    if number_len == 0 {
+        return None;
+    }
+
+    let number: String = body[..number_len]
+        .chars()
+        .map(|c| match c {
+            '０'..='９' => char::from_digit(u32::from(c) - u32::from('０'), 10).unwrap_or(c),
+            _ => c,
+        })
+        .collect();
+
+    let mut rest = body[number_len..].chars();
+    let marker = match rest.next()? {
+        '話' | '话' | '回' | '章' | '화' => CjkMarker::Chapter(number.parse().ok()?),
+        '巻' | '卷' | '冊' | '권' => CjkMarker::Volume(number.parse().ok()?),
+        _ => return None,
+    };
+
+    Some((marker, rest.as_str()))
+}
+
+/// Kind marked by a whole capture like "Omake" or "Side Story"
+fn chapter_kind_of(marker: &str) -> Option<ChapterKind> {
+    match marker.split_whitespace().collect::<Vec<_>>()[..] {
+        [token] => ChapterKind::from_token(token),
+        [first, second] => ChapterKind::from_tokens(first, second),
+        _ => None,
+    }
+}
+
+/// Special marker starting at `tokens[i]` and the number of tokens it spans
+fn chapter_kind_at(tokens: &[String], i: usize) -> Option<(ChapterKind, usize)> {
+    let tok = tokens.get(i)?;
+    if let Some(next) = tokens.get(i + 1)
+        && let Some(kind) = ChapterKind::from_tokens(tok, next)
+    {
+        return Some((kind, 2));
+    }
+
+    ChapterKind::from_token(tok).map(|kind| (kind, 1))
 }
 
 fn tokenize_preserving_brackets(s: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut buf = String::new();
     let mut bracket_depth: i32 = 0;
+    let chars: Vec<char> = s.chars().collect();
 
-    for c in s.chars() {
+    for (i, &c) in chars.iter().enumerate() {
         match c {
             '[' => {
                 bracket_depth += 1;
@@ -98,6 +694,13 @@ fn tokenize_preserving_brackets(s: &str) -> Vec<String> {
                 buf.push(c);
             }
 
+            // Keep chapter ranges like "001-005" together
+            '-' if buf.ends_with(|c: char| c.is_ascii_digit())
+                && chars.get(i + 1).is_some_and(char::is_ascii_digit) =>
+            {
+                buf.push(c);
+            }
+
             // Split ONLY when NOT inside brackets
             '-' | ':' | ' ' | '\t' | '\n' if bracket_depth == 0 => {
                 if !buf.is_empty() {
@@ -117,6 +720,7 @@ fn tokenize_preserving_brackets(s: &str) -> Vec<String> {
     tokens
 }
 
+#[allow(clippy::too_many_lines)]
 pub fn parse_filename(path: PathBuf, filename: &str) -> Chapter {
     let name = filename.trim_end_matches(".cbz");
 
@@ -136,12 +740,14 @@ pub fn parse_filename(path: PathBuf, filename: &str) -> Chapter {
         core = core[..start].trim().to_string();
     }
 
-    // Extract language tag (en) (jp) etc.
+    // Extract language tag (en) (jp) (pt-br) etc.
+    let mut language = None;
     if let (Some(start), Some(end)) = (core.rfind('('), core.rfind(')'))
         && end > start
     {
         let inside = &core[start + 1..end];
-        if (2..=3).contains(&inside.len()) {
+        if let Some(tag) = normalize_language(inside) {
+            language = Some(tag);
             core = core[..start].trim().to_string();
         }
     }
@@ -151,18 +757,50 @@ pub fn parse_filename(path: PathBuf, filename: &str) -> Chapter {
 
     let mut volume: Option<u32> = None;
     let mut chapter: Option<f32> = None;
+    let mut chapter_end: Option<f32> = None;
+    let mut kind = ChapterKind::Regular;
     let mut leftovers: Vec<String> = Vec::new();
 
+    // Whether the chapter is a number without a prefix, and other numbers that could be it
+    let mut bare = false;
+    let mut others: Vec<f32> = Vec::new();
+
     let mut i = 0;
     while i < tokens.len() {
         let tok = &tokens[i];
-        let low = tok.to_lowercase();
 
-        // Volume detection
-        if (low.starts_with("vol") || low.starts_with("volume"))
-            && !tok.contains('[')
-            && !tok.contains(']')
+        // CJK markers: "第12話", "第3巻", "제5화"
+        if let Some((first, mut rest)) =
+            parse_cjk_marker(tok).filter(|(marker, _)| marker.is_free(volume, chapter))
         {
+            // Markers glued together: "第3巻第12話"
+            let mut marker = Some(first);
+            while let Some(found) = marker.take() {
+                match found {
+                    CjkMarker::Volume(v) => volume = Some(v),
+                    CjkMarker::Chapter(n) => chapter = Some(n),
+                }
+                if let Some((next, after)) =
+                    parse_cjk_marker(rest).filter(|(marker, _)| marker.is_free(volume, chapter))
+                {
+                    marker = Some(next);
+                    rest = after;
+                }
+            }
+
+            // A kind glued after them: "第12話番外編"
+            if kind == ChapterKind::Regular
+                && let Some((marker, after)) = ChapterKind::strip_cjk(rest)
+            {
+                kind = marker;
+                rest = after;
+            }
+            if !rest.is_empty() {
+                leftovers.push(rest.to_string());
+            }
+        }
+        // Volume detection
+        else if is_volume_prefix(tok) && !tok.contains('[') && !tok.contains(']') {
             // Extract digits from token itself
             let digits_only: String = tok.chars().filter(char::is_ascii_digit).collect();
 
@@ -173,7 +811,27 @@ pub fn parse_filename(path: PathBuf, filename: &str) -> Chapter {
             {
                 volume = Some(v);
                 i += 1; // skip the next token
+            } else {
+                // A title word like "Tom" or "Tome" without a number
+                leftovers.push(tok.to_string());
+            }
+        }
+        // Special marker leading the title: "Special", "Omake", "Side Story", ...
+        else if kind == ChapterKind::Regular
+            && chapter.is_none()
+            && leftovers.is_empty()
+            && let Some((marker, len)) = chapter_kind_at(&tokens, i)
+        {
+            kind = marker;
+
+            // Keep numbered markers in the title, "Extra 3" is not chapter 3
+            if tokens
+                .get(i + len)
+                .is_some_and(|next| next.parse::<f32>().is_ok())
+            {
+                leftovers.extend(tokens[i..i + len].iter().cloned());
             }
+            i += len - 1;
         }
         // Chapter detection
         else if chapter.is_none() && is_chapter_prefix(tok) {
@@ -184,44 +842,113 @@ pub fn parse_filename(path: PathBuf, filename: &str) -> Chapter {
             // number might be next token
             if num.is_empty()
                 && let Some(next) = tokens.get(i + 1)
-                && next.parse::<f32>().is_ok()
+                && parse_chapter_number(next).is_some()
             {
                 num = next;
                 i += 1;
             }
 
-            if let Ok(n) = num.parse::<f32>() {
-                chapter = Some(n);
+            if let Some((start, end)) = parse_chapter_number(num) {
+                chapter = Some(start);
+                chapter_end = end;
             } else {
                 leftovers.push(tok.to_string());
             }
-        } else if let Ok(n) = tok.parse::<f32>() {
-            if chapter.is_none() {
-                chapter = Some(n);
+        } else if let Some((start, end)) = parse_chapter_number(tok) {
+            let prefixed = i > 0 && is_chapter_prefix(&tokens[i - 1]);
+
+            // Numbers after a special marker belong to the title
+            if chapter.is_none() && kind == ChapterKind::Regular {
+                chapter = Some(start);
+                chapter_end = end;
+                bare = true;
+            } else if bare || prefixed {
+                others.push(start);
             }
             leftovers.push(tok.to_string());
         }
         // Everything else = title token
         else {
+            // Another chapter marker in the title: "Ch.081.4 - ...: Ch14 - Endurance"
+            if chapter.is_some()
+                && is_chapter_prefix(tok)
+                && let Some((start, _)) = parse_chapter_number(
+                    tok.trim_start_matches(|c: char| c.is_alphabetic() || c == '.' || c == '#'),
+                )
+            {
+                others.push(start);
+            }
             leftovers.push(tok.to_string());
         }
 
         i += 1;
     }
 
+    // A title that is only a marker: "Ch.12.5 - Omake"
+    if kind == ChapterKind::Regular
+        && let Some((marker, len)) = chapter_kind_at(&leftovers, 0)
+        && len == leftovers.len()
+    {
+        kind = marker;
+    }
+
     let title = if leftovers.is_empty() {
         None
     } else {
         Some(leftovers.join(" "))
     };
 
-    Chapter {
+    let mut chapter = Chapter {
         path,
         volume,
         chapter,
+        chapter_end,
         title,
         translators,
-    }
+        language,
+        kind,
+        ..Default::default()
+    };
+    (chapter.confidence, chapter.alternatives) = assess(&chapter, bare, &others);
+    chapter
+}
+
+/// How sure the parser is about a reading, and the other ways to read the filename
+fn assess(chapter: &Chapter, bare: bool, others: &[f32]) -> (u8, Vec<ParseReading>) {
+    let reading = ParseReading::of(chapter);
+    let mut alternatives: Vec<ParseReading> = others
+        .iter()
+        .map(|&number| ParseReading {
+            chapter: Some(number),
+            chapter_end: None,
+            ..reading.clone()
+        })
+        .collect();
+
+    let confidence = match chapter.chapter {
+        Some(number) if bare => {
+            // The number may just be part of the title: "Night 44", "Blade Runner 2049"
+            alternatives.push(ParseReading {
+                chapter: None,
+                chapter_end: None,
+                ..reading.clone()
+            });
+
+            let looks_like_year = number.fract() == 0.0 && (1900.0..=2100.0).contains(&number);
+            if looks_like_year || !others.is_empty() {
+                40
+            } else {
+                60
+            }
+        }
+        Some(_) if !others.is_empty() => 50,
+        Some(_) => 100,
+        None if chapter.kind != ChapterKind::Regular || chapter.is_volume() => 90,
+        None => 30,
+    };
+
+    alternatives.dedup();
+    (confidence, alternatives)
 }
 
 #[cfg(test)]
@@ -292,6 +1019,11 @@ mod tests {
         let c = parse("Night 44.cbz");
         assert_eq!(c.chapter, Some(44.0));
         assert_eq!(c.title, Some("Night 44".into()));
+
+        // The number may as well be part of the title
+        assert!(c.is_uncertain());
+        assert_eq!(c.alternatives.len(), 1);
+        assert_eq!(c.alternatives[0].chapter, None);
     }
 
     // From some edge cases found in the wild
@@ -307,6 +1039,42 @@ mod tests {
         );
         assert_eq!(c.volume, None);
         assert_eq!(c.translators, Vec::<String>::new());
+
+        // The second chapter marker is offered as an alternative
+        assert!(c.is_uncertain());
+        assert_eq!(c.alternatives[0].chapter, Some(14.0));
+    }
+
+    #[test]
+    fn test_confident_parse() {
+        let c = parse("Vol.03 Ch.12 Title.cbz");
+        assert_eq!(c.confidence, 100);
+        assert!(c.alternatives.is_empty());
+    }
+
+    #[test]
+    fn test_parse_override() {
+        let path = PathBuf::from("/manga/Series/Night 44.cbz");
+        let mut overrides = ParseOverrides::default();
+        overrides.set(
+            &path,
+            Some(ParseReading {
+                chapter: Some(3.0),
+                title: Some("Night 44".into()),
+                ..Default::default()
+            }),
+        );
+
+        let parser = FilenameParser::new(&[], overrides);
+        let (mut c, _) = parser.parse_with_name(path.clone(), "Night 44.cbz");
+        parser.apply_override(&mut c);
+        assert_eq!(c.chapter, Some(3.0));
+        assert!(c.overridden);
+        assert!(!c.is_uncertain());
+
+        // Alternatives of the parsed reading stay available
+        let (parsed, _) = parser.parse_with_name(path, "Night 44.cbz");
+        assert_eq!(parsed.chapter, Some(44.0));
     }
 
     #[test]
@@ -352,19 +1120,431 @@ mod tests {
         assert_eq!(c.translators, vec!["ROCK-paper-SCISSORS"]);
     }
 
-    // FIX: Still unresolved idk how to fix this or if I should care about this edge case
     #[test]
-    #[ignore = "unresolved"]
+    fn test_polish_markers() {
+        let c = parse("Tom 3 Rozdział 12 - Powrót.cbz");
+        assert_eq!(c.volume, Some(3));
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.title, Some("Powrót".into()));
+    }
+
+    #[test]
+    fn test_spanish_markers() {
+        let c = parse("Tomo 2 Capítulo 4 - El inicio.cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(4.0));
+        assert_eq!(c.title, Some("El inicio".into()));
+
+        let c = parse("Cap. 7.5.cbz");
+        assert_eq!(c.chapter, Some(7.5));
+        assert_eq!(c.title, None);
+    }
+
+    #[test]
+    fn test_german_markers() {
+        let c = parse("Kapitel 9 - Der Anfang.cbz");
+        assert_eq!(c.chapter, Some(9.0));
+        assert_eq!(c.title, Some("Der Anfang".into()));
+    }
+
+    #[test]
+    fn test_russian_markers() {
+        let c = parse("Том 1 Глава 15 - Начало.cbz");
+        assert_eq!(c.volume, Some(1));
+        assert_eq!(c.chapter, Some(15.0));
+        assert_eq!(c.title, Some("Начало".into()));
+    }
+
+    #[test]
+    fn test_vietnamese_markers() {
+        let c = parse("Tập 2 Chương 8.cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(8.0));
+        assert_eq!(c.title, None);
+    }
+
+    #[test]
+    fn test_japanese_markers() {
+        let c = parse("第3巻 第12話 はじまり.cbz");
+        assert_eq!(c.volume, Some(3));
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.title, Some("はじまり".into()));
+
+        // Fullwidth digits and a title glued to the marker
+        let c = parse("第１２話はじまり.cbz");
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.title, Some("はじまり".into()));
+    }
+
+    #[test]
+    fn test_glued_cjk_markers() {
+        let c = parse("第3巻第12話.cbz");
+        assert_eq!(c.volume, Some(3));
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.title, None);
+
+        let c = parse("제2권제5화시작.cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(5.0));
+        assert_eq!(c.title, Some("시작".into()));
+
+        let c = parse("第12話番外編.cbz");
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.kind, ChapterKind::Extra);
+        assert_eq!(c.title, None);
+
+        let c = parse("第3巻第12話外伝 はじまり.cbz");
+        assert_eq!(c.volume, Some(3));
+        assert_eq!(c.chapter, Some(12.0));
+        assert_eq!(c.kind, ChapterKind::SideStory);
+        assert_eq!(c.title, Some("はじまり".into()));
+
+        // Unglued kind words are found too
+        let c = parse("番外編.cbz");
+        assert_eq!(c.chapter, None);
+        assert_eq!(c.kind, ChapterKind::Extra);
+    }
+
+    #[test]
+    fn test_chinese_markers() {
+        let c = parse("第2卷 第30话.cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(30.0));
+    }
+
+    #[test]
+    fn test_korean_markers() {
+        let c = parse("제2권 제5화 시작.cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(5.0));
+        assert_eq!(c.title, Some("시작".into()));
+    }
+
+    #[test]
+    fn test_marker_lookalikes() {
+        // Words that only start like a marker stay in the title
+        let c = parse("Ch.3 Tomorrow Capital.cbz");
+        assert_eq!(c.volume, None);
+        assert_eq!(c.chapter, Some(3.0));
+        assert_eq!(c.title, Some("Tomorrow Capital".into()));
+    }
+
+    #[test]
+    fn test_chapter_range() {
+        let c = parse("Ch. 001-005.cbz");
+        assert_eq!(c.chapter, Some(1.0));
+        assert_eq!(c.chapter_end, Some(5.0));
+        assert_eq!(c.title, None);
+
+        let c = parse("Vol.2 Ch.10-12.5 - Finale [group].cbz");
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, Some(10.0));
+        assert_eq!(c.chapter_end, Some(12.5));
+        assert_eq!(c.title, Some("Finale".into()));
+    }
+
+    #[test]
+    fn test_descending_range() {
+        for name in ["Ch.12-2.cbz", "12-2.cbz"] {
+            let c = parse(name);
+            assert_eq!(c.chapter, Some(12.0), "{name}");
+            assert_eq!(c.chapter_end, None, "{name}");
+        }
+
+        let c = parse("Ch.5-5 - Title.cbz");
+        assert_eq!(c.chapter, Some(5.0));
+        assert_eq!(c.chapter_end, None);
+        assert_eq!(c.title, Some("Title".into()));
+    }
+
+    #[test]
+    fn test_volume_words_in_title() {
+        let c = parse("Ch.5 - Tom Sawyer.cbz");
+        assert_eq!(c.volume, None);
+        assert_eq!(c.title, Some("Tom Sawyer".into()));
+
+        let c = parse("Ch.3 - Volcano.cbz");
+        assert_eq!(c.volume, None);
+        assert_eq!(c.title, Some("Volcano".into()));
+
+        let c = parse("Tome 2 Ch.3.cbz");
+        assert_eq!(c.volume, Some(2));
+    }
+
+    #[test]
+    fn test_volume_only() {
+        let c = parse("Vol. 05.cbz");
+        assert_eq!(c.volume, Some(5));
+        assert_eq!(c.chapter, None);
+        assert!(c.is_volume());
+
+        let c = parse("Vol. 05 Ch. 3.cbz");
+        assert!(!c.is_volume());
+    }
+
+    #[test]
+    fn test_range_and_volume_sorting() {
+        let mut chapters: Vec<Chapter> = [
+            "Ch. 010-012.cbz",
+            "Vol. 02.cbz",
+            "Ch. 009.cbz",
+            "Vol. 01.cbz",
+            "Ch. 010.cbz",
+        ]
+        .into_iter()
+        .map(parse)
+        .collect();
+        chapters.sort();
+
+        let order: Vec<String> = chapters
+            .iter()
+            .map(|c| c.path.display().to_string())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "Vol. 01.cbz",
+                "Vol. 02.cbz",
+                "Ch. 009.cbz",
+                "Ch. 010.cbz",
+                "Ch. 010-012.cbz",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_language_normalization() {
+        assert_eq!(normalize_language("jp"), Some("ja".into()));
+        assert_eq!(normalize_language("KR"), Some("ko".into()));
+        assert_eq!(normalize_language("pt-br"), Some("pt-BR".into()));
+        assert_eq!(normalize_language("zh_hant"), Some("zh-Hant".into()));
+        assert_eq!(normalize_language("es-419"), Some("es-419".into()));
+        assert_eq!(normalize_language("v2"), None);
+        for tag in ["hq", "end", "raw", "fin"] {
+            assert_eq!(normalize_language(tag), None, "{tag}");
+        }
+
+        let c = parse("Ch.3 Title (pt-br).cbz");
+        assert_eq!(c.language, Some("pt-BR".into()));
+        assert_eq!(c.title, Some("Title".into()));
+
+        let c = parse("Ch.3 Title (jp).cbz");
+        assert_eq!(c.language, Some("ja".into()));
+
+        let c = parse("Ch.3 Title (HQ).cbz");
+        assert_eq!(c.language, None);
+        assert_eq!(c.title, Some("Title (HQ)".into()));
+
+        let c = parse("Ch.40 Last Stand (end).cbz");
+        assert_eq!(c.language, None);
+        assert_eq!(c.title, Some("Last Stand (end)".into()));
+    }
+
+    #[test]
+    fn test_series_folder() {
+        let parser = SeriesFolderParser::new(&SeriesFolderConfig::default());
+
+        let folder = parser.parse("Berserk (1989) [Dark Horse] {anilist-30002}");
+        assert_eq!(folder.title, "Berserk");
+        assert_eq!(folder.year, Some(1989));
+        assert_eq!(folder.publisher, Some("Dark Horse".into()));
+        assert_eq!(folder.ids, vec![("anilist".into(), "30002".into())]);
+        assert_eq!(folder.web(), Some("https://anilist.co/manga/30002".into()));
+
+        let folder = parser.parse("Batman v2016 {cv-91273} {mal-123}");
+        assert_eq!(folder.title, "Batman");
+        assert_eq!(folder.volume_year, Some(2016));
+        assert_eq!(folder.year, None);
+        assert_eq!(folder.ids.len(), 2);
+
+        let folder = parser.parse("Plain Name");
+        assert_eq!(folder.title, "Plain Name");
+        assert_eq!(folder.publisher, None);
+    }
+
+    #[test]
     fn test_no_chapter_number() {
         let c = parse("Special           : Special Chapter.cbz");
 
         // No chapter number present
         assert_eq!(c.chapter, None);
         assert_eq!(c.volume, None);
+        assert_eq!(c.kind, ChapterKind::Special);
         assert_eq!(c.title, Some("Special Chapter".into()));
         assert_eq!(c.translators, Vec::<String>::new());
     }
 
+    #[test]
+    fn test_numbered_extra() {
+        let c = parse("Extra 3 - After Story (en).cbz");
+
+        assert_eq!(c.chapter, None);
+        assert_eq!(c.kind, ChapterKind::Extra);
+        assert_eq!(c.title, Some("Extra 3 After Story".into()));
+    }
+
+    #[test]
+    fn test_side_story_ignores_bare_numbers() {
+        let c = parse("Vol.02 Side Story - Night 44.cbz");
+
+        assert_eq!(c.volume, Some(2));
+        assert_eq!(c.chapter, None);
+        assert_eq!(c.kind, ChapterKind::SideStory);
+        assert_eq!(c.title, Some("Night 44".into()));
+    }
+
+    #[test]
+    fn test_marker_only_title() {
+        let c = parse("Ch.12.5 - Omake.cbz");
+        assert_eq!(c.chapter, Some(12.5));
+        assert_eq!(c.kind, ChapterKind::Omake);
+
+        let c = parse("Oneshot.cbz");
+        assert_eq!(c.kind, ChapterKind::Oneshot);
+        assert_eq!(c.title, None);
+
+        let c = parse("Ch.3 - Bonus Round.cbz");
+        assert_eq!(c.kind, ChapterKind::Regular);
+    }
+
+    #[test]
+    fn test_specials_sorting() {
+        let mut chapters: Vec<Chapter> = [
+            "Epilogue.cbz",
+            "Omake.cbz",
+            "Ch.2.cbz",
+            "Ch.1.5 - Bonus.cbz",
+            "Prologue.cbz",
+            "Ch.1.cbz",
+        ]
+        .into_iter()
+        .map(parse)
+        .collect();
+        chapters.sort();
+
+        let order: Vec<String> = chapters
+            .iter()
+            .map(|c| c.path.display().to_string())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "Prologue.cbz",
+                "Ch.1.cbz",
+                "Ch.1.5 - Bonus.cbz",
+                "Ch.2.cbz",
+                "Omake.cbz",
+                "Epilogue.cbz",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_specials_sort_by_volume() {
+        let mut chapters: Vec<Chapter> = [
+            "Vol.02 Ch.010.cbz",
+            "Vol.01 Omake.cbz",
+            "Vol.02 Prologue.cbz",
+            "Vol.01 Ch.001.cbz",
+            "Vol.02 Omake.cbz",
+            "Vol.01 Ch.002.cbz",
+        ]
+        .into_iter()
+        .map(parse)
+        .collect();
+        chapters.sort();
+
+        let order: Vec<String> = chapters
+            .iter()
+            .map(|c| c.path.display().to_string())
+            .collect();
+        assert_eq!(
+            order,
+            vec![
+                "Vol.01 Ch.001.cbz",
+                "Vol.01 Ch.002.cbz",
+                "Vol.01 Omake.cbz",
+                "Vol.02 Prologue.cbz",
+                "Vol.02 Ch.010.cbz",
+                "Vol.02 Omake.cbz",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_template_pattern() {
+        let parser = FilenameParser::new(
+            &[FilenamePattern {
+                name: String::from("group first"),
+                regex: None,
+                template: Some(String::from("[{group}] {series} - {chapter} ({year})")),
+            }],
+            ParseOverrides::default(),
+        );
+
+        let name = "[Scans A, Scans B] Some Series - 12.5 (2019).cbz";
+        let (c, pattern) = parser.parse_with_name(PathBuf::from(name), name);
+        assert_eq!(pattern, Some("group first"));
+        assert_eq!(c.series, Some("Some Series".into()));
+        assert_eq!(c.chapter, Some(12.5));
+        assert_eq!(c.year, Some(2019));
+        assert_eq!(c.translators, vec!["Scans A", "Scans B"]);
+
+        // Falls back to the built-in heuristic
+        let name = "Vol.1 Ch.2 Title.cbz";
+        let (c, pattern) = parser.parse_with_name(PathBuf::from(name), name);
+        assert_eq!(pattern, None);
+        assert_eq!(c.chapter, Some(2.0));
+    }
+
+    #[test]
+    fn test_regex_pattern() {
+        let parser = FilenameParser::new(
+            &[
+                FilenamePattern {
+                    name: String::from("invalid"),
+                    regex: Some(String::from("(?P<unknown>.+)")),
+                    template: None,
+                },
+                FilenamePattern {
+                    name: String::from("episode"),
+                    regex: Some(String::from(
+                        r"^E(?P<chapter>\d+)_(?P<title>.+)_(?P<language>[a-z]{2})$",
+                    )),
+                    template: None,
+                },
+            ],
+            ParseOverrides::default(),
+        );
+
+        let (c, _) = parser.parse_with_name(PathBuf::new(), "E007_The_Title_pl.cbz");
+        assert_eq!(c.chapter, Some(7.0));
+        assert_eq!(c.title, Some("The_Title".into()));
+        assert_eq!(c.language, Some("pl".into()));
+    }
+
+    #[test]
+    fn test_kind_capture() {
+        let parser = FilenameParser::new(
+            &[FilenamePattern {
+                name: String::from("kind"),
+                regex: None,
+                template: Some(String::from("{series} - {kind} {chapter}")),
+            }],
+            ParseOverrides::default(),
+        );
+
+        let name = "Some Series - Side Story 3.cbz";
+        let (c, pattern) = parser.parse_with_name(PathBuf::from(name), name);
+        assert_eq!(pattern, Some("kind"));
+        assert_eq!(c.kind, ChapterKind::SideStory);
+        assert_eq!(c.chapter, Some(3.0));
+
+        let name = "Some Series - 番外編 2.cbz";
+        let (c, _) = parser.parse_with_name(PathBuf::from(name), name);
+        assert_eq!(c.kind, ChapterKind::Extra);
+    }
+
     #[test]
     fn test_chapter_and_ch_in_title() {
         let c = parse("Chapter 29           : Cheep Talk.cbz");
@@ -374,4 +1554,80 @@ mod tests {
         assert_eq!(c.title, Some("Cheep Talk".into()));
         assert_eq!(c.translators, Vec::<String>::new());
     }
+
+    #[test]
+    fn test_nested_series_in_library_roots() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        for file in [
+            "Publisher/Berserk/Ch.001.cbz",
+            "Publisher/Berserk/Ch.002 (es).cbz",
+            "Publisher/Empty/cover.jpg",
+            "Deep/Nested/Series/Ch.001.cbz",
+            "_oneshots/Oneshot (2019) [Kodansha].cbz",
+            "_oneshots/Another Oneshot.cbz",
+        ] {
+            let path = root.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+
+        let parser = FilenameParser::new(&[], ParseOverrides::default());
+        let folder_parser = SeriesFolderParser::new(&SeriesFolderConfig::default());
+        let library = |depth| LibraryRoot {
+            path: root.to_string_lossy().to_string(),
+            depth,
+            oneshots_dir: Some(String::from("_oneshots")),
+            language: Some(String::from("EN")),
+        };
+
+        let scan = |depth| {
+            let index = Mutex::new(LibraryIndex::default());
+            let (events_tx, events_rx) = mpsc::channel();
+            scan_library(
+                &[library(depth)],
+                &parser,
+                &folder_parser,
+                &index,
+                &events_tx,
+            );
+            events_rx
+                .try_iter()
+                .filter_map(|event| match event {
+                    ScanEvent::Folder(series) => Some(series),
+                    ScanEvent::Warning(_) | ScanEvent::Done => None,
+                })
+                .flatten()
+                .collect::<Vec<Series>>()
+        };
+
+        let series = scan(None);
+        let find = |name: &str| series.iter().find(|s| s.name == name).unwrap();
+        assert_eq!(series.len(), 4);
+        assert!(!find("Berserk").oneshot);
+
+        // Each oneshot is its own series, found in Komga by its archive
+        let oneshot = find("Oneshot (2019) [Kodansha]");
+        assert!(oneshot.oneshot);
+        assert_eq!(oneshot.title(), "Oneshot");
+        assert_eq!(oneshot.folder.year, Some(2019));
+        assert_eq!(
+            oneshot.path,
+            root.join("_oneshots/Oneshot (2019) [Kodansha].cbz")
+        );
+        assert_eq!(oneshot.chapters.items.len(), 1);
+        assert!(find("Another Oneshot").oneshot);
+        assert!(series.iter().all(|s| s.name != "_oneshots"));
+        assert_eq!(find("Series").path, root.join("Deep/Nested/Series"));
+
+        let languages: Vec<Option<&str>> = find("Berserk")
+            .chapters
+            .items
+            .iter()
+            .map(|c| c.language.as_deref())
+            .collect();
+        assert_eq!(languages, [Some("en"), Some("es")]);
+
+        assert_eq!(scan(Some(2)).len(), 3);
+    }
 }
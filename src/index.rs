@@ -0,0 +1,327 @@
+//! On-disk index of archives, so unchanged files don't have to be read again
+
+use std::{
+    collections::HashMap,
+    fs::{self, Metadata},
+    hash::{DefaultHasher, Hash, Hasher},
+    io::Cursor,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use anyhow::bail;
+use image::ImageFormat;
+use serde::{Deserialize, Serialize};
+
+use crate::{comic_info::ComicInfo, data::FilenameParser, ui::list::Chapter};
+
+/// Largest side of a cover thumbnail
+const THUMBNAIL_SIZE: u32 = 480;
+
+/// State of the file a cached entry was read from, any change makes it stale
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FileKey {
+    pub size: u64,
+    pub modified_secs: u64,
+    pub modified_nanos: u32,
+}
+
+impl FileKey {
+    pub fn of(metadata: &Metadata) -> Self {
+        let modified = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .unwrap_or_default();
+
+        Self {
+            size: metadata.len(),
+            modified_secs: modified.as_secs(),
+            modified_nanos: modified.subsec_nanos(),
+        }
+    }
+}
+
+/// Cached data of one archive
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub key: FileKey,
+
+    /// Filename reading without overrides, with the fingerprint of the parser that made it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parsed: Option<(u64, Chapter)>,
+
+    /// `ComicInfo.xml` of the archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comic_info: Option<ComicInfo>,
+
+    /// Number of pages in the archive
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pages: Option<u32>,
+
+    /// Cover thumbnail file in the thumbnails directory
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+impl IndexEntry {
+    fn new(key: FileKey) -> Self {
+        Self {
+            key,
+            parsed: None,
+            comic_info: None,
+            pages: None,
+            thumbnail: None,
+        }
+    }
+}
+
+/// Archive data cached in `index.json` in the cache directory, keyed by path, size and mtime
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct LibraryIndex {
+    #[serde(default)]
+    entries: HashMap<String, IndexEntry>,
+
+    /// Changed since it was read
+    #[serde(skip)]
+    dirty: bool,
+}
+
+impl LibraryIndex {
+    fn get_path() -> anyhow::Result<PathBuf> {
+        let Some(cache_dir) = dirs::cache_dir() else {
+            bail!("Failed to find cache directory")
+        };
+
+        let app_dir = cache_dir.join(env!("CARGO_PKG_NAME"));
+        fs::create_dir_all(app_dir.join("thumbnails"))?;
+        Ok(app_dir)
+    }
+
+    pub fn read() -> anyhow::Result<Self> {
+        let path = Self::get_path()?.join("index.json");
+        if path.exists() {
+            Ok(serde_json::from_slice(&fs::read(path)?)?)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    /// Writes the index if anything changed
+    pub fn save(&mut self) -> anyhow::Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+
+        let path = Self::get_path()?.join("index.json");
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(self)?)?;
+        fs::rename(tmp, path)?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Entry of a file, emptied first when the file changed since it was cached
+    fn entry(&mut self, path: &Path, key: FileKey) -> &mut IndexEntry {
+        let entry = self
+            .entries
+            .entry(path.to_string_lossy().to_string())
+            .or_insert_with(|| IndexEntry::new(key));
+
+        if entry.key != key {
+            remove_thumbnail(entry);
+            *entry = IndexEntry::new(key);
+        }
+        entry
+    }
+
+    /// Fresh entry of a file
+    fn fresh(&self, path: &Path) -> Option<&IndexEntry> {
+        let key = FileKey::of(&fs::metadata(path).ok()?);
+        self.entries
+            .get(path.to_string_lossy().as_ref())
+            .filter(|entry| entry.key == key)
+    }
+
+    /// Reading of the filename without overrides, parsed only when the file or the patterns
+    /// changed
+    pub fn chapter(
+        &mut self,
+        path: &Path,
+        metadata: &Metadata,
+        parser: &FilenameParser,
+    ) -> Chapter {
+        let fingerprint = parser.fingerprint();
+        let entry = self.entry(path, FileKey::of(metadata));
+        if let Some((cached_by, chapter)) = &entry.parsed
+            && *cached_by == fingerprint
+        {
+            return chapter.clone();
+        }
+
+        let filename = path.file_name().unwrap_or_default().to_string_lossy();
+        let (chapter, _) = parser.parse_with_name(path.to_path_buf(), &filename);
+        entry.parsed = Some((fingerprint, chapter.clone()));
+        self.dirty = true;
+        chapter
+    }
+
+    /// `ComicInfo.xml` and the page count, when the file didn't change
+    pub fn summary(&self, path: &Path) -> Option<(ComicInfo, u32)> {
+        let entry = self.fresh(path)?;
+        Some((entry.comic_info.clone()?, entry.pages?))
+    }
+
+    /// `ComicInfo.xml` with the page count and the cover thumbnail, when the file didn't change
+    pub fn comic(&self, path: &Path) -> Option<(ComicInfo, Option<Vec<u8>>)> {
+        let entry = self.fresh(path)?;
+        let mut info = entry.comic_info.clone()?;
+        info.page_count = entry.pages;
+
+        let thumbnail = entry.thumbnail.as_ref().and_then(|name| {
+            let dir = Self::get_path().ok()?;
+            fs::read(dir.join("thumbnails").join(name)).ok()
+        });
+
+        Some((info, thumbnail))
+    }
+
+    /// Caches what was read from an archive whose state was `key` before it was read, so a
+    /// write in between leaves the entry stale. `thumbnail` is made by [`write_thumbnail`].
+    pub fn store_comic(
+        &mut self,
+        path: &Path,
+        key: FileKey,
+        info: &ComicInfo,
+        pages: u32,
+        thumbnail: Option<String>,
+    ) {
+        let entry = self.entry(path, key);
+        entry.comic_info = Some(info.clone());
+        entry.pages = Some(pages);
+        if thumbnail.is_some() && entry.thumbnail != thumbnail {
+            remove_thumbnail(entry);
+            entry.thumbnail = thumbnail;
+        }
+        self.dirty = true;
+    }
+
+    /// Moves the entry of a renamed file, keeping what was read from the archive and its
+    /// thumbnail. The old filename reading is dropped.
+    pub fn rename(&mut self, from: &Path, to: &Path) {
+        if let Some(mut entry) = self.entries.remove(from.to_string_lossy().as_ref()) {
+            entry.parsed = None;
+            self.entries.insert(to.to_string_lossy().to_string(), entry);
+            self.dirty = true;
+        }
+    }
+
+    /// Drops entries of files that no longer exist
+    pub fn prune(&mut self) {
+        let before = self.entries.len();
+        self.entries.retain(|path, entry| {
+            let exists = Path::new(path).exists();
+            if !exists {
+                remove_thumbnail(entry);
+            }
+            exists
+        });
+        self.dirty |= self.entries.len() != before;
+    }
+}
+
+/// Scales the cover of an archive down and writes it as a JPEG named after the file and its
+/// state, returning the name
+pub fn write_thumbnail(path: &Path, key: FileKey, cover: &[u8]) -> anyhow::Result<String> {
+    let mut hasher = DefaultHasher::new();
+    (path, key).hash(&mut hasher);
+    let name = format!("{:016x}.jpg", hasher.finish());
+
+    let thumbnail = image::load_from_memory(cover)?
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .to_rgb8();
+    let mut buffer = Cursor::new(Vec::new());
+    thumbnail.write_to(&mut buffer, ImageFormat::Jpeg)?;
+
+    fs::write(
+        LibraryIndex::get_path()?.join("thumbnails").join(&name),
+        buffer.into_inner(),
+    )?;
+    Ok(name)
+}
+
+fn remove_thumbnail(entry: &mut IndexEntry) {
+    if let Some(name) = entry.thumbnail.take()
+        && let Ok(dir) = LibraryIndex::get_path()
+    {
+        let _ = fs::remove_file(dir.join("thumbnails").join(name));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_files_are_parsed_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("Ch.001.cbz");
+        fs::write(&path, b"").unwrap();
+
+        let parser = FilenameParser::default();
+        let mut index = LibraryIndex::default();
+        let chapter = index.chapter(&path, &fs::metadata(&path).unwrap(), &parser);
+        assert_eq!(chapter.chapter, Some(1.0));
+        assert!(index.dirty);
+
+        // A cached reading is returned as is while the file is unchanged
+        index.dirty = false;
+        let entry = index.entries.values_mut().next().unwrap();
+        entry.parsed.as_mut().unwrap().1.chapter = Some(2.0);
+        let chapter = index.chapter(&path, &fs::metadata(&path).unwrap(), &parser);
+        assert_eq!(chapter.chapter, Some(2.0));
+        assert!(!index.dirty);
+
+        fs::write(&path, b"changed").unwrap();
+        let chapter = index.chapter(&path, &fs::metadata(&path).unwrap(), &parser);
+        assert_eq!(chapter.chapter, Some(1.0));
+
+        let info = ComicInfo {
+            title: String::from("Title"),
+            ..Default::default()
+        };
+        let key = FileKey::of(&fs::metadata(&path).unwrap());
+        index.store_comic(&path, key, &info, 12, None);
+
+        let json = serde_json::to_string(&index).unwrap();
+        let index: LibraryIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(index.len(), 1);
+        let (info, thumbnail) = index.comic(&path).unwrap();
+        assert_eq!(info.title, "Title");
+        assert_eq!(info.page_count, Some(12));
+        assert!(thumbnail.is_none());
+
+        // A write while the archive was read leaves the entry stale
+        let other = dir.path().join("Ch.002.cbz");
+        fs::write(&other, b"").unwrap();
+        let key = FileKey::of(&fs::metadata(&other).unwrap());
+        fs::write(&other, b"written meanwhile").unwrap();
+        let mut stale = LibraryIndex::default();
+        stale.store_comic(&other, key, &info, 12, None);
+        assert!(stale.comic(&other).is_none());
+
+        // Renamed files keep what was read from them
+        let mut index = index;
+        let renamed = dir.path().join("Vol.01 Ch.001.cbz");
+        fs::rename(&path, &renamed).unwrap();
+        index.rename(&path, &renamed);
+        assert!(index.comic(&path).is_none());
+        assert_eq!(index.comic(&renamed).unwrap().0.title, "Title");
+        let chapter = index.chapter(&renamed, &fs::metadata(&renamed).unwrap(), &parser);
+        assert_eq!(chapter.volume, Some(1));
+    }
+}
@@ -167,6 +167,7 @@ impl KomgaBook {
             web: comic_info.web.clone(),
             page_count: Some(self.media.pages_count),
             language_iso: series.metadata.language.clone(),
+            format: comic_info.format.clone(),
             manga: series
                 .metadata
                 .reading_direction
@@ -19,6 +19,7 @@ pub struct KomgaSeriesMetadata {
     pub reading_direction: Option<String>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct KomgaSeriesBooksMetadata {
@@ -27,6 +28,7 @@ pub struct KomgaSeriesBooksMetadata {
     pub translator: Option<String>,
 }
 
+#[allow(dead_code)]
 #[derive(Debug, Deserialize)]
 struct RawMetadata {
     authors: Vec<super::RawAuthor>,
@@ -0,0 +1,318 @@
+//! House rules for metadata declared in `config.toml`, checked against each chapter's
+//! `ComicInfo` and filename reading
+//!
+//! ```toml
+//! [[lint_rules]]
+//! name = "manga-rtl"
+//! field = "Manga"
+//! check = "equals"
+//! value = "YesAndRightToLeft"
+//! language = "ja"
+//! fix = true
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    ui::list::{Chapter, ChapterKind, Series},
+};
+
+/// A single rule
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LintRule {
+    /// Shown next to the issues, like `manga-rtl`
+    pub name: String,
+    pub field: ComicInfoField,
+    #[serde(flatten)]
+    pub check: LintCheck,
+    /// Only checks chapters in this language
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Only checks series with this `series.json` status, like `Ended`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    /// Whether the fix may be written by the batch updaters
+    #[serde(default)]
+    pub fix: bool,
+}
+
+/// What a rule expects of its field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum LintCheck {
+    /// The field can't be empty, has no fix
+    Required,
+    /// The field must be `value`, fixed by setting it
+    Equals { value: String },
+    /// Every comma separated value must be one of `values`, fixed by dropping the others
+    OneOf { values: Vec<String> },
+    /// The field must be the highest chapter number of the series without the fraction, fixed
+    /// by setting it
+    LastChapter,
+}
+
+/// Data of the series a chapter is checked in
+#[derive(Debug, Clone, Default)]
+pub struct LintContext {
+    /// Status in `series.json`
+    pub status: Option<String>,
+    /// Highest regular chapter number
+    pub last_chapter: Option<f32>,
+}
+
+impl LintContext {
+    pub fn of(series: &Series) -> Self {
+        let last_chapter = series
+            .chapters
+            .items_state
+            .iter()
+            .filter(|c| c.kind == ChapterKind::Regular && !c.is_volume())
+            .filter_map(|c| c.chapter_end.or(c.chapter))
+            .max_by(f32::total_cmp);
+
+        Self {
+            status: series
+                .series_json
+                .as_ref()
+                .and_then(|json| json.metadata.status.clone()),
+            last_chapter,
+        }
+    }
+}
+
+/// A broken rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintIssue {
+    /// Name of the rule
+    pub rule: String,
+    pub message: String,
+    /// Whether the rule can fix it
+    pub fixable: bool,
+}
+
+fn same(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+impl LintRule {
+    /// Whether the rule is meant for the chapter, the `ComicInfo` language wins over the filename
+    fn applies(&self, info: &ComicInfo, chapter: &Chapter, context: &LintContext) -> bool {
+        let language = info.language_iso.as_deref().or(chapter.language.as_deref());
+        let language_matches = self
+            .language
+            .as_deref()
+            .is_none_or(|expected| language.is_some_and(|l| same(l, expected)));
+        let status_matches = self
+            .status
+            .as_deref()
+            .is_none_or(|expected| context.status.as_deref().is_some_and(|s| same(s, expected)));
+
+        language_matches && status_matches
+    }
+
+    /// Value the field should have, `None` when the current one is fine and `Some(None)` when it
+    /// should be empty
+    #[allow(clippy::option_option)]
+    fn expected(&self, current: Option<&str>, context: &LintContext) -> Option<Option<String>> {
+        match &self.check {
+            LintCheck::Required => current.is_none().then_some(None),
+            LintCheck::Equals { value } => {
+                (!current.is_some_and(|c| same(c, value))).then(|| Some(value.clone()))
+            }
+            LintCheck::OneOf { values } => {
+                let current = current?;
+                let (allowed, other): (Vec<&str>, Vec<&str>) = current
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|v| !v.is_empty())
+                    .partition(|v| values.iter().any(|allowed| same(allowed, v)));
+                (!other.is_empty()).then(|| (!allowed.is_empty()).then(|| allowed.join(", ")))
+            }
+            LintCheck::LastChapter => {
+                let last = format!("{}", context.last_chapter?.trunc());
+                (current != Some(last.as_str())).then_some(Some(last))
+            }
+        }
+    }
+
+    pub fn check(
+        &self,
+        info: &ComicInfo,
+        chapter: &Chapter,
+        context: &LintContext,
+    ) -> Option<LintIssue> {
+        if !self.applies(info, chapter, context) {
+            return None;
+        }
+
+        let current = self.field.get(info);
+        let expected = self.expected(current.as_deref(), context)?;
+        let field = self.field;
+        let message = match (&self.check, current) {
+            (LintCheck::Required, _) | (_, None) => format!("{field} is empty"),
+            (LintCheck::OneOf { .. }, Some(current)) => {
+                format!("{field} {current} has values not in the allowed list")
+            }
+            (_, Some(current)) => format!(
+                "{field} is {current}, expected {}",
+                expected.unwrap_or_default()
+            ),
+        };
+
+        Some(LintIssue {
+            rule: self.name.clone(),
+            message,
+            fixable: self.fix && self.check != LintCheck::Required,
+        })
+    }
+
+    /// Fixes the field when the rule is broken and may be fixed
+    pub fn fix(&self, info: &mut ComicInfo, chapter: &Chapter, context: &LintContext) {
+        if !self.fix || self.check == LintCheck::Required || !self.applies(info, chapter, context) {
+            return;
+        }
+
+        let current = self.field.get(info);
+        if let Some(expected) = self.expected(current.as_deref(), context)
+            && let Err(e) = self.field.set(info, expected.as_deref())
+        {
+            warn!(
+                "Failed to fix {} ({}): {e}",
+                self.name,
+                chapter.path.display()
+            );
+        }
+    }
+}
+
+/// Issues of a chapter in rule order
+pub fn lint(
+    rules: &[LintRule],
+    info: &ComicInfo,
+    chapter: &Chapter,
+    context: &LintContext,
+) -> Vec<LintIssue> {
+    rules
+        .iter()
+        .filter_map(|rule| rule.check(info, chapter, context))
+        .collect()
+}
+
+/// `info` with the fixable issues fixed
+pub fn fix(
+    rules: &[LintRule],
+    mut info: ComicInfo,
+    chapter: &Chapter,
+    context: &LintContext,
+) -> ComicInfo {
+    for rule in rules {
+        rule.fix(&mut info, chapter, context);
+    }
+    info
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::comic_info::ComicInfoManga;
+
+    use super::*;
+
+    const RULES: &str = r#"
+        [[lint_rules]]
+        name = "manga-rtl"
+        field = "Manga"
+        check = "equals"
+        value = "YesAndRightToLeft"
+        language = "ja"
+        fix = true
+
+        [[lint_rules]]
+        name = "finished-count"
+        field = "Count"
+        check = "last_chapter"
+        status = "Ended"
+        fix = true
+
+        [[lint_rules]]
+        name = "genres"
+        field = "Genre"
+        check = "one_of"
+        values = ["Action", "Drama"]
+        fix = true
+
+        [[lint_rules]]
+        name = "summary"
+        field = "Summary"
+        check = "required"
+    "#;
+
+    #[derive(Serialize, Deserialize)]
+    struct Rules {
+        lint_rules: Vec<LintRule>,
+    }
+
+    fn rules() -> Vec<LintRule> {
+        toml::from_str::<Rules>(RULES).unwrap().lint_rules
+    }
+
+    #[test]
+    fn test_lint_and_fix() {
+        let rules = rules();
+        assert_eq!(rules[1].check, LintCheck::LastChapter);
+
+        // Rules survive the config being saved
+        let written = toml::to_string_pretty(&Rules {
+            lint_rules: rules.clone(),
+        })
+        .unwrap();
+        assert_eq!(toml::from_str::<Rules>(&written).unwrap().lint_rules, rules);
+
+        let chapter = Chapter {
+            chapter: Some(3.0),
+            language: Some(String::from("ja")),
+            ..Default::default()
+        };
+        let context = LintContext {
+            status: Some(String::from("ended")),
+            last_chapter: Some(41.5),
+        };
+        let info = ComicInfo {
+            manga: ComicInfoManga::Yes,
+            genre: Some(String::from("Action, Isekai")),
+            count: Some(40),
+            ..Default::default()
+        };
+
+        let issues = lint(&rules, &info, &chapter, &context);
+        let names: Vec<&str> = issues.iter().map(|i| i.rule.as_str()).collect();
+        assert_eq!(names, ["manga-rtl", "finished-count", "genres", "summary"]);
+        assert_eq!(issues[1].message, "Count is 40, expected 41");
+        assert!(!issues[3].fixable);
+
+        let fixed = fix(&rules, info, &chapter, &context);
+        assert!(matches!(fixed.manga, ComicInfoManga::YesAndRightToLeft));
+        assert_eq!(fixed.count, Some(41));
+        assert_eq!(fixed.genre.as_deref(), Some("Action"));
+
+        let issues = lint(&rules, &fixed, &chapter, &context);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].message, "Summary is empty");
+    }
+
+    #[test]
+    fn test_conditions() {
+        let rules = rules();
+        let chapter = Chapter {
+            language: Some(String::from("en")),
+            ..Default::default()
+        };
+        let info = ComicInfo {
+            summary: Some(String::from("Guts.")),
+            ..Default::default()
+        };
+
+        // Neither Japanese nor finished
+        assert!(lint(&rules, &info, &chapter, &LintContext::default()).is_empty());
+    }
+}
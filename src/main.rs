@@ -8,16 +8,27 @@ use std::fs::File;
 use log::LevelFilter;
 use simplelog::{WriteLogger, format_description};
 
-use crate::{config::Config, data::get_series_list, ui::App};
+use crate::{
+    config::{Config, ParseOverrides},
+    data::{FilenameParser, SeriesFolderParser},
+    index::LibraryIndex,
+    ui::App,
+};
 
 mod chapter_manager;
 mod comic_info;
 mod config;
 mod data;
+mod index;
 mod komga;
+mod lint;
 mod managers;
 mod serializers;
+mod series_json;
+mod template;
+mod transform;
 mod ui;
+mod volume_map;
 mod zip_util;
 
 #[tokio::main]
@@ -38,10 +49,22 @@ async fn main() -> anyhow::Result<()> {
         log_file,
     )?;
 
-    let series = get_series_list(&config.manga_dir)?;
+    let overrides = ParseOverrides::read().unwrap_or_else(|e| {
+        error!("Failed to read filename overrides: {e}");
+        ParseOverrides::default()
+    });
+    let parser = FilenameParser::new(&config.filename_patterns, overrides);
+    let folder_parser = SeriesFolderParser::new(&config.series_folder);
+    let index = LibraryIndex::read().unwrap_or_else(|e| {
+        error!("Failed to read library index: {e}");
+        LibraryIndex::default()
+    });
 
     let terminal = ratatui::init();
-    let app_result = App::new(series, config)?.run(terminal);
+    let app_result = App::new(config, parser, folder_parser, index).and_then(|mut app| {
+        app.start_scan();
+        app.run(terminal)
+    });
     ratatui::restore();
     app_result
 }
@@ -0,0 +1,447 @@
+use std::{cmp::Ordering, str::FromStr};
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+use tui_input::Input;
+
+use crate::{
+    comic_info::ComicInfoField,
+    managers::{details::ChapterDetails, stats::format_size},
+    ui::list::Chapter,
+};
+
+/// Width of the title when it isn't the last column
+const TITLE_WIDTH: usize = 32;
+
+/// Column of the chapters list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChapterColumn {
+    Volume,
+    Number,
+    Title,
+    Translator,
+    Language,
+    Pages,
+    Size,
+    /// How well the chapter is tagged
+    Status,
+}
+
+impl ChapterColumn {
+    pub const ALL: [Self; 8] = [
+        Self::Volume,
+        Self::Number,
+        Self::Title,
+        Self::Translator,
+        Self::Language,
+        Self::Pages,
+        Self::Size,
+        Self::Status,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Volume => "volume",
+            Self::Number => "number",
+            Self::Title => "title",
+            Self::Translator => "translator",
+            Self::Language => "language",
+            Self::Pages => "pages",
+            Self::Size => "size",
+            Self::Status => "status",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "chapter" => Some(Self::Number),
+            "translators" => Some(Self::Translator),
+            "lang" => Some(Self::Language),
+            name => Self::ALL.into_iter().find(|c| c.name() == name),
+        }
+    }
+
+    /// Width of the column, the title takes the rest when it's last
+    fn width(self) -> usize {
+        match self {
+            Self::Volume => 4,
+            Self::Number | Self::Language | Self::Pages => 5,
+            Self::Title => TITLE_WIDTH,
+            Self::Translator => 16,
+            Self::Size => 9,
+            Self::Status => 8,
+        }
+    }
+
+    /// Value used for filtering, the filename reading wins over `ComicInfo`
+    pub fn value(self, chapter: &Chapter, details: Option<&ChapterDetails>) -> Option<String> {
+        let info = details.map(|d| &d.info);
+        let value = match self {
+            Self::Volume => chapter.volume.map(|v| v.to_string()),
+            Self::Number => chapter.chapter.map(|v| v.to_string()),
+            Self::Title => Some(chapter.title.clone().unwrap_or_else(|| {
+                let name = chapter.path.file_name().unwrap_or_default();
+                name.to_string_lossy().to_string()
+            })),
+            Self::Translator if chapter.translators.is_empty() => {
+                info.and_then(|i| i.translator.clone())
+            }
+            Self::Translator => Some(chapter.translators.join(", ")),
+            Self::Language => chapter
+                .language
+                .clone()
+                .or_else(|| info.and_then(|i| i.language_iso.clone())),
+            Self::Pages => details.map(|d| d.pages.to_string()),
+            Self::Size => details.map(|d| d.size.to_string()),
+            Self::Status => details.map(|d| d.status().label().to_string()),
+        };
+
+        value.filter(|v| !v.trim().is_empty())
+    }
+
+    /// Text shown in the list, padded to the column width unless it's the last column
+    pub fn cell(self, chapter: &Chapter, details: Option<&ChapterDetails>, last: bool) -> String {
+        let text = match self {
+            Self::Volume => chapter.volume.map(|v| format!("v{v}")).unwrap_or_default(),
+            Self::Number => chapter.number_label(),
+            Self::Size => details.map(|d| format_size(d.size)).unwrap_or_default(),
+            Self::Pages => details.map(|d| format!("{}p", d.pages)).unwrap_or_default(),
+            column => column.value(chapter, details).unwrap_or_default(),
+        };
+
+        if last {
+            return text;
+        }
+
+        let width = self.width();
+        let mut text: String = text.chars().take(width).collect();
+        if text.chars().count() == width && self == Self::Title {
+            text.pop();
+            text.push('…');
+        }
+        match self {
+            Self::Volume | Self::Number | Self::Pages | Self::Size => format!("{text:>width$}"),
+            _ => format!("{text:<width$}"),
+        }
+    }
+
+    /// Order of two chapters by the column, ties keep the chapter order and chapters without a
+    /// value go last
+    pub fn compare(
+        self,
+        a: (&Chapter, Option<&ChapterDetails>),
+        b: (&Chapter, Option<&ChapterDetails>),
+    ) -> Ordering {
+        let ordering = match self {
+            // Volume-aware, `Chapter::cmp` puts volume archives before every chapter
+            Self::Volume => missing_last(a.0.volume, b.0.volume),
+            Self::Number => Ordering::Equal,
+            Self::Pages => missing_last(a.1.map(|d| d.pages), b.1.map(|d| d.pages)),
+            Self::Size => missing_last(a.1.map(|d| d.size), b.1.map(|d| d.size)),
+            Self::Status => missing_last(
+                a.1.map(ChapterDetails::status),
+                b.1.map(ChapterDetails::status),
+            ),
+            column => missing_last(
+                column.value(a.0, a.1).map(|v| v.to_lowercase()),
+                column.value(b.0, b.1).map(|v| v.to_lowercase()),
+            ),
+        };
+
+        ordering.then_with(|| a.0.cmp(b.0))
+    }
+}
+
+/// Compares two values, missing ones last
+fn missing_last<T: Ord>(a: Option<T>, b: Option<T>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.cmp(&b),
+        (None, Some(_)) => Ordering::Greater,
+        (Some(_), None) => Ordering::Less,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Sorting of the chapters list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChapterSort {
+    pub column: ChapterColumn,
+    pub reverse: bool,
+}
+
+impl ChapterSort {
+    pub fn compare(
+        self,
+        a: (&Chapter, Option<&ChapterDetails>),
+        b: (&Chapter, Option<&ChapterDetails>),
+    ) -> Ordering {
+        let ordering = self.column.compare(a, b);
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    pub fn label(self) -> String {
+        let arrow = if self.reverse { "↓" } else { "↑" };
+        format!("{} {arrow}", self.column.name())
+    }
+}
+
+/// What a filter condition looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FilterKey {
+    Column(ChapterColumn),
+    Field(ComicInfoField),
+}
+
+impl FilterKey {
+    fn field(name: &str) -> Option<ComicInfoField> {
+        ComicInfoField::ALL
+            .into_iter()
+            .find(|field| field.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Columns win over fields of the same name
+    fn parse(name: &str) -> anyhow::Result<Self> {
+        ChapterColumn::from_name(name)
+            .map(Self::Column)
+            .or_else(|| Self::field(name).map(Self::Field))
+            .ok_or_else(|| anyhow!("Unknown column or field: {name}"))
+    }
+
+    /// `ComicInfo` fields win over columns of the same name
+    fn parse_missing(name: &str) -> anyhow::Result<Self> {
+        Self::field(name)
+            .map(Self::Field)
+            .map_or_else(|| Self::parse(name), Ok)
+    }
+
+    fn value(self, chapter: &Chapter, details: Option<&ChapterDetails>) -> Option<String> {
+        match self {
+            Self::Column(column) => column.value(chapter, details),
+            Self::Field(field) => details.and_then(|d| field.get(&d.info)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum FilterCondition {
+    Missing(FilterKey),
+    Equals(FilterKey, String),
+    NotEquals(FilterKey, String),
+    Contains(FilterKey, String),
+}
+
+/// Whether one of the comma separated values is `expected`, numbers are compared as numbers
+fn has_value(actual: &str, expected: &str) -> bool {
+    let expected = expected.trim();
+    std::iter::once(actual)
+        .chain(actual.split(','))
+        .map(str::trim)
+        .any(|value| {
+            value.eq_ignore_ascii_case(expected)
+                || value
+                    .parse::<f64>()
+                    .ok()
+                    .zip(expected.parse::<f64>().ok())
+                    .is_some_and(|(a, b)| (a - b).abs() < f64::EPSILON)
+        })
+}
+
+impl FilterCondition {
+    fn matches(&self, chapter: &Chapter, details: Option<&ChapterDetails>) -> bool {
+        match self {
+            Self::Missing(key) => key.value(chapter, details).is_none(),
+            Self::Equals(key, expected) => key
+                .value(chapter, details)
+                .is_some_and(|v| has_value(&v, expected)),
+            Self::NotEquals(key, expected) => !key
+                .value(chapter, details)
+                .is_some_and(|v| has_value(&v, expected)),
+            Self::Contains(key, expected) => key
+                .value(chapter, details)
+                .is_some_and(|v| v.to_lowercase().contains(&expected.to_lowercase())),
+        }
+    }
+}
+
+impl FromStr for FilterCondition {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if let Some(name) = s.strip_prefix("missing ").or_else(|| s.strip_prefix("no ")) {
+            return Ok(Self::Missing(FilterKey::parse_missing(name.trim())?));
+        }
+
+        // The first operator splits, `!=` starts before its `=`
+        let Some((at, operator)) = ["!=", "=", "~"]
+            .into_iter()
+            .filter_map(|operator| s.find(operator).map(|at| (at, operator)))
+            .min()
+        else {
+            bail!("Expected `missing <field>` or `<column> =, != or ~ <value>`: {s}")
+        };
+
+        let key = FilterKey::parse(s[..at].trim())?;
+        let value = s[at + operator.len()..].trim().to_string();
+        Ok(match operator {
+            "!=" => Self::NotEquals(key, value),
+            "=" => Self::Equals(key, value),
+            _ => Self::Contains(key, value),
+        })
+    }
+}
+
+/// Filter of chapters like `missing summary and translator = X`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChapterFilter {
+    conditions: Vec<FilterCondition>,
+}
+
+impl ChapterFilter {
+    pub fn matches(&self, chapter: &Chapter, details: Option<&ChapterDetails>) -> bool {
+        self.conditions.iter().all(|c| c.matches(chapter, details))
+    }
+}
+
+impl FromStr for ChapterFilter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let conditions = s
+            .split(" and ")
+            .filter(|c| !c.trim().is_empty())
+            .map(str::parse)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        Ok(Self { conditions })
+    }
+}
+
+/// Sorting and filtering of the chapters list
+pub struct ChapterView {
+    /// Sorting, `None` for the chapter order
+    pub sort: Option<ChapterSort>,
+
+    /// Filter being typed
+    pub filter: Input,
+
+    /// Why the filter couldn't be read
+    pub error: Option<String>,
+}
+
+impl ChapterView {
+    pub fn new() -> Self {
+        Self {
+            sort: None,
+            filter: Input::default(),
+            error: None,
+        }
+    }
+
+    /// Sorts by the next column, going back to the chapter order after the last one
+    pub fn next_sort(&mut self) {
+        let reverse = self.sort.is_some_and(|s| s.reverse);
+        let next = match self.sort {
+            None => ChapterColumn::ALL.first(),
+            Some(sort) => ChapterColumn::ALL
+                .iter()
+                .skip_while(|&&c| c != sort.column)
+                .nth(1),
+        };
+        self.sort = next.map(|&column| ChapterSort { column, reverse });
+    }
+
+    pub fn reverse_sort(&mut self) {
+        let sort = self.sort.get_or_insert(ChapterSort {
+            column: ChapterColumn::Number,
+            reverse: false,
+        });
+        sort.reverse = !sort.reverse;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::comic_info::ComicInfo;
+
+    use super::*;
+
+    fn chapter(volume: Option<u32>, number: f32, translator: &str) -> Chapter {
+        Chapter {
+            path: PathBuf::from(format!("{number}.cbz")),
+            volume,
+            chapter: Some(number),
+            translators: vec![translator.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        let tagged = ChapterDetails {
+            info: ComicInfo {
+                summary: Some(String::from("Guts.")),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let a = chapter(Some(3), 12.0, "Band of the Hawk, Scans");
+        let b = chapter(Some(4), 20.0, "Other");
+
+        let filter: ChapterFilter = "missing summary".parse().unwrap();
+        assert!(!filter.matches(&a, Some(&tagged)));
+        assert!(filter.matches(&a, None));
+
+        let filter: ChapterFilter = "translator = scans and volume = 3".parse().unwrap();
+        assert!(filter.matches(&a, None));
+        assert!(!filter.matches(&b, None));
+
+        let filter: ChapterFilter = "number != 12.0".parse().unwrap();
+        assert!(!filter.matches(&a, None));
+        assert!(filter.matches(&b, None));
+
+        let filter: ChapterFilter = "Translator ~ hawk".parse().unwrap();
+        assert!(filter.matches(&a, None));
+        let filter: ChapterFilter = "title ~ a=b".parse().unwrap();
+        assert!(!filter.matches(&a, None));
+
+        assert!("pages > 3".parse::<ChapterFilter>().is_err());
+        assert!("colour = red".parse::<ChapterFilter>().is_err());
+    }
+
+    #[test]
+    fn test_volume_aware_sort() {
+        let mut chapters = [
+            chapter(None, 1.0, ""),
+            chapter(Some(2), 10.0, ""),
+            chapter(Some(1), 11.0, ""),
+        ];
+        let sort = ChapterSort {
+            column: ChapterColumn::Volume,
+            reverse: false,
+        };
+        chapters.sort_by(|a, b| sort.compare((a, None), (b, None)));
+
+        let numbers: Vec<f32> = chapters.iter().filter_map(|c| c.chapter).collect();
+        assert_eq!(numbers, [11.0, 10.0, 1.0]);
+    }
+
+    #[test]
+    fn test_next_sort_cycles() {
+        let mut view = ChapterView::new();
+        view.next_sort();
+        assert_eq!(view.sort.map(|s| s.column), Some(ChapterColumn::Volume));
+        for _ in 1..ChapterColumn::ALL.len() {
+            view.next_sort();
+        }
+        assert_eq!(view.sort.map(|s| s.column), Some(ChapterColumn::Status));
+        view.next_sort();
+        assert_eq!(view.sort, None);
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::mpsc;
 use tui_input::Input;
 
 use crate::{
-    comic_info::{ComicInfo, ComicInfoAgeRating, ComicInfoManga},
+    comic_info::{ComicInfo, ComicInfoAgeRating, ComicInfoField, ComicInfoManga},
     ui::widgets::spinner::SpinnerState,
 };
 
@@ -32,6 +32,9 @@ impl ComicInfoManager {
 pub struct ComicInfoForm {
     pub fields: Vec<(&'static str, Input)>, // label + input
     pub active_index: usize,
+
+    /// Info the form was filled with
+    pub original: Box<ComicInfo>,
 }
 
 impl ComicInfoForm {
@@ -90,6 +93,10 @@ impl ComicInfoForm {
                 "Language ISO*",
                 Input::new(info.language_iso.clone().unwrap_or_default()),
             ),
+            (
+                "Format",
+                Input::new(info.format.clone().unwrap_or_default()),
+            ),
             ("Manga*", Input::new(info.manga.to_string())),
             ("Age Rating*", Input::new(info.age_rating.to_string())),
             (
@@ -101,15 +108,26 @@ impl ComicInfoForm {
         Self {
             fields,
             active_index: 0,
+            original: Box::new(info.clone()),
         }
     }
 
+    /// Fields edited since the form was filled
+    pub fn changed_fields(&self) -> Vec<ComicInfoField> {
+        self.original.diff(&self.to_comic_info())
+    }
+
     pub fn next(&mut self) {
         self.active_index = (self.active_index + 1) % self.fields.len();
     }
 
+    /// Number of fields in the left column
+    fn side_step(&self) -> usize {
+        self.fields.len().div_ceil(2)
+    }
+
     pub fn next_side(&mut self) {
-        self.active_index = (self.active_index + 10) % self.fields.len();
+        self.active_index = (self.active_index + self.side_step()) % self.fields.len();
     }
 
     pub fn prev(&mut self) {
@@ -121,7 +139,7 @@ impl ComicInfoForm {
     }
 
     pub fn prev_side(&mut self) {
-        let step = 10 % self.fields.len();
+        let step = self.side_step() % self.fields.len();
         if self.active_index < step {
             self.active_index = self.fields.len() + self.active_index - step;
         } else {
@@ -152,10 +170,11 @@ impl ComicInfoForm {
             web: parse_opt_string(self.fields[14].1.value()),
             page_count: parse_opt_u32(self.fields[15].1.value()),
             language_iso: parse_opt_string(self.fields[16].1.value()),
-            manga: parse_enum::<ComicInfoManga>(self.fields[17].1.value()).unwrap_or_default(),
-            age_rating: parse_enum::<ComicInfoAgeRating>(self.fields[18].1.value())
+            format: parse_opt_string(self.fields[17].1.value()),
+            manga: parse_enum::<ComicInfoManga>(self.fields[18].1.value()).unwrap_or_default(),
+            age_rating: parse_enum::<ComicInfoAgeRating>(self.fields[19].1.value())
                 .unwrap_or_default(),
-            count: parse_opt_u32(self.fields[19].1.value()),
+            count: parse_opt_u32(self.fields[20].1.value()),
         }
     }
 }
@@ -0,0 +1,107 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::mpsc,
+};
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    lint::LintIssue,
+};
+
+/// Fields a chapter needs to count as tagged
+const KEY_FIELDS: [ComicInfoField; 5] = [
+    ComicInfoField::Series,
+    ComicInfoField::Number,
+    ComicInfoField::Title,
+    ComicInfoField::Writer,
+    ComicInfoField::Summary,
+];
+
+/// How well a chapter is tagged
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MetadataStatus {
+    /// No `ComicInfo.xml` or an empty one
+    Untagged,
+    /// Some of the key fields are empty
+    Partial,
+    /// All key fields are set
+    Tagged,
+}
+
+impl MetadataStatus {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Untagged => "untagged",
+            Self::Partial => "partial",
+            Self::Tagged => "tagged",
+        }
+    }
+}
+
+/// What was read from the archive of a chapter
+#[derive(Debug, Clone, Default)]
+pub struct ChapterDetails {
+    pub info: ComicInfo,
+    /// Size of the archive in bytes
+    pub size: u64,
+    pub pages: u32,
+    /// Broken lint rules
+    pub issues: Vec<LintIssue>,
+}
+
+impl ChapterDetails {
+    pub fn status(&self) -> MetadataStatus {
+        let set = |field: &ComicInfoField| field.get(&self.info).is_some();
+        if KEY_FIELDS.iter().all(set) {
+            MetadataStatus::Tagged
+        } else if ComicInfoField::ALL
+            .iter()
+            .filter(|&&field| field != ComicInfoField::PageCount)
+            .any(set)
+        {
+            MetadataStatus::Partial
+        } else {
+            MetadataStatus::Untagged
+        }
+    }
+}
+
+/// Archive data of the chapters in the current series
+pub struct DetailsManager {
+    /// Series the details are for, `None` to read the current series again
+    pub series: Option<PathBuf>,
+
+    /// Details per chapter path
+    pub chapters: HashMap<PathBuf, ChapterDetails>,
+
+    /// Channel for receiving the details of a series
+    #[allow(clippy::type_complexity)]
+    pub details_rx: Option<mpsc::Receiver<(PathBuf, HashMap<PathBuf, ChapterDetails>)>>,
+}
+
+impl DetailsManager {
+    pub fn new() -> Self {
+        Self {
+            series: None,
+            chapters: HashMap::new(),
+            details_rx: None,
+        }
+    }
+
+    /// Drops the details and any read in progress, so the current series is read again
+    pub fn invalidate(&mut self) {
+        self.series = None;
+        self.details_rx = None;
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&ChapterDetails> {
+        self.chapters.get(path)
+    }
+
+    /// Lint issues of a chapter
+    pub fn issues(&self, path: &Path) -> &[LintIssue] {
+        self.get(path)
+            .map_or(&[], |details| details.issues.as_slice())
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::HashSet;
+
+use ratatui::widgets::TableState;
+use tui_input::Input;
+
+use crate::{comic_info::ComicInfoField, config::FieldPreset};
+
+/// Picks the fields copied from the form to the selected chapters
+pub struct FieldPicker {
+    /// Ticked fields
+    pub fields: HashSet<ComicInfoField>,
+
+    /// Index of the loaded preset in the config, `None` once the ticks are changed
+    pub preset: Option<usize>,
+
+    /// Name of the preset being saved
+    pub name: Option<Input>,
+
+    /// State of the fields table
+    pub state: TableState,
+}
+
+impl FieldPicker {
+    /// Starts with the fields Ctrl+D writes
+    pub fn new() -> Self {
+        Self {
+            fields: ComicInfoField::SHARED.into_iter().collect(),
+            preset: None,
+            name: None,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn selected(&self) -> Option<ComicInfoField> {
+        self.state
+            .selected()
+            .and_then(|i| ComicInfoField::ALL.get(i).copied())
+    }
+
+    /// Ticked fields in `ComicInfo.xml` order
+    pub fn chosen(&self) -> Vec<ComicInfoField> {
+        ComicInfoField::ALL
+            .into_iter()
+            .filter(|field| self.fields.contains(field))
+            .collect()
+    }
+
+    /// Shown in the preview title
+    pub fn label(&self, presets: &[FieldPreset]) -> String {
+        match self.preset.and_then(|i| presets.get(i)) {
+            Some(preset) => preset.name.clone(),
+            None => format!("{} fields", self.fields.len()),
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        let Some(field) = self.selected() else {
+            return;
+        };
+        if !self.fields.remove(&field) {
+            self.fields.insert(field);
+        }
+        self.preset = None;
+    }
+
+    /// Ticks every field, or none when all are ticked
+    pub fn toggle_all(&mut self) {
+        if self.fields.len() == ComicInfoField::ALL.len() {
+            self.fields.clear();
+        } else {
+            self.fields = ComicInfoField::ALL.into_iter().collect();
+        }
+        self.preset = None;
+    }
+
+    /// Loads the preset after the current one, wrapping around
+    pub fn next_preset(&mut self, presets: &[FieldPreset]) {
+        if presets.is_empty() {
+            return;
+        }
+
+        let next = self.preset.map_or(0, |i| (i + 1) % presets.len());
+        self.fields = presets[next].fields.iter().copied().collect();
+        self.preset = Some(next);
+    }
+
+    /// Starts naming a preset, filled with the loaded one so it can be overwritten
+    pub fn start_naming(&mut self, presets: &[FieldPreset]) {
+        let name = self
+            .preset
+            .and_then(|i| presets.get(i))
+            .map(|preset| preset.name.clone())
+            .unwrap_or_default();
+        self.name = Some(Input::new(name));
+    }
+
+    /// Saves the ticked fields under the typed name, replacing a preset of the same name
+    pub fn save_preset(&mut self, presets: &mut Vec<FieldPreset>) -> Option<String> {
+        let name = self.name.take()?.value().trim().to_string();
+        if name.is_empty() {
+            return None;
+        }
+
+        let preset = FieldPreset {
+            name: name.clone(),
+            fields: self.chosen(),
+        };
+        let i = presets
+            .iter()
+            .position(|p| p.name == name)
+            .unwrap_or(presets.len());
+        if i == presets.len() {
+            presets.push(preset);
+        } else {
+            presets[i] = preset;
+        }
+        self.preset = Some(i);
+        Some(name)
+    }
+
+    /// Removes the loaded preset, the ticks stay
+    pub fn delete_preset(&mut self, presets: &mut Vec<FieldPreset>) -> Option<String> {
+        let i = self.preset.take().filter(|&i| i < presets.len())?;
+        Some(presets.remove(i).name)
+    }
+
+    pub fn next(&mut self) {
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state
+            .select(Some(next.min(ComicInfoField::ALL.len() - 1)));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets() {
+        let mut presets = Vec::new();
+        let mut picker = FieldPicker::new();
+        assert_eq!(picker.chosen(), ComicInfoField::SHARED);
+
+        picker.toggle_all();
+        picker.toggle_all();
+        assert!(picker.chosen().is_empty());
+
+        // Ticked out of order, saved in ComicInfo.xml order
+        for field in [ComicInfoField::Translator, ComicInfoField::Year] {
+            let i = ComicInfoField::ALL.iter().position(|f| *f == field);
+            picker.state.select(i);
+            picker.toggle();
+        }
+        picker.start_naming(&presets);
+        picker.name = Some(Input::new(String::from("release")));
+        assert_eq!(picker.save_preset(&mut presets).as_deref(), Some("release"));
+        assert_eq!(
+            presets[0].fields,
+            [ComicInfoField::Year, ComicInfoField::Translator]
+        );
+        assert_eq!(picker.label(&presets), "release");
+
+        // Loading a preset and saving it under the same name overwrites it
+        picker.toggle_all();
+        assert_eq!(picker.label(&presets), "21 fields");
+        picker.next_preset(&presets);
+        assert_eq!(picker.chosen(), presets[0].fields);
+        picker.state.select_first();
+        picker.toggle();
+        picker.name = Some(Input::new(String::from("release")));
+        picker.save_preset(&mut presets);
+        assert_eq!(presets.len(), 1);
+        assert_eq!(presets[0].fields.len(), 3);
+
+        assert_eq!(
+            picker.delete_preset(&mut presets).as_deref(),
+            Some("release")
+        );
+        assert!(presets.is_empty());
+        assert_eq!(picker.chosen().len(), 3);
+    }
+}
@@ -0,0 +1,362 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc,
+    },
+    time::Duration,
+};
+
+use ratatui::widgets::TableState;
+use tokio::sync::Semaphore;
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    ui::list::Chapter,
+    zip_util::{repair_entry_names, update_comic_info},
+};
+
+pub type JobId = u64;
+
+/// Work done on a single archive
+#[derive(Debug, Clone)]
+pub enum JobTask {
+    /// Writes the changed `fields` of `info` into `ComicInfo.xml`, re-read when the task runs
+    WriteComicInfo {
+        chapter: Chapter,
+        info: Box<ComicInfo>,
+        fields: Vec<ComicInfoField>,
+    },
+    /// Rewrites legacy encoded entry names as UTF-8, from the current names to the previewed ones
+    RepairEntryNames {
+        chapter: Chapter,
+        names: HashMap<String, String>,
+    },
+}
+
+impl JobTask {
+    pub fn chapter(&self) -> &Chapter {
+        match self {
+            Self::WriteComicInfo { chapter, .. } | Self::RepairEntryNames { chapter, .. } => {
+                chapter
+            }
+        }
+    }
+
+    /// Blocks until the archive is written
+    pub fn run(&self) -> anyhow::Result<()> {
+        match self {
+            Self::WriteComicInfo {
+                chapter,
+                info,
+                fields,
+            } => update_comic_info(&chapter.path, info, fields),
+            Self::RepairEntryNames { chapter, names } => {
+                let renamed = repair_entry_names(&chapter.path, names)?;
+                debug!("Renamed {renamed} entries in ({})", chapter.path.display());
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JobState {
+    /// Waiting for the jobs before it
+    Queued,
+    Running,
+    /// Stopped before every archive was written
+    Cancelled,
+    Finished,
+}
+
+impl JobState {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Queued => "Queued",
+            Self::Running => "Running",
+            Self::Cancelled => "Cancelled",
+            Self::Finished => "Finished",
+        }
+    }
+}
+
+/// Archive a job failed to write
+#[derive(Debug, Clone)]
+pub struct JobFailure {
+    pub task: JobTask,
+    pub error: String,
+}
+
+/// Batch write in the queue
+#[derive(Debug)]
+pub struct Job {
+    pub id: JobId,
+    pub title: String,
+    pub state: JobState,
+    pub total: usize,
+    pub done: usize,
+    pub failed: Vec<JobFailure>,
+    /// How long it ran, once it's over
+    pub elapsed: Option<Duration>,
+    tasks: Vec<JobTask>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Job {
+    pub fn is_over(&self) -> bool {
+        matches!(self.state, JobState::Cancelled | JobState::Finished)
+    }
+
+    /// Archives whose write is over, failed or not
+    pub fn processed(&self) -> usize {
+        self.done + self.failed.len()
+    }
+
+    /// Counts shown next to the progress bar
+    pub fn progress(&self) -> String {
+        format!(
+            "{}/{} done, {} failed",
+            self.done,
+            self.total,
+            self.failed.len()
+        )
+    }
+}
+
+/// Sent by a running job
+#[derive(Debug)]
+pub enum JobEvent {
+    Started(JobId),
+    /// An archive was written
+    Done(JobId),
+    /// Writing the task at the index failed
+    Failed(JobId, usize, String),
+    /// Every task ran or was skipped after a cancel
+    Ended(JobId, Duration),
+}
+
+/// What a queued job needs to run, see [`crate::chapter_manager::run_job`]
+pub struct JobRunner {
+    pub id: JobId,
+    pub title: String,
+    pub tasks: Vec<JobTask>,
+    pub cancel: Arc<AtomicBool>,
+    /// Lets one job run at a time, in the order they were queued
+    pub queue: Arc<Semaphore>,
+    pub events: mpsc::Sender<JobEvent>,
+}
+
+/// Queue of batch writes running in the background
+pub struct JobsManager {
+    /// Jobs in the order they were queued
+    pub jobs: Vec<Job>,
+
+    /// Channel for receiving the progress of the jobs
+    pub events_rx: mpsc::Receiver<JobEvent>,
+
+    /// State of the jobs table
+    pub state: TableState,
+
+    events_tx: mpsc::Sender<JobEvent>,
+    queue: Arc<Semaphore>,
+    next_id: JobId,
+}
+
+impl JobsManager {
+    pub fn new() -> Self {
+        let (events_tx, events_rx) = mpsc::channel();
+        Self {
+            jobs: Vec::new(),
+            events_rx,
+            state: TableState::default(),
+            events_tx,
+            queue: Arc::new(Semaphore::new(1)),
+            next_id: 1,
+        }
+    }
+
+    /// Queues a job, it runs once the returned runner is awaited
+    pub fn add(&mut self, title: &str, tasks: Vec<JobTask>) -> JobRunner {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        self.jobs.push(Job {
+            id,
+            title: title.to_string(),
+            state: JobState::Queued,
+            total: tasks.len(),
+            done: 0,
+            failed: Vec::new(),
+            elapsed: None,
+            tasks: tasks.clone(),
+            cancel: cancel.clone(),
+        });
+
+        JobRunner {
+            id,
+            title: title.to_string(),
+            tasks,
+            cancel,
+            queue: self.queue.clone(),
+            events: self.events_tx.clone(),
+        }
+    }
+
+    pub fn get(&self, id: JobId) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.id == id)
+    }
+
+    pub fn selected(&self) -> Option<&Job> {
+        self.state.selected().and_then(|i| self.jobs.get(i))
+    }
+
+    /// Job shown in the footer, the running one
+    pub fn running(&self) -> Option<&Job> {
+        self.jobs.iter().find(|job| job.state == JobState::Running)
+    }
+
+    /// Updates the jobs with an event, returns the id of a job that just ended
+    pub fn apply(&mut self, event: JobEvent) -> Option<JobId> {
+        let id = match &event {
+            JobEvent::Started(id)
+            | JobEvent::Done(id)
+            | JobEvent::Failed(id, ..)
+            | JobEvent::Ended(id, _) => *id,
+        };
+        let job = self.jobs.iter_mut().find(|job| job.id == id)?;
+
+        match event {
+            JobEvent::Started(_) => {
+                job.state = JobState::Running;
+                None
+            }
+            JobEvent::Done(_) => {
+                job.done += 1;
+                None
+            }
+            JobEvent::Failed(_, i, error) => {
+                if let Some(task) = job.tasks.get(i) {
+                    job.failed.push(JobFailure {
+                        task: task.clone(),
+                        error,
+                    });
+                }
+                None
+            }
+            JobEvent::Ended(_, elapsed) => {
+                job.elapsed = Some(elapsed);
+                job.state = if job.processed() < job.total {
+                    JobState::Cancelled
+                } else {
+                    JobState::Finished
+                };
+                job.tasks = Vec::new();
+                Some(id)
+            }
+        }
+    }
+
+    /// Stops the selected job before its next archive, returns whether it was still going
+    pub fn cancel_selected(&self) -> bool {
+        match self.selected() {
+            Some(job) if !job.is_over() => {
+                job.cancel.store(true, Ordering::Relaxed);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Title and tasks of a new job writing the failed archives of the selected job again
+    pub fn retry_selected(&self) -> Option<(String, Vec<JobTask>)> {
+        let job = self.selected().filter(|job| job.is_over())?;
+        if job.failed.is_empty() {
+            return None;
+        }
+
+        let tasks = job.failed.iter().map(|f| f.task.clone()).collect();
+        let title = job.title.strip_prefix("Retry: ").unwrap_or(&job.title);
+        Some((format!("Retry: {title}"), tasks))
+    }
+
+    pub fn next(&mut self) {
+        let len = self.jobs.len();
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(next.min(len.saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn task(name: &str) -> JobTask {
+        JobTask::WriteComicInfo {
+            chapter: Chapter {
+                path: PathBuf::from(name),
+                ..Default::default()
+            },
+            info: Box::default(),
+            fields: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_progress_and_retry() {
+        let mut jobs = JobsManager::new();
+        let runner = jobs.add("Save series info", vec![task("a.cbz"), task("b.cbz")]);
+        let id = runner.id;
+        jobs.state.select_first();
+        assert_eq!(jobs.get(id).unwrap().state, JobState::Queued);
+        // Only finished jobs can be retried
+        assert!(jobs.retry_selected().is_none());
+
+        assert_eq!(jobs.apply(JobEvent::Started(id)), None);
+        assert_eq!(jobs.running().unwrap().id, id);
+        jobs.apply(JobEvent::Done(id));
+        jobs.apply(JobEvent::Failed(id, 1, String::from("disk full")));
+        assert_eq!(jobs.apply(JobEvent::Ended(id, Duration::ZERO)), Some(id));
+
+        let job = jobs.get(id).unwrap();
+        assert_eq!(job.state, JobState::Finished);
+        assert_eq!(job.progress(), "1/2 done, 1 failed");
+        assert_eq!(job.failed[0].task.chapter().path, PathBuf::from("b.cbz"));
+        assert!(!jobs.cancel_selected());
+
+        let (title, tasks) = jobs.retry_selected().unwrap();
+        assert_eq!(title, "Retry: Save series info");
+        assert_eq!(tasks.len(), 1);
+
+        // A retry of a retry keeps a single prefix
+        let retry = jobs.add(&title, tasks).id;
+        jobs.state.select(Some(1));
+        jobs.apply(JobEvent::Failed(retry, 0, String::from("disk full")));
+        jobs.apply(JobEvent::Ended(retry, Duration::ZERO));
+        assert_eq!(jobs.retry_selected().unwrap().0, "Retry: Save series info");
+    }
+
+    #[test]
+    fn test_cancel() {
+        let mut jobs = JobsManager::new();
+        let runner = jobs.add("Repair entry names", vec![task("a.cbz"), task("b.cbz")]);
+        jobs.state.select_first();
+
+        assert!(jobs.cancel_selected());
+        assert!(runner.cancel.load(Ordering::Relaxed));
+
+        jobs.apply(JobEvent::Started(runner.id));
+        jobs.apply(JobEvent::Done(runner.id));
+        jobs.apply(JobEvent::Ended(runner.id, Duration::ZERO));
+        assert_eq!(jobs.get(runner.id).unwrap().state, JobState::Cancelled);
+    }
+}
@@ -42,6 +42,7 @@ impl KomgaManager {
     }
 
     /// Util method to build a GET request
+    #[allow(dead_code)]
     fn get(&self, path: &str) -> anyhow::Result<reqwest::RequestBuilder> {
         Ok(self
             .client
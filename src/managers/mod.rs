@@ -1,4 +1,20 @@
+pub mod chapter_view;
 pub mod comic_form;
+pub mod details;
+pub mod fields;
 pub mod image;
+pub mod jobs;
 pub mod komf;
 pub mod komga;
+pub mod pattern;
+pub mod preview;
+pub mod registers;
+pub mod rename;
+pub mod report;
+pub mod resolve;
+pub mod scan;
+pub mod series_json;
+pub mod stats;
+pub mod transform;
+pub mod volume_map;
+pub mod watcher;
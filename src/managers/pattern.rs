@@ -0,0 +1,92 @@
+use ratatui::widgets::TableState;
+use tui_input::Input;
+
+use crate::{
+    config::{FilenamePattern, ParseOverrides},
+    data::{FilenameParser, compile_pattern},
+    ui::list::Chapter,
+};
+
+/// Tests a filename pattern against the chapters of a series
+pub struct PatternTester {
+    /// Typed pattern, a regex or a template
+    pub input: Input,
+
+    /// Error of the typed pattern
+    pub error: Option<String>,
+
+    /// Parsed chapters with the name of the pattern that matched
+    pub results: Vec<(Chapter, Option<String>)>,
+
+    /// Chapters the pattern is tested against
+    pub chapters: Vec<Chapter>,
+
+    /// State of the results table
+    pub state: TableState,
+}
+
+impl PatternTester {
+    pub fn new() -> Self {
+        Self {
+            input: Input::default(),
+            error: None,
+            results: Vec::new(),
+            chapters: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    /// Typed pattern, treated as a regex when it uses named groups or anchors
+    pub fn pattern(&self) -> FilenamePattern {
+        let value = self.input.value().to_string();
+        let is_regex = value.starts_with('^') || value.contains("(?P<") || value.contains("(?<");
+
+        FilenamePattern {
+            name: String::from("typed"),
+            regex: is_regex.then(|| value.clone()),
+            template: (!is_regex).then_some(value),
+        }
+    }
+
+    /// Parses the chapters with the typed pattern, or with `parser` when nothing is typed
+    pub fn test(&mut self, parser: &FilenameParser) {
+        self.error = None;
+
+        let parser = if self.input.value().is_empty() {
+            parser.clone()
+        } else {
+            let pattern = self.pattern();
+            if let Err(e) = compile_pattern(&pattern) {
+                self.error = Some(e.to_string());
+                return;
+            }
+            FilenameParser::new(&[pattern], ParseOverrides::default())
+        };
+
+        self.results = self
+            .chapters
+            .iter()
+            .map(|chapter| {
+                let filename = chapter
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let (parsed, matched) = parser.parse_with_name(chapter.path.clone(), &filename);
+                (parsed, matched.map(ToString::to_string))
+            })
+            .collect();
+    }
+
+    pub fn next(&mut self) {
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state
+            .select(Some(next.min(self.results.len().saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
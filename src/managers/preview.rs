@@ -0,0 +1,162 @@
+use std::sync::mpsc;
+
+use ratatui::widgets::TableState;
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    ui::list::Chapter,
+};
+
+/// Changes a batch write would make to a single chapter
+pub struct ChapterPreview {
+    pub chapter: Chapter,
+    pub old: ComicInfo,
+    pub new: ComicInfo,
+}
+
+impl ChapterPreview {
+    pub fn new(chapter: Chapter, old: ComicInfo, new: ComicInfo) -> Self {
+        Self { chapter, old, new }
+    }
+}
+
+/// A single changed field of a chapter
+pub struct PreviewRow {
+    /// Index into [`BatchPreview::chapters`]
+    pub chapter: usize,
+    pub field: ComicInfoField,
+    /// Whether the change will be written
+    pub apply: bool,
+}
+
+/// Dry-run result of a batch write, one row per chapter × changed field
+pub struct BatchPreview {
+    pub title: String,
+    pub chapters: Vec<ChapterPreview>,
+    pub rows: Vec<PreviewRow>,
+}
+
+impl BatchPreview {
+    pub fn new(title: &str, chapters: Vec<ChapterPreview>) -> Self {
+        let rows = chapters
+            .iter()
+            .enumerate()
+            .flat_map(|(i, preview)| {
+                preview
+                    .old
+                    .diff(&preview.new)
+                    .into_iter()
+                    .map(move |field| PreviewRow {
+                        chapter: i,
+                        field,
+                        apply: true,
+                    })
+            })
+            .collect();
+
+        Self {
+            title: title.to_string(),
+            chapters,
+            rows,
+        }
+    }
+
+    /// Number of chapters with at least one change
+    pub fn changed_chapters(&self) -> usize {
+        let mut chapters: Vec<usize> = self.rows.iter().map(|r| r.chapter).collect();
+        chapters.dedup();
+        chapters.len()
+    }
+
+    /// Number of changes that will be written
+    pub fn applied(&self) -> usize {
+        self.rows.iter().filter(|r| r.apply).count()
+    }
+
+    /// Old and new value of a row
+    pub fn values(&self, row: &PreviewRow) -> (Option<String>, Option<String>) {
+        let preview = &self.chapters[row.chapter];
+        (row.field.get(&preview.old), row.field.get(&preview.new))
+    }
+
+    pub fn toggle_row(&mut self, index: usize) {
+        if let Some(row) = self.rows.get_mut(index) {
+            row.apply = !row.apply;
+        }
+    }
+
+    /// Toggles every row of the chapter under `index`
+    pub fn toggle_chapter(&mut self, index: usize) {
+        let Some(chapter) = self.rows.get(index).map(|r| r.chapter) else {
+            return;
+        };
+        self.toggle_where(|row| row.chapter == chapter);
+    }
+
+    /// Toggles the field under `index` across every chapter
+    pub fn toggle_field(&mut self, index: usize) {
+        let Some(field) = self.rows.get(index).map(|r| r.field) else {
+            return;
+        };
+        self.toggle_where(|row| row.field == field);
+    }
+
+    pub fn toggle_all(&mut self) {
+        self.toggle_where(|_| true);
+    }
+
+    /// Unticks all matching rows if any of them is ticked, ticks them otherwise
+    fn toggle_where<P: Fn(&PreviewRow) -> bool>(&mut self, predicate: P) {
+        let apply = !self.rows.iter().filter(|r| predicate(r)).any(|r| r.apply);
+        for row in self.rows.iter_mut().filter(|r| predicate(r)) {
+            row.apply = apply;
+        }
+    }
+
+    /// Previewed [`ComicInfo`] and accepted fields of every chapter with at least one
+    pub fn into_targets(self) -> Vec<(Chapter, ComicInfo, Vec<ComicInfoField>)> {
+        let mut fields: Vec<Vec<ComicInfoField>> = vec![Vec::new(); self.chapters.len()];
+        for row in self.rows.iter().filter(|r| r.apply) {
+            fields[row.chapter].push(row.field);
+        }
+
+        self.chapters
+            .into_iter()
+            .zip(fields)
+            .filter(|(_, fields)| !fields.is_empty())
+            .map(|(preview, fields)| (preview.chapter, preview.new, fields))
+            .collect()
+    }
+}
+
+pub struct PreviewManager {
+    /// Preview waiting for confirmation
+    pub preview: Option<BatchPreview>,
+
+    /// Channel for receiving a computed preview
+    pub preview_rx: Option<mpsc::Receiver<BatchPreview>>,
+
+    /// State of the preview table
+    pub state: TableState,
+}
+
+impl PreviewManager {
+    pub fn new() -> Self {
+        Self {
+            preview: None,
+            preview_rx: None,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn next(&mut self) {
+        let len = self.preview.as_ref().map_or(0, |p| p.rows.len());
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(next.min(len.saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
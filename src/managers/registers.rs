@@ -0,0 +1,298 @@
+use std::collections::BTreeMap;
+
+use ratatui::widgets::TableState;
+use serde::{Deserialize, Serialize};
+use tui_input::Input;
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    config::MetadataPresets,
+};
+
+/// Register yanks and pastes go to when none is named
+pub const UNNAMED: char = '"';
+
+/// Field values yanked from a chapter form or saved as a preset, an empty value clears the field
+/// on paste
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MetadataClip {
+    pub values: BTreeMap<ComicInfoField, String>,
+}
+
+impl MetadataClip {
+    /// Copies `fields` of `info`
+    pub fn new(info: &ComicInfo, fields: &[ComicInfoField]) -> Self {
+        let values = fields
+            .iter()
+            .map(|field| (*field, field.get(info).unwrap_or_default()))
+            .collect();
+        Self { values }
+    }
+
+    /// Copies every field but the page count, which belongs to the archive
+    pub fn whole(info: &ComicInfo) -> Self {
+        let fields: Vec<ComicInfoField> = ComicInfoField::ALL
+            .into_iter()
+            .filter(|field| *field != ComicInfoField::PageCount)
+            .collect();
+        Self::new(info, &fields)
+    }
+
+    pub fn fields(&self) -> Vec<ComicInfoField> {
+        self.values.keys().copied().collect()
+    }
+
+    /// Info with the clip's values set, values that don't fit their field are logged and left
+    /// empty
+    pub fn to_comic_info(&self) -> ComicInfo {
+        let mut info = ComicInfo::default();
+        for (field, value) in &self.values {
+            if let Err(e) = field.set(&mut info, Some(value)) {
+                warn!("Failed to set {field} to {value}: {e}");
+            }
+        }
+        info
+    }
+
+    /// Short listing of the fields, like `Publisher, Genre +3`
+    pub fn summary(&self) -> String {
+        let names: Vec<&str> = self.values.keys().map(|field| field.name()).collect();
+        match names.len() {
+            0..=3 => names.join(", "),
+            len => format!("{} +{}", names[..2].join(", "), len - 2),
+        }
+    }
+}
+
+/// Row of the registers popup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ClipSource {
+    Register(char),
+    Preset(String),
+}
+
+impl ClipSource {
+    /// Shown in the preview title
+    pub fn label(&self) -> String {
+        match self {
+            Self::Register(name) => format!("Paste register {name}"),
+            Self::Preset(name) => format!("Paste preset {name}"),
+        }
+    }
+}
+
+/// Yank registers like vim's, plus the named presets of the config directory
+pub struct Registers {
+    clips: BTreeMap<char, MetadataClip>,
+
+    /// `"` was pressed, the next key names the register
+    pub naming: bool,
+
+    /// Register of the next yank or paste, the unnamed one when unset
+    pub target: Option<char>,
+
+    pub presets: MetadataPresets,
+
+    /// Name of the preset being saved from the selected row
+    pub name: Option<Input>,
+
+    /// State of the registers table
+    pub state: TableState,
+}
+
+impl Registers {
+    pub fn new(presets: MetadataPresets) -> Self {
+        Self {
+            clips: BTreeMap::new(),
+            naming: false,
+            target: None,
+            presets,
+            name: None,
+            state: TableState::default(),
+        }
+    }
+
+    /// Names the register of the next yank or paste, `false` for keys that can't name one
+    pub fn name_register(&mut self, key: char) -> bool {
+        self.naming = false;
+        if key == UNNAMED || key.is_ascii_lowercase() || key.is_ascii_digit() {
+            self.target = Some(key);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Yanks into the named register, which the unnamed one also gets like in vim
+    pub fn yank(&mut self, clip: MetadataClip) -> char {
+        let name = self.target.take().unwrap_or(UNNAMED);
+        if name != UNNAMED {
+            self.clips.insert(UNNAMED, clip.clone());
+        }
+        self.clips.insert(name, clip);
+        name
+    }
+
+    /// Clip of the named register for a paste
+    pub fn paste(&mut self) -> Option<(ClipSource, MetadataClip)> {
+        let name = self.target.take().unwrap_or(UNNAMED);
+        let clip = self.clips.get(&name)?.clone();
+        Some((ClipSource::Register(name), clip))
+    }
+
+    /// Registers then presets, in the order of the table
+    pub fn rows(&self) -> Vec<(ClipSource, &MetadataClip)> {
+        let registers = self
+            .clips
+            .iter()
+            .map(|(name, clip)| (ClipSource::Register(*name), clip));
+        let presets = self
+            .presets
+            .presets
+            .iter()
+            .map(|(name, clip)| (ClipSource::Preset(name.clone()), clip));
+        registers.chain(presets).collect()
+    }
+
+    pub fn selected(&self) -> Option<(ClipSource, MetadataClip)> {
+        let i = self.state.selected()?;
+        self.rows()
+            .into_iter()
+            .nth(i)
+            .map(|(source, clip)| (source, clip.clone()))
+    }
+
+    /// Starts naming a preset, filled with the selected one so it can be overwritten
+    pub fn start_naming(&mut self) {
+        let name = match self.selected() {
+            Some((ClipSource::Preset(name), _)) => name,
+            Some((ClipSource::Register(_), _)) => String::new(),
+            None => return,
+        };
+        self.name = Some(Input::new(name));
+    }
+
+    /// Saves the selected row under the typed name, replacing a preset of the same name
+    pub fn save_preset(&mut self) -> Option<String> {
+        let name = self.name.take()?.value().trim().to_string();
+        let (_, clip) = self.selected()?;
+        if name.is_empty() {
+            return None;
+        }
+        self.presets.presets.insert(name.clone(), clip);
+        Some(name)
+    }
+
+    /// Removes the selected preset, registers stay
+    pub fn delete_preset(&mut self) -> Option<String> {
+        let Some((ClipSource::Preset(name), _)) = self.selected() else {
+            return None;
+        };
+        self.presets.presets.remove(&name);
+        let last = self.rows().len().saturating_sub(1);
+        self.state
+            .select(self.state.selected().map(|i| i.min(last)));
+        Some(name)
+    }
+
+    pub fn next(&mut self) {
+        let last = self.rows().len().saturating_sub(1);
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(next.min(last)));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(publisher: &str) -> ComicInfo {
+        ComicInfo {
+            title: String::from("The Hawk"),
+            publisher: Some(publisher.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_yank_and_paste() {
+        let mut registers = Registers::new(MetadataPresets::default());
+        assert_eq!(registers.paste(), None);
+
+        let whole = MetadataClip::whole(&info("Viz"));
+        assert_eq!(whole.values.len(), ComicInfoField::ALL.len() - 1);
+        assert_eq!(whole.values[&ComicInfoField::Title], "The Hawk");
+
+        let fields = [ComicInfoField::Publisher, ComicInfoField::Genre];
+        assert_eq!(
+            registers.yank(MetadataClip::new(&info("Viz"), &fields)),
+            UNNAMED
+        );
+
+        assert!(registers.name_register('a'));
+        registers.yank(MetadataClip::new(&info("Dark Horse"), &fields));
+        assert!(!registers.name_register('!'));
+
+        // The unnamed register has the last yank
+        let (source, clip) = registers.paste().unwrap();
+        assert_eq!(source, ClipSource::Register(UNNAMED));
+        assert_eq!(
+            clip.to_comic_info().publisher.as_deref(),
+            Some("Dark Horse")
+        );
+        assert_eq!(clip.fields(), fields);
+
+        // Unset fields are pasted as cleared, fields not yanked are kept
+        let mut target = ComicInfo {
+            genre: Some(String::from("Action")),
+            ..info("Viz")
+        };
+        target.update_fields(&clip.to_comic_info(), &clip.fields());
+        assert_eq!(target.genre, None);
+        assert_eq!(target.title, "The Hawk");
+
+        registers.name_register('b');
+        assert_eq!(registers.paste(), None);
+        assert_eq!(registers.target, None);
+    }
+
+    #[test]
+    fn test_presets() {
+        let mut registers = Registers::new(MetadataPresets::default());
+        registers.yank(MetadataClip::new(
+            &info("Viz Media"),
+            &[ComicInfoField::Publisher, ComicInfoField::AgeRating],
+        ));
+        registers.state.select_first();
+        registers.start_naming();
+        assert_eq!(registers.name.as_ref().map(Input::value), Some(""));
+        registers.name = Some(Input::new(String::from("Viz Media shonen")));
+        assert_eq!(registers.save_preset().as_deref(), Some("Viz Media shonen"));
+
+        let rows = registers.rows();
+        assert_eq!(
+            rows[1].0,
+            ClipSource::Preset(String::from("Viz Media shonen"))
+        );
+
+        let written = toml::to_string_pretty(&registers.presets).unwrap();
+        assert!(written.contains(r#"Publisher = "Viz Media""#));
+        let read: MetadataPresets = toml::from_str(&written).unwrap();
+        assert_eq!(read, registers.presets);
+
+        registers.state.select(Some(1));
+        assert_eq!(
+            registers.delete_preset().as_deref(),
+            Some("Viz Media shonen")
+        );
+        assert!(registers.presets.presets.is_empty());
+        assert_eq!(registers.state.selected(), Some(0));
+        assert_eq!(registers.delete_preset(), None);
+    }
+}
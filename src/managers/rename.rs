@@ -0,0 +1,154 @@
+use std::{path::PathBuf, sync::mpsc};
+
+use ratatui::widgets::TableState;
+
+use crate::ui::list::Chapter;
+
+/// What a [`RenamePreview`] renames
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenameKind {
+    /// Entries inside of the archives
+    EntryNames,
+    /// The archives themselves
+    Files,
+}
+
+/// A single old → new name
+pub struct RenameRow {
+    pub chapter: Chapter,
+    pub old: String,
+    pub new: String,
+    /// Whether the new name clashes with another one
+    pub conflict: bool,
+    /// Whether the new name comes from a guessed encoding that may be wrong
+    pub ambiguous: bool,
+    /// Whether the rename is done on apply
+    pub apply: bool,
+}
+
+/// Names that would change, shown for confirmation before renaming
+pub struct RenamePreview {
+    pub title: String,
+    pub kind: RenameKind,
+    pub rows: Vec<RenameRow>,
+}
+
+impl RenamePreview {
+    /// Rows without conflicts or guessed names start ticked
+    pub fn new(title: &str, kind: RenameKind, mut rows: Vec<RenameRow>) -> Self {
+        for row in &mut rows {
+            row.apply = !row.conflict && !row.ambiguous;
+        }
+
+        Self {
+            title: title.to_string(),
+            kind,
+            rows,
+        }
+    }
+
+    pub fn conflicts(&self) -> usize {
+        self.rows.iter().filter(|r| r.conflict).count()
+    }
+
+    pub fn ambiguous(&self) -> usize {
+        self.rows.iter().filter(|r| r.ambiguous).count()
+    }
+
+    /// Conflicting rows that are still ticked, they block the apply
+    pub fn ticked_conflicts(&self) -> usize {
+        self.rows.iter().filter(|r| r.apply && r.conflict).count()
+    }
+
+    pub fn toggle_row(&mut self, index: usize) {
+        if let Some(row) = self.rows.get_mut(index) {
+            row.apply = !row.apply;
+        }
+    }
+
+    /// Chapters with at least one rename
+    pub fn chapters(&self) -> Vec<Chapter> {
+        let mut chapters: Vec<Chapter> = Vec::new();
+        for row in &self.rows {
+            if !chapters.iter().any(|c| c.path == row.chapter.path) {
+                chapters.push(row.chapter.clone());
+            }
+        }
+        chapters
+    }
+}
+
+/// Archives renamed in a series, as old and new paths
+pub struct RenamedFiles {
+    pub series: PathBuf,
+    pub paths: Vec<(PathBuf, PathBuf)>,
+}
+
+pub struct RenameManager {
+    /// Preview waiting for confirmation
+    pub preview: Option<RenamePreview>,
+
+    /// Channel for receiving a computed preview
+    pub preview_rx: Option<mpsc::Receiver<RenamePreview>>,
+
+    /// Channel for receiving the files that were renamed
+    pub renamed_rx: Option<mpsc::Receiver<RenamedFiles>>,
+
+    /// State of the preview table
+    pub state: TableState,
+}
+
+impl RenameManager {
+    pub fn new() -> Self {
+        Self {
+            preview: None,
+            preview_rx: None,
+            renamed_rx: None,
+            state: TableState::default(),
+        }
+    }
+
+    pub fn next(&mut self) {
+        let len = self.preview.as_ref().map_or(0, |p| p.rows.len());
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(next.min(len.saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(new: &str, conflict: bool, ambiguous: bool) -> RenameRow {
+        RenameRow {
+            chapter: Chapter::default(),
+            old: String::from("old.cbz"),
+            new: new.to_string(),
+            conflict,
+            ambiguous,
+            apply: false,
+        }
+    }
+
+    #[test]
+    fn test_conflicts_start_unticked() {
+        let rows = vec![
+            row("a.cbz", false, false),
+            row("b.cbz", true, false),
+            row("c.cbz", false, true),
+        ];
+        let mut preview = RenamePreview::new("Rename files", RenameKind::Files, rows);
+
+        let ticked: Vec<bool> = preview.rows.iter().map(|r| r.apply).collect();
+        assert_eq!(ticked, [true, false, false]);
+        assert_eq!(preview.ticked_conflicts(), 0);
+
+        preview.toggle_row(1);
+        assert_eq!(preview.ticked_conflicts(), 1);
+    }
+}
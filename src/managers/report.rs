@@ -0,0 +1,266 @@
+use std::{collections::BTreeMap, sync::mpsc};
+
+use ratatui::widgets::TableState;
+
+use crate::ui::list::{Chapter, ChapterKind};
+
+/// Problem found in the numbering of a series
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReportIssue {
+    /// Chapters missing between two numbers, both ends included
+    Gap { from: i64, to: i64 },
+    /// Same number in multiple files
+    Duplicate {
+        number: String,
+        files: Vec<String>,
+        translators: Vec<String>,
+    },
+    /// Regular chapter without a number
+    Unnumbered { file: String },
+    /// Chapter placed in an earlier volume than the chapter before it
+    VolumeBackwards {
+        file: String,
+        volume: u32,
+        previous: u32,
+    },
+}
+
+impl ReportIssue {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Gap { .. } => "Missing",
+            Self::Duplicate { .. } => "Duplicate",
+            Self::Unnumbered { .. } => "No number",
+            Self::VolumeBackwards { .. } => "Volume order",
+        }
+    }
+
+    pub fn detail(&self) -> String {
+        match self {
+            Self::Gap { from, to } if from == to => format!("Chapter {from}"),
+            Self::Gap { from, to } => format!("Chapters {from}-{to}"),
+            Self::Duplicate {
+                number,
+                files,
+                translators,
+            } => {
+                let translators = if translators.is_empty() {
+                    String::new()
+                } else {
+                    format!(" by {}", translators.join(" / "))
+                };
+                format!("{number} in {} files{translators}", files.len())
+            }
+            Self::Unnumbered { file } => file.clone(),
+            Self::VolumeBackwards {
+                file,
+                volume,
+                previous,
+            } => format!("{file} is in volume {volume} after volume {previous}"),
+        }
+    }
+}
+
+/// Numbering problems of a single series
+#[derive(Debug, Clone)]
+pub struct SeriesReport {
+    pub series: String,
+    pub chapters: usize,
+    pub issues: Vec<ReportIssue>,
+}
+
+fn file_name(chapter: &Chapter) -> String {
+    chapter
+        .path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Finds gaps, duplicates, unnumbered chapters and volumes going backwards
+#[allow(clippy::cast_possible_truncation)]
+pub fn analyze(series: &str, chapters: &[Chapter]) -> SeriesReport {
+    let mut issues = Vec::new();
+
+    let mut numbered: Vec<&Chapter> = chapters
+        .iter()
+        .filter(|c| c.chapter.is_some() && c.kind == ChapterKind::Regular)
+        .collect();
+    // By number alone, the list sorts by volume first which would hide volumes going backwards
+    numbered.sort_by(|a, b| {
+        let number = |c: &Chapter| c.chapter.unwrap_or_default();
+        number(a).total_cmp(&number(b)).then_with(|| a.cmp(b))
+    });
+
+    // Gaps between whole chapter numbers, ranges cover everything up to their end
+    let mut covered: Option<i64> = None;
+    for chapter in &numbered {
+        let start = chapter.chapter.unwrap_or_default().floor() as i64;
+        let end = chapter.chapter_end.map_or(start, |end| end.floor() as i64);
+        let expected = covered.map_or(1, |covered| covered + 1);
+        if start > expected {
+            issues.push(ReportIssue::Gap {
+                from: expected,
+                to: start - 1,
+            });
+        }
+        covered = Some(covered.map_or(end, |covered| covered.max(end)));
+    }
+
+    // Same number, or the same volume for volume archives
+    let mut by_number: BTreeMap<String, Vec<&Chapter>> = BTreeMap::new();
+    for chapter in chapters {
+        let number = match (chapter.chapter, chapter.chapter_end) {
+            _ if chapter.is_volume() => format!("Volume {}", chapter.volume.unwrap_or_default()),
+            (Some(start), Some(end)) => format!("Chapter {start}-{end}"),
+            (Some(start), None) => format!("Chapter {start}"),
+            (None, _) => continue,
+        };
+        by_number.entry(number).or_default().push(chapter);
+    }
+    for (number, duplicates) in by_number.into_iter().filter(|(_, c)| c.len() > 1) {
+        let mut translators: Vec<String> = duplicates
+            .iter()
+            .map(|c| c.translators.join(", "))
+            .filter(|t| !t.is_empty())
+            .collect();
+        translators.dedup();
+
+        issues.push(ReportIssue::Duplicate {
+            number,
+            files: duplicates.iter().map(|c| file_name(c)).collect(),
+            translators,
+        });
+    }
+
+    issues.extend(
+        chapters
+            .iter()
+            .filter(|c| c.kind == ChapterKind::Regular && c.chapter.is_none() && !c.is_volume())
+            .map(|c| ReportIssue::Unnumbered { file: file_name(c) }),
+    );
+
+    let mut previous: Option<u32> = None;
+    for chapter in &numbered {
+        let Some(volume) = chapter.volume else {
+            continue;
+        };
+        match previous {
+            Some(previous) if volume < previous => {
+                issues.push(ReportIssue::VolumeBackwards {
+                    file: file_name(chapter),
+                    volume,
+                    previous,
+                });
+            }
+            _ => previous = Some(volume),
+        }
+    }
+
+    SeriesReport {
+        series: series.to_string(),
+        chapters: chapters.len(),
+        issues,
+    }
+}
+
+pub struct ReportManager {
+    /// Reports of the analyzed series
+    pub reports: Vec<SeriesReport>,
+
+    /// Whether the report covers the whole library
+    pub library: bool,
+
+    /// Channel for receiving the computed reports
+    pub reports_rx: Option<mpsc::Receiver<Vec<SeriesReport>>>,
+
+    /// State of the report table
+    pub state: TableState,
+}
+
+impl ReportManager {
+    pub fn new() -> Self {
+        Self {
+            reports: Vec::new(),
+            library: false,
+            reports_rx: None,
+            state: TableState::default(),
+        }
+    }
+
+    /// Number of issues in all reports
+    pub fn issues(&self) -> usize {
+        self.reports.iter().map(|r| r.issues.len()).sum()
+    }
+
+    pub fn next(&mut self) {
+        let len = self.issues();
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state.select(Some(next.min(len.saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn chapter(name: &str, volume: Option<u32>, number: Option<f32>, group: &str) -> Chapter {
+        Chapter {
+            path: PathBuf::from(name),
+            volume,
+            chapter: number,
+            translators: vec![group.to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_analyze() {
+        let chapters = vec![
+            chapter("a.cbz", Some(1), Some(1.0), "alpha"),
+            chapter("b.cbz", Some(1), Some(2.0), "alpha"),
+            chapter("c.cbz", Some(2), Some(2.0), "beta"),
+            chapter("d.cbz", Some(2), Some(3.0), "alpha"),
+            chapter("e.cbz", Some(1), Some(6.0), "alpha"),
+            chapter("f.cbz", None, None, "alpha"),
+        ];
+
+        let report = analyze("Series", &chapters);
+        assert_eq!(
+            report.issues,
+            vec![
+                ReportIssue::Gap { from: 4, to: 5 },
+                ReportIssue::Duplicate {
+                    number: "Chapter 2".into(),
+                    files: vec!["b.cbz".into(), "c.cbz".into()],
+                    translators: vec!["alpha".into(), "beta".into()],
+                },
+                ReportIssue::Unnumbered {
+                    file: "f.cbz".into()
+                },
+                ReportIssue::VolumeBackwards {
+                    file: "e.cbz".into(),
+                    volume: 1,
+                    previous: 2,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ranges_cover_gaps() {
+        let mut range = chapter("a.cbz", None, Some(1.0), "");
+        range.chapter_end = Some(5.0);
+        let chapters = vec![range, chapter("b.cbz", None, Some(6.0), "")];
+
+        assert!(analyze("Series", &chapters).issues.is_empty());
+    }
+}
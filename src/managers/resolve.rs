@@ -0,0 +1,103 @@
+use ratatui::widgets::TableState;
+use tui_input::Input;
+
+use crate::{
+    data::{FilenameParser, parse_filename},
+    ui::list::{Chapter, ParseReading},
+};
+
+/// Where a reading offered by the [`ParseResolver`] comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingSource {
+    /// Typed by hand
+    Typed,
+    /// What the parser picked, choosing it removes the override
+    Parsed,
+    /// Another way to read the filename
+    Alternative,
+}
+
+/// Picks or types the right reading of an ambiguous filename
+pub struct ParseResolver {
+    /// Chapter being resolved
+    pub chapter: Option<Chapter>,
+
+    /// Reading typed like a filename, "Vol.3 Ch.12 - Title"
+    pub input: Input,
+
+    /// Readings to choose from with their confidence
+    pub readings: Vec<(ReadingSource, ParseReading, u8)>,
+
+    /// State of the readings table
+    pub state: TableState,
+}
+
+impl ParseResolver {
+    pub fn new() -> Self {
+        Self {
+            chapter: None,
+            input: Input::default(),
+            readings: Vec::new(),
+            state: TableState::default(),
+        }
+    }
+
+    /// Starts resolving a chapter
+    pub fn open(&mut self, chapter: Chapter, parser: &FilenameParser) {
+        self.chapter = Some(chapter);
+        self.input.reset();
+        self.update(parser);
+        self.state.select_first();
+    }
+
+    /// Rebuilds the readings after the input changed
+    pub fn update(&mut self, parser: &FilenameParser) {
+        let Some(chapter) = &self.chapter else {
+            return;
+        };
+        let filename = chapter
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let (parsed, _) = parser.parse_with_name(chapter.path.clone(), &filename);
+
+        self.readings.clear();
+        if !self.input.value().trim().is_empty() {
+            let typed = parse_filename(chapter.path.clone(), self.input.value());
+            self.readings
+                .push((ReadingSource::Typed, ParseReading::of(&typed), 100));
+        }
+        self.readings.push((
+            ReadingSource::Parsed,
+            ParseReading::of(&parsed),
+            parsed.confidence,
+        ));
+        self.readings.extend(
+            parsed
+                .alternatives
+                .into_iter()
+                .map(|reading| (ReadingSource::Alternative, reading, 0)),
+        );
+
+        let selected = self.state.selected().unwrap_or_default();
+        self.state
+            .select(Some(selected.min(self.readings.len().saturating_sub(1))));
+    }
+
+    pub fn selected(&self) -> Option<&(ReadingSource, ParseReading, u8)> {
+        self.readings.get(self.state.selected().unwrap_or_default())
+    }
+
+    pub fn next(&mut self) {
+        let next = self.state.selected().map_or(0, |i| i + 1);
+        self.state
+            .select(Some(next.min(self.readings.len().saturating_sub(1))));
+    }
+
+    pub fn prev(&mut self) {
+        let prev = self.state.selected().unwrap_or_default().saturating_sub(1);
+        self.state.select(Some(prev));
+    }
+}
@@ -0,0 +1,46 @@
+use std::sync::mpsc;
+
+use crate::data::ScanEvent;
+
+/// Progress of the library scan running in the background
+pub struct LibraryScan {
+    /// Events of the running scan
+    pub scan_rx: Option<mpsc::Receiver<ScanEvent>>,
+
+    /// Folders looked at so far
+    pub folders: usize,
+
+    /// Series found so far
+    pub series: usize,
+
+    /// Paths that couldn't be read
+    pub warnings: Vec<String>,
+}
+
+impl LibraryScan {
+    pub fn new() -> Self {
+        Self {
+            scan_rx: None,
+            folders: 0,
+            series: 0,
+            warnings: Vec::new(),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.scan_rx.is_some()
+    }
+
+    /// Progress shown in the footer
+    pub fn progress(&self) -> String {
+        let unreadable = if self.warnings.is_empty() {
+            String::new()
+        } else {
+            format!(", {} unreadable", self.warnings.len())
+        };
+        format!(
+            "Scanning library: {} series in {} folders{unreadable}",
+            self.series, self.folders
+        )
+    }
+}
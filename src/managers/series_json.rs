@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use tui_input::Input;
+
+use crate::{
+    series_json::{SeriesJson, SeriesMetadata},
+    ui::list::Series,
+};
+
+/// Edits `series.json` of a series
+pub struct SeriesJsonEditor {
+    /// Folder of the edited series
+    pub folder: Option<PathBuf>,
+
+    /// File as it was read, keeps the keys that aren't edited
+    pub json: SeriesJson,
+
+    /// Whether the file exists yet
+    pub exists: bool,
+
+    /// Label and input of every edited field
+    pub fields: Vec<(&'static str, Input)>,
+
+    /// Index of the active field
+    pub active: usize,
+
+    /// Why the inputs can't be saved
+    pub error: Option<String>,
+}
+
+impl SeriesJsonEditor {
+    pub fn new() -> Self {
+        Self {
+            folder: None,
+            json: SeriesJson::default(),
+            exists: false,
+            fields: Vec::new(),
+            active: 0,
+            error: None,
+        }
+    }
+
+    /// Starts editing a series, new files are filled from the folder name
+    pub fn open(&mut self, series: &Series) {
+        self.exists = series.series_json.is_some();
+        self.json = series
+            .series_json
+            .clone()
+            .unwrap_or_else(|| SeriesJson::from_folder(&series.folder));
+        self.folder = Some(series.path.clone());
+        self.active = 0;
+        self.error = None;
+
+        let metadata = &self.json.metadata;
+        let text = |value: &Option<String>| Input::new(value.clone().unwrap_or_default());
+        self.fields = vec![
+            ("Name", Input::new(metadata.name.clone())),
+            ("Publisher", text(&metadata.publisher)),
+            (
+                "Year",
+                Input::new(metadata.year.map(|y| y.to_string()).unwrap_or_default()),
+            ),
+            ("Status", text(&metadata.status)),
+            ("Description", text(&metadata.description_text)),
+            (
+                "Total issues",
+                Input::new(
+                    metadata
+                        .total_issues
+                        .map(|n| n.to_string())
+                        .unwrap_or_default(),
+                ),
+            ),
+            ("Book type", text(&metadata.booktype)),
+            ("Age rating", text(&metadata.age_rating)),
+        ];
+    }
+
+    pub fn next(&mut self) {
+        self.active = (self.active + 1) % self.fields.len().max(1);
+    }
+
+    pub fn prev(&mut self) {
+        self.active = self
+            .active
+            .checked_sub(1)
+            .unwrap_or(self.fields.len().saturating_sub(1));
+    }
+
+    pub fn active_input_mut(&mut self) -> Option<&mut Input> {
+        self.fields.get_mut(self.active).map(|(_, input)| input)
+    }
+
+    /// File with the inputs applied, or why they're invalid
+    pub fn to_series_json(&self) -> Result<SeriesJson, String> {
+        let value = |i: usize| {
+            let value = self.fields[i].1.value().trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        let year = value(2)
+            .map(|v| v.parse().map_err(|_| format!("Year must be a number: {v}")))
+            .transpose()?;
+        let total_issues = value(5)
+            .map(|v| {
+                v.parse()
+                    .map_err(|_| format!("Total issues must be a number: {v}"))
+            })
+            .transpose()?;
+
+        Ok(SeriesJson {
+            metadata: SeriesMetadata {
+                name: value(0).unwrap_or_default(),
+                publisher: value(1),
+                year,
+                status: value(3),
+                description_text: value(4),
+                total_issues,
+                booktype: value(6),
+                age_rating: value(7),
+                ..self.json.metadata.clone()
+            },
+            volume_map: self.json.volume_map.clone(),
+            extra: self.json.extra.clone(),
+        })
+    }
+}
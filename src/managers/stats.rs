@@ -0,0 +1,285 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::mpsc,
+};
+
+use serde::Serialize;
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    ui::list::Chapter,
+};
+
+/// How many entries the top lists keep
+const TOP: usize = 10;
+
+/// What is known about one chapter
+pub struct ChapterStats {
+    pub chapter: Chapter,
+    pub info: ComicInfo,
+    /// Size of the archive in bytes
+    pub size: u64,
+    pub pages: u32,
+}
+
+/// Chapters of a field that are empty
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldCoverage {
+    pub field: String,
+    pub missing: usize,
+    /// Share of chapters that lack the field, `0.0` to `100.0`
+    pub missing_percent: f64,
+}
+
+/// Size and tagging of a single series
+#[derive(Debug, Clone, Serialize)]
+pub struct SeriesStats {
+    pub series: String,
+    pub chapters: usize,
+    pub size: u64,
+    /// Share of empty fields over all its chapters, `0.0` to `100.0`
+    pub missing_percent: f64,
+}
+
+/// Numbers about the whole library
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LibraryStats {
+    pub series: usize,
+    pub chapters: usize,
+    pub total_size: u64,
+    pub total_pages: u64,
+    /// Chapters per `Format`, volumes and chapters when it's empty
+    pub formats: BTreeMap<String, usize>,
+    /// Chapters per language
+    pub languages: BTreeMap<String, usize>,
+    /// Coverage per `ComicInfo` field, the least covered first
+    pub coverage: Vec<FieldCoverage>,
+    pub top_translators: Vec<(String, usize)>,
+    pub largest_series: Vec<SeriesStats>,
+    pub most_incomplete_series: Vec<SeriesStats>,
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percent(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        part as f64 * 100.0 / total as f64
+    }
+}
+
+/// Keeps the `TOP` largest counts, ties by name
+fn top(counts: HashMap<String, usize>) -> Vec<(String, usize)> {
+    let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    counts.truncate(TOP);
+    counts
+}
+
+impl LibraryStats {
+    /// Numbers of the series with their chapters, data in `ComicInfo` wins over the filename
+    pub fn compute(series: &[(String, Vec<ChapterStats>)]) -> Self {
+        let mut stats = Self {
+            series: series.len(),
+            ..Default::default()
+        };
+        let mut missing: HashMap<ComicInfoField, usize> = HashMap::new();
+        let mut translators: HashMap<String, usize> = HashMap::new();
+        let mut per_series = Vec::new();
+
+        for (name, chapters) in series {
+            let mut series_missing = 0;
+            let mut series_size = 0;
+
+            for chapter in chapters {
+                stats.chapters += 1;
+                stats.total_size += chapter.size;
+                stats.total_pages += u64::from(chapter.pages);
+                series_size += chapter.size;
+
+                let info = &chapter.info;
+                let format = info.format.clone().unwrap_or_else(|| {
+                    String::from(if chapter.chapter.is_volume() {
+                        "Volume"
+                    } else {
+                        "Chapter"
+                    })
+                });
+                *stats.formats.entry(format).or_default() += 1;
+
+                let language = info
+                    .language_iso
+                    .clone()
+                    .or_else(|| chapter.chapter.language.clone())
+                    .unwrap_or_else(|| String::from("unknown"));
+                *stats.languages.entry(language).or_default() += 1;
+
+                let names: Vec<String> = match &info.translator {
+                    Some(translator) if !translator.trim().is_empty() => translator
+                        .split(',')
+                        .map(|t| t.trim().to_string())
+                        .filter(|t| !t.is_empty())
+                        .collect(),
+                    _ => chapter.chapter.translators.clone(),
+                };
+                for name in names {
+                    *translators.entry(name).or_default() += 1;
+                }
+
+                for field in ComicInfoField::ALL {
+                    if field.get(info).is_none() {
+                        *missing.entry(field).or_default() += 1;
+                        series_missing += 1;
+                    }
+                }
+            }
+
+            per_series.push(SeriesStats {
+                series: name.clone(),
+                chapters: chapters.len(),
+                size: series_size,
+                missing_percent: percent(
+                    series_missing,
+                    chapters.len() * ComicInfoField::ALL.len(),
+                ),
+            });
+        }
+
+        stats.coverage = ComicInfoField::ALL
+            .into_iter()
+            .map(|field| {
+                let missing = missing.get(&field).copied().unwrap_or_default();
+                FieldCoverage {
+                    field: field.name().to_string(),
+                    missing,
+                    missing_percent: percent(missing, stats.chapters),
+                }
+            })
+            .collect();
+        stats.coverage.sort_by_key(|c| std::cmp::Reverse(c.missing));
+
+        stats.top_translators = top(translators);
+
+        per_series.sort_by_key(|s| std::cmp::Reverse(s.size));
+        stats.largest_series = per_series.iter().take(TOP).cloned().collect();
+
+        per_series.sort_by(|a, b| b.missing_percent.total_cmp(&a.missing_percent));
+        stats.most_incomplete_series = per_series.into_iter().take(TOP).collect();
+
+        stats
+    }
+}
+
+/// Human readable size like `1.5 GiB`
+#[allow(clippy::cast_precision_loss)]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+pub struct StatsManager {
+    /// Computed statistics
+    pub stats: Option<LibraryStats>,
+
+    /// Channel for receiving statistics
+    pub stats_rx: Option<mpsc::Receiver<LibraryStats>>,
+
+    /// Scroll of the dashboard
+    pub scroll: u16,
+}
+
+impl StatsManager {
+    pub fn new() -> Self {
+        Self {
+            stats: None,
+            stats_rx: None,
+            scroll: 0,
+        }
+    }
+
+    pub fn next(&mut self) {
+        self.scroll = self.scroll.saturating_add(1);
+    }
+
+    pub fn prev(&mut self) {
+        self.scroll = self.scroll.saturating_sub(1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    fn chapter(number: f32, info: ComicInfo, size: u64) -> ChapterStats {
+        ChapterStats {
+            chapter: Chapter {
+                path: PathBuf::from(format!("{number}.cbz")),
+                chapter: Some(number),
+                translators: vec![String::from("Scans")],
+                ..Default::default()
+            },
+            info,
+            size,
+            pages: 20,
+        }
+    }
+
+    #[test]
+    fn test_compute() {
+        let tagged = ComicInfo {
+            writer: Some(String::from("Kentaro Miura")),
+            translator: Some(String::from("Dark Horse, Fans")),
+            language_iso: Some(String::from("en")),
+            ..Default::default()
+        };
+        let series = vec![
+            (
+                String::from("Berserk"),
+                vec![chapter(1.0, tagged.clone(), 100), chapter(2.0, tagged, 100)],
+            ),
+            (
+                String::from("Untagged"),
+                vec![chapter(1.0, ComicInfo::default(), 500)],
+            ),
+        ];
+
+        let stats = LibraryStats::compute(&series);
+        assert_eq!(stats.series, 2);
+        assert_eq!(stats.chapters, 3);
+        assert_eq!(stats.total_size, 700);
+        assert_eq!(stats.total_pages, 60);
+        assert_eq!(stats.formats["Chapter"], 3);
+        assert_eq!(stats.languages["en"], 2);
+        assert_eq!(stats.languages["unknown"], 1);
+
+        let writer = stats.coverage.iter().find(|c| c.field == "Writer").unwrap();
+        assert_eq!(writer.missing, 1);
+        assert!((writer.missing_percent - 100.0 / 3.0).abs() < 0.01);
+
+        // ComicInfo translators win over the ones in the filename
+        assert_eq!(stats.top_translators[0], (String::from("Dark Horse"), 2));
+        assert!(stats.top_translators.contains(&(String::from("Scans"), 1)));
+
+        assert_eq!(stats.largest_series[0].series, "Untagged");
+        assert_eq!(stats.most_incomplete_series[0].series, "Untagged");
+    }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
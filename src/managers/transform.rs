@@ -0,0 +1,106 @@
+use tui_input::Input;
+
+use crate::{
+    comic_info::ComicInfoField,
+    transform::{Transform, TransformKind},
+};
+
+/// Row of the transform dialog with the focus
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformFocus {
+    Field,
+    Kind,
+    /// Text input of the transform by index
+    Input(usize),
+}
+
+/// Dialog building a [`Transform`] of the selected chapters
+pub struct TransformDialog {
+    /// Index in [`ComicInfoField::ALL`]
+    pub field: usize,
+
+    /// Index in [`TransformKind::ALL`]
+    pub kind: usize,
+
+    /// Texts of the transform, only the ones its kind needs are shown
+    pub inputs: [Input; 2],
+
+    pub focus: TransformFocus,
+
+    /// Why the typed transform can't be built
+    pub error: Option<String>,
+}
+
+impl TransformDialog {
+    pub fn new() -> Self {
+        Self {
+            field: 0,
+            kind: 0,
+            inputs: [Input::default(), Input::default()],
+            focus: TransformFocus::Field,
+            error: None,
+        }
+    }
+
+    pub fn field(&self) -> ComicInfoField {
+        ComicInfoField::ALL[self.field]
+    }
+
+    pub fn kind(&self) -> TransformKind {
+        TransformKind::ALL[self.kind]
+    }
+
+    /// Rows that can have the focus, in order
+    fn rows(&self) -> Vec<TransformFocus> {
+        let inputs = (0..self.kind().inputs().len()).map(TransformFocus::Input);
+        [TransformFocus::Field, TransformFocus::Kind]
+            .into_iter()
+            .chain(inputs)
+            .collect()
+    }
+
+    pub fn next(&mut self) {
+        let rows = self.rows();
+        let i = rows.iter().position(|r| *r == self.focus).unwrap_or(0);
+        self.focus = rows[(i + 1) % rows.len()];
+    }
+
+    pub fn prev(&mut self) {
+        let rows = self.rows();
+        let i = rows.iter().position(|r| *r == self.focus).unwrap_or(0);
+        self.focus = rows[(i + rows.len() - 1) % rows.len()];
+    }
+
+    /// Cycles the field or the kind under the focus
+    pub fn cycle(&mut self, forward: bool) {
+        let step = |value: usize, len: usize| {
+            if forward {
+                (value + 1) % len
+            } else {
+                (value + len - 1) % len
+            }
+        };
+        match self.focus {
+            TransformFocus::Field => self.field = step(self.field, ComicInfoField::ALL.len()),
+            TransformFocus::Kind => self.kind = step(self.kind, TransformKind::ALL.len()),
+            TransformFocus::Input(_) => {}
+        }
+        self.error = None;
+    }
+
+    pub fn active_input_mut(&mut self) -> Option<&mut Input> {
+        match self.focus {
+            TransformFocus::Input(i) => self.inputs.get_mut(i),
+            TransformFocus::Field | TransformFocus::Kind => None,
+        }
+    }
+
+    pub fn transform(&self) -> anyhow::Result<Transform> {
+        Transform::new(
+            self.field(),
+            self.kind(),
+            self.inputs[0].value(),
+            self.inputs[1].value(),
+        )
+    }
+}
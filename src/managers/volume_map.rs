@@ -0,0 +1,52 @@
+use std::path::PathBuf;
+
+use tui_input::Input;
+
+use crate::{ui::list::Series, volume_map::VolumeMap};
+
+/// Edits the chapter to volume table of a series
+pub struct VolumeMapEditor {
+    /// Folder of the edited series
+    pub folder: Option<PathBuf>,
+
+    /// Table like `1: 1-8, 2: 9-17`
+    pub input: Input,
+
+    /// Why the table can't be saved
+    pub error: Option<String>,
+}
+
+impl VolumeMapEditor {
+    pub fn new() -> Self {
+        Self {
+            folder: None,
+            input: Input::default(),
+            error: None,
+        }
+    }
+
+    /// Starts editing the table saved in `series.json`
+    pub fn open(&mut self, series: &Series) {
+        let text = series.volume_map().map(ToString::to_string);
+        self.input = Input::new(text.unwrap_or_default());
+        self.folder = Some(series.path.clone());
+        self.error = None;
+    }
+
+    /// Replaces the typed table with the volumes the chapters already have
+    pub fn import(&mut self, series: &Series) {
+        let map = VolumeMap::from_chapters(&series.chapters.items_state);
+        if map.is_empty() {
+            self.error = Some(String::from("No chapter has both a volume and a number"));
+            return;
+        }
+        self.input = Input::new(map.to_string());
+        self.error = None;
+    }
+
+    /// Typed table, `None` when it's cleared
+    pub fn map(&self) -> anyhow::Result<Option<VolumeMap>> {
+        let map: VolumeMap = self.input.value().parse()?;
+        Ok((!map.is_empty()).then_some(map))
+    }
+}
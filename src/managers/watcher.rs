@@ -0,0 +1,77 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::config::LibraryRoot;
+
+/// Quiet time after the last change before the lists are updated, so copies can finish
+const DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Watches the library roots and batches up changed paths
+pub struct LibraryWatcher {
+    /// Kept alive for as long as the roots should be watched
+    _watcher: RecommendedWatcher,
+
+    /// Changed paths from the watcher thread
+    events_rx: mpsc::Receiver<PathBuf>,
+
+    /// Paths changed since the last batch
+    pending: HashSet<PathBuf>,
+
+    /// Time of the last change
+    last_event: Option<Instant>,
+}
+
+impl LibraryWatcher {
+    /// Starts watching the roots, roots that can't be watched are logged and skipped
+    pub fn new(roots: &[LibraryRoot]) -> notify::Result<Self> {
+        let (events_tx, events_rx) = mpsc::channel();
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<Event>| match event {
+                Ok(event) if !event.kind.is_access() => {
+                    for path in event.paths {
+                        let _ = events_tx.send(path);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("File watcher error: {e}"),
+            })?;
+
+        for root in roots {
+            if let Err(e) = watcher.watch(Path::new(&root.path), RecursiveMode::Recursive) {
+                warn!("Failed to watch library ({}): {e}", root.path);
+            }
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events_rx,
+            pending: HashSet::new(),
+            last_event: None,
+        })
+    }
+
+    /// Changed paths once nothing changed for a moment, hidden files are left out
+    pub fn poll(&mut self) -> Option<Vec<PathBuf>> {
+        while let Ok(path) = self.events_rx.try_recv() {
+            let hidden = path
+                .file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with('.'));
+            if !hidden {
+                self.pending.insert(path);
+                self.last_event = Some(Instant::now());
+            }
+        }
+
+        if self.last_event?.elapsed() < DEBOUNCE {
+            return None;
+        }
+        self.last_event = None;
+        Some(self.pending.drain().collect())
+    }
+}
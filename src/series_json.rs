@@ -0,0 +1,236 @@
+//! Mylar `series.json` in series folders, also read by Komga
+
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoAgeRating},
+    ui::list::SeriesFolder,
+    volume_map::VolumeMap,
+};
+
+/// Name of the file in the series folder
+pub const FILE_NAME: &str = "series.json";
+
+/// Contents of `series.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SeriesJson {
+    pub metadata: SeriesMetadata,
+
+    /// Volumes of the chapters by number, not a Mylar key so Komga ignores it
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_volume_map",
+        deserialize_with = "deserialize_volume_map"
+    )]
+    pub volume_map: Option<VolumeMap>,
+
+    /// Other top level keys, kept as they were
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Series data in `series.json`, empty values are written as `null` like Mylar does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeriesMetadata {
+    #[serde(rename = "type", default = "series_type")]
+    pub kind: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub publisher: Option<String>,
+    #[serde(default)]
+    pub year: Option<u16>,
+    /// `Continuing` or `Ended`
+    #[serde(default)]
+    pub status: Option<String>,
+    #[serde(default)]
+    pub description_text: Option<String>,
+    #[serde(default)]
+    pub total_issues: Option<u32>,
+    /// `Print`, `OneShot`, `TPB`, `GN` or `HC`
+    #[serde(default)]
+    pub booktype: Option<String>,
+    #[serde(default)]
+    pub age_rating: Option<String>,
+
+    /// Keys not edited here, like `comicid` or `imprint`
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[allow(clippy::ref_option)]
+fn serialize_volume_map<S: serde::Serializer>(
+    map: &Option<VolumeMap>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    map.as_ref().map(ToString::to_string).serialize(serializer)
+}
+
+/// A map that doesn't parse is dropped so the rest of the file still loads
+fn deserialize_volume_map<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<VolumeMap>, D::Error> {
+    let Some(text) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    Ok(text
+        .parse()
+        .inspect_err(|e| warn!("Invalid volume map in series.json ({text}): {e}"))
+        .ok())
+}
+
+fn series_type() -> String {
+    String::from("comicSeries")
+}
+
+impl Default for SeriesMetadata {
+    fn default() -> Self {
+        Self {
+            kind: series_type(),
+            name: String::new(),
+            publisher: None,
+            year: None,
+            status: None,
+            description_text: None,
+            total_issues: None,
+            booktype: None,
+            age_rating: None,
+            extra: Map::new(),
+        }
+    }
+}
+
+impl SeriesJson {
+    /// Reads `series.json` of a series folder, `None` when there's none
+    pub fn read(folder: &Path) -> anyhow::Result<Option<Self>> {
+        let path = folder.join(FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+    }
+
+    pub fn write(&self, folder: &Path) -> anyhow::Result<()> {
+        fs::write(folder.join(FILE_NAME), serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// New file filled with what the folder name tells
+    pub fn from_folder(folder: &SeriesFolder) -> Self {
+        Self {
+            metadata: SeriesMetadata {
+                name: folder.title.clone(),
+                publisher: folder.publisher.clone(),
+                year: folder.year,
+                ..Default::default()
+            },
+            volume_map: None,
+            extra: Map::new(),
+        }
+    }
+
+    /// Overwrites the shared fields of `info` that are set here
+    pub fn apply(&self, info: &mut ComicInfo) {
+        let metadata = &self.metadata;
+        let set = |value: &Option<String>| value.clone().filter(|v| !v.trim().is_empty());
+
+        if !metadata.name.trim().is_empty() {
+            info.series.clone_from(&metadata.name);
+        }
+        if let Some(publisher) = set(&metadata.publisher) {
+            info.publisher = Some(publisher);
+        }
+        if let Some(description) = set(&metadata.description_text) {
+            info.summary = Some(description);
+        }
+        if metadata.total_issues.is_some() {
+            info.count = metadata.total_issues;
+        }
+        if let Some(rating) = set(&metadata.age_rating) {
+            match ComicInfoAgeRating::from(rating.as_str()) {
+                ComicInfoAgeRating::Unknown => {}
+                rating => info.age_rating = rating,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MYLAR: &str = r#"{
+        "version": "1.0.2",
+        "metadata": {
+            "type": "comicSeries",
+            "publisher": "Dark Horse",
+            "imprint": null,
+            "name": "Berserk",
+            "comicid": 30002,
+            "year": 1989,
+            "description_text": "Guts.",
+            "description_formatted": null,
+            "volume": null,
+            "booktype": "Print",
+            "age_rating": "Mature 17+",
+            "collects": [],
+            "ComicImage": null,
+            "total_issues": 41,
+            "publication_run": "1989 - Present",
+            "status": "Continuing"
+        }
+    }"#;
+
+    #[test]
+    fn test_unknown_keys_are_kept() {
+        let json: SeriesJson = serde_json::from_str(MYLAR).unwrap();
+        assert_eq!(json.metadata.name, "Berserk");
+        assert_eq!(json.metadata.total_issues, Some(41));
+        assert_eq!(json.metadata.extra["comicid"], 30002);
+        assert_eq!(json.extra["version"], "1.0.2");
+
+        let written = serde_json::to_string(&json).unwrap();
+        let read: SeriesJson = serde_json::from_str(&written).unwrap();
+        assert_eq!(read, json);
+    }
+
+    #[test]
+    fn test_apply_shared_fields() {
+        let json: SeriesJson = serde_json::from_str(MYLAR).unwrap();
+        let mut info = ComicInfo {
+            series: String::from("berserk"),
+            writer: Some(String::from("Kentaro Miura")),
+            ..Default::default()
+        };
+        json.apply(&mut info);
+
+        assert_eq!(info.series, "Berserk");
+        assert_eq!(info.publisher.as_deref(), Some("Dark Horse"));
+        assert_eq!(info.summary.as_deref(), Some("Guts."));
+        assert_eq!(info.count, Some(41));
+        assert_eq!(info.age_rating.to_string(), "Mature 17+");
+        assert_eq!(info.writer.as_deref(), Some("Kentaro Miura"));
+    }
+
+    #[test]
+    fn test_volume_map() {
+        let mut json: SeriesJson = serde_json::from_str(MYLAR).unwrap();
+        assert_eq!(json.volume_map, None);
+        assert!(!serde_json::to_string(&json).unwrap().contains("volume_map"));
+
+        json.volume_map = Some("1: 1-8, 2: 9-17".parse().unwrap());
+        let written = serde_json::to_string(&json).unwrap();
+        assert!(written.contains(r#""volume_map":"1: 1-8, 2: 9-17""#));
+        assert_eq!(serde_json::from_str::<SeriesJson>(&written).unwrap(), json);
+
+        // A broken map doesn't lose the rest of the file
+        let broken = written.replace("9-17", "17-9");
+        let read: SeriesJson = serde_json::from_str(&broken).unwrap();
+        assert_eq!(read.volume_map, None);
+        assert_eq!(read.metadata.name, "Berserk");
+    }
+}
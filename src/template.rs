@@ -0,0 +1,321 @@
+//! Filename templates like `<Vol.{volume:02} >Ch.{number:04}< - {title}>.cbz`
+//!
+//! `{name}` is replaced with a value, `{name:04}` pads numbers with zeros. Text between `<` and
+//! `>` is only kept when every placeholder inside of it has a value.
+//!
+//! The same templates fill metadata fields with [`render_text`], like `{title} ({translator})`.
+
+use anyhow::bail;
+
+use crate::{comic_info::ComicInfo, ui::list::Chapter};
+
+/// Names of the placeholders understood in templates
+pub const PLACEHOLDERS: [&str; 9] = [
+    "series",
+    "volume",
+    "number",
+    "title",
+    "lang",
+    "translator",
+    "year",
+    "format",
+    "filename",
+];
+
+/// Values a template is rendered with
+#[derive(Debug, Default, Clone)]
+pub struct TemplateValues {
+    pub series: Option<String>,
+    pub volume: Option<u32>,
+    pub number: Option<f32>,
+    pub number_end: Option<f32>,
+    pub title: Option<String>,
+    pub lang: Option<String>,
+    pub translator: Option<String>,
+    pub year: Option<u16>,
+    pub format: Option<String>,
+    pub filename: Option<String>,
+}
+
+impl TemplateValues {
+    /// Values from the `ComicInfo.xml`, falling back to what was parsed from the filename
+    pub fn new(chapter: &Chapter, info: &ComicInfo) -> Self {
+        let non_empty = |v: &str| (!v.trim().is_empty()).then(|| v.trim().to_string());
+        let translators = chapter.translators.join(", ");
+
+        Self {
+            series: non_empty(&info.series).or_else(|| chapter.series.clone()),
+            volume: info.volume.or(chapter.volume),
+            number: info.number.or(chapter.chapter),
+            number_end: chapter.chapter_end,
+            title: non_empty(&info.title).or_else(|| chapter.title.clone()),
+            lang: info
+                .language_iso
+                .as_deref()
+                .and_then(non_empty)
+                .or_else(|| chapter.language.clone()),
+            translator: info
+                .translator
+                .as_deref()
+                .and_then(non_empty)
+                .or_else(|| non_empty(&translators)),
+            year: info.year.or(chapter.year),
+            format: info
+                .format
+                .as_deref()
+                .and_then(non_empty)
+                .or_else(|| chapter.kind.format().map(String::from)),
+            filename: chapter
+                .path
+                .file_stem()
+                .map(|stem| stem.to_string_lossy().to_string()),
+        }
+    }
+
+    fn get(&self, name: &str, width: usize) -> Option<String> {
+        match name {
+            "series" => self.series.clone(),
+            "volume" => self.volume.map(|v| format!("{v:0width$}")),
+            "number" => self.number.map(|start| match self.number_end {
+                Some(end) => format!("{}-{}", pad_number(start, width), pad_number(end, width)),
+                None => pad_number(start, width),
+            }),
+            "title" => self.title.clone(),
+            "lang" => self.lang.clone(),
+            "translator" => self.translator.clone(),
+            "year" => self.year.map(|v| format!("{v:0width$}")),
+            "format" => self.format.clone(),
+            "filename" => self.filename.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// Pads the integer part of a number: `12.5` with width 4 is `0012.5`
+fn pad_number(number: f32, width: usize) -> String {
+    let number = number.to_string();
+    let (int, frac) = number.split_once('.').unwrap_or((&number, ""));
+    if frac.is_empty() {
+        format!("{int:0>width$}")
+    } else {
+        format!("{int:0>width$}.{frac}")
+    }
+}
+
+/// Replaces characters that are not allowed in filenames
+pub fn sanitize(value: &str) -> String {
+    let replaced: String = value
+        .chars()
+        .filter(|c| !c.is_control())
+        .filter_map(|c| match c {
+            '/' | '\\' | '|' => Some('-'),
+            ':' => Some(' '),
+            '*' | '?' | '"' | '<' | '>' => None,
+            c => Some(c),
+        })
+        .collect();
+
+    replaced.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Checks that a template is well formed and only uses known placeholders
+pub fn validate(template: &str) -> anyhow::Result<()> {
+    render(template, &TemplateValues::default()).map(|_| ())
+}
+
+/// Renders a template into a filename ending with `.cbz`
+pub fn render(template: &str, values: &TemplateValues) -> anyhow::Result<String> {
+    let name = render_sections(template, values, sanitize)?;
+    let name = name.split_whitespace().collect::<Vec<_>>().join(" ");
+    let stem = name
+        .strip_suffix(".cbz")
+        .unwrap_or(&name)
+        .trim_end_matches(['.', ' ', '-'])
+        .trim_start();
+    Ok(format!("{stem}.cbz"))
+}
+
+/// Renders a template into the value of a metadata field, values are kept as they are
+pub fn render_text(template: &str, values: &TemplateValues) -> anyhow::Result<String> {
+    let text = render_sections(template, values, |v| v.trim().to_string())?;
+
+    // Line breaks are kept for summaries
+    let lines: Vec<String> = text
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect();
+    Ok(lines.join("\n").trim().to_string())
+}
+
+/// Renders the sections of a template, `clean` is applied to every value
+fn render_sections(
+    template: &str,
+    values: &TemplateValues,
+    clean: fn(&str) -> String,
+) -> anyhow::Result<String> {
+    let mut out = String::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find('<') {
+        out.push_str(&render_section(&rest[..start], values, clean)?.0);
+
+        let Some(end) = rest[start..].find('>') else {
+            bail!("Unclosed section in template: {}", &rest[start..]);
+        };
+        let (section, complete) = render_section(&rest[start + 1..start + end], values, clean)?;
+        if complete {
+            out.push_str(&section);
+        }
+        rest = &rest[start + end + 1..];
+    }
+    if rest.contains('>') {
+        bail!("Unopened section in template: {rest}");
+    }
+    out.push_str(&render_section(rest, values, clean)?.0);
+
+    Ok(out)
+}
+
+/// Renders text with placeholders, also returning whether every placeholder had a value.
+/// Brackets around a placeholder without a value, like `({lang})`, are dropped with it.
+fn render_section(
+    text: &str,
+    values: &TemplateValues,
+    clean: fn(&str) -> String,
+) -> anyhow::Result<(String, bool)> {
+    let mut out = String::new();
+    let mut complete = true;
+    let mut rest = text;
+
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+
+        let Some(end) = rest[start..].find('}') else {
+            bail!("Unclosed placeholder in template: {}", &rest[start..]);
+        };
+        let placeholder = &rest[start + 1..start + end];
+        let (name, width) = match placeholder.split_once(':') {
+            Some((name, width)) => (name, width.parse()?),
+            None => (placeholder, 0),
+        };
+        if !PLACEHOLDERS.contains(&name) {
+            bail!("Unknown placeholder {{{name}}}");
+        }
+
+        rest = &rest[start + end + 1..];
+        if let Some(value) = values
+            .get(name, width)
+            .map(|v| clean(&v))
+            .filter(|v| !v.is_empty())
+        {
+            out.push_str(&value);
+            continue;
+        }
+
+        complete = false;
+        let close = match out.chars().last() {
+            Some('(') => Some(')'),
+            Some('[') => Some(']'),
+            _ => None,
+        };
+        if let Some(close) = close
+            && let Some(after) = rest.strip_prefix(close)
+        {
+            out.pop();
+            rest = after;
+        }
+    }
+    out.push_str(rest);
+
+    Ok((out, complete))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> TemplateValues {
+        TemplateValues {
+            volume: Some(3),
+            number: Some(12.5),
+            title: Some("Who? What: Why/How".into()),
+            lang: Some("en".into()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_padding_and_sanitizing() {
+        let name = render("Vol.{volume:02} Ch.{number:04} - {title}", &values()).unwrap();
+        assert_eq!(name, "Vol.03 Ch.0012.5 - Who What Why-How.cbz");
+    }
+
+    #[test]
+    fn test_conditional_sections() {
+        let template = "<Vol.{volume:02} >Ch.{number:03}< ({lang})>< [{translator}]>.cbz";
+        assert_eq!(
+            render(template, &values()).unwrap(),
+            "Vol.03 Ch.012.5 (en).cbz"
+        );
+
+        let values = TemplateValues {
+            number: Some(1.0),
+            number_end: Some(5.0),
+            ..Default::default()
+        };
+        assert_eq!(render(template, &values).unwrap(), "Ch.001-005.cbz");
+    }
+
+    #[test]
+    fn test_missing_values_outside_sections() {
+        let template = "Vol.{volume:02} Ch.{number:04} - {title} ({lang}) [{translator}].cbz";
+        let values = TemplateValues {
+            volume: Some(1),
+            number: Some(7.0),
+            ..Default::default()
+        };
+        assert_eq!(render(template, &values).unwrap(), "Vol.01 Ch.0007.cbz");
+
+        // Brackets in the values and the template text are kept
+        let values = TemplateValues {
+            number: Some(7.0),
+            title: Some("Sign () [] Here".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render("Ch.{number} - {title} [] ({lang}).cbz", &values).unwrap(),
+            "Ch.7 - Sign () [] Here [].cbz"
+        );
+        assert_eq!(
+            render_text("{title} ({translator})", &values).unwrap(),
+            "Sign () [] Here"
+        );
+    }
+
+    #[test]
+    fn test_metadata_text() {
+        let template = "{title}< ({translator})>";
+        assert_eq!(
+            render_text(template, &values()).unwrap(),
+            "Who? What: Why/How"
+        );
+
+        let values = TemplateValues {
+            title: Some("Side Story".into()),
+            translator: Some("Scans".into()),
+            ..Default::default()
+        };
+        assert_eq!(
+            render_text(template, &values).unwrap(),
+            "Side Story (Scans)"
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(validate("{chapter}").is_err());
+        assert!(validate("<Vol.{volume}").is_err());
+        assert!(validate("Ch.{number:x}").is_err());
+        assert!(validate("Ch.{number").is_err());
+    }
+}
@@ -0,0 +1,264 @@
+//! Batch transforms of a single metadata field, like removing `Ch. 12 - ` from every title with
+//! the regex `^Ch\. [\d.]+ - ` replaced by nothing
+
+use regex::Regex;
+
+use crate::{
+    comic_info::{ComicInfo, ComicInfoField},
+    template::{self, TemplateValues},
+    ui::list::Chapter,
+};
+
+/// Kinds of transforms, in the order the dialog cycles through them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransformKind {
+    Replace,
+    Regex,
+    Upper,
+    Lower,
+    TitleCase,
+    Trim,
+    Prefix,
+    Suffix,
+    Template,
+}
+
+impl TransformKind {
+    pub const ALL: [Self; 9] = [
+        Self::Replace,
+        Self::Regex,
+        Self::Upper,
+        Self::Lower,
+        Self::TitleCase,
+        Self::Trim,
+        Self::Prefix,
+        Self::Suffix,
+        Self::Template,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Replace => "Find & replace",
+            Self::Regex => "Regex replace",
+            Self::Upper => "UPPER CASE",
+            Self::Lower => "lower case",
+            Self::TitleCase => "Title Case",
+            Self::Trim => "Trim spaces",
+            Self::Prefix => "Add prefix",
+            Self::Suffix => "Add suffix",
+            Self::Template => "Template",
+        }
+    }
+
+    /// Labels of the texts the transform needs
+    pub fn inputs(self) -> &'static [&'static str] {
+        match self {
+            Self::Replace => &["Find", "Replace with"],
+            Self::Regex => &["Regex", "Replace with ($1, ${name})"],
+            Self::Upper | Self::Lower | Self::TitleCase | Self::Trim => &[],
+            Self::Prefix => &["Prefix"],
+            Self::Suffix => &["Suffix"],
+            Self::Template => &["Template like {title} ({translator})"],
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operation {
+    Replace { find: String, with: String },
+    Regex { regex: Regex, with: String },
+    Upper,
+    Lower,
+    TitleCase,
+    Trim,
+    Prefix(String),
+    Suffix(String),
+    Template(String),
+}
+
+/// A transform of one field, checked when it's built
+#[derive(Debug, Clone)]
+pub struct Transform {
+    pub field: ComicInfoField,
+    pub kind: TransformKind,
+    operation: Operation,
+}
+
+fn title_case(value: &str) -> String {
+    value
+        .split(' ')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first
+                    .to_uppercase()
+                    .chain(chars.flat_map(char::to_lowercase))
+                    .collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl Transform {
+    /// Builds a transform from the texts typed for its inputs
+    pub fn new(
+        field: ComicInfoField,
+        kind: TransformKind,
+        text: &str,
+        with: &str,
+    ) -> anyhow::Result<Self> {
+        let operation = match kind {
+            TransformKind::Replace => {
+                if text.is_empty() {
+                    anyhow::bail!("Nothing to find");
+                }
+                Operation::Replace {
+                    find: text.to_string(),
+                    with: with.to_string(),
+                }
+            }
+            TransformKind::Regex => Operation::Regex {
+                regex: Regex::new(text)?,
+                with: with.to_string(),
+            },
+            TransformKind::Upper => Operation::Upper,
+            TransformKind::Lower => Operation::Lower,
+            TransformKind::TitleCase => Operation::TitleCase,
+            TransformKind::Trim => Operation::Trim,
+            TransformKind::Prefix => Operation::Prefix(text.to_string()),
+            TransformKind::Suffix => Operation::Suffix(text.to_string()),
+            TransformKind::Template => {
+                template::render_text(text, &TemplateValues::default())?;
+                Operation::Template(text.to_string())
+            }
+        };
+
+        Ok(Self {
+            field,
+            kind,
+            operation,
+        })
+    }
+
+    /// Shown in the preview title
+    pub fn label(&self) -> String {
+        let detail = match &self.operation {
+            Operation::Replace { find, with } => format!(" \"{find}\" → \"{with}\""),
+            Operation::Regex { regex, with } => format!(" /{}/ → \"{with}\"", regex.as_str()),
+            Operation::Prefix(text) | Operation::Suffix(text) | Operation::Template(text) => {
+                format!(" \"{text}\"")
+            }
+            Operation::Upper | Operation::Lower | Operation::TitleCase | Operation::Trim => {
+                String::new()
+            }
+        };
+        format!("{}: {}{detail}", self.field, self.kind.name())
+    }
+
+    /// New value of the field, empty fields are only filled by templates
+    pub fn value(&self, chapter: &Chapter, info: &ComicInfo) -> Option<String> {
+        let current = self.field.get(info);
+        let value = match &self.operation {
+            Operation::Template(text) => {
+                template::render_text(text, &TemplateValues::new(chapter, info)).ok()?
+            }
+            Operation::Replace { find, with } => current?.replace(find.as_str(), with),
+            Operation::Regex { regex, with } => {
+                regex.replace_all(&current?, with.as_str()).to_string()
+            }
+            Operation::Upper => current?.to_uppercase(),
+            Operation::Lower => current?.to_lowercase(),
+            Operation::TitleCase => title_case(&current?),
+            Operation::Trim => current?.split_whitespace().collect::<Vec<_>>().join(" "),
+            Operation::Prefix(text) => format!("{text}{}", current?),
+            Operation::Suffix(text) => format!("{}{text}", current?),
+        };
+        Some(value)
+    }
+
+    /// `info` with the field transformed, values that don't fit the field are logged and skipped
+    pub fn apply(&self, chapter: &Chapter, mut info: ComicInfo) -> ComicInfo {
+        let Some(value) = self.value(chapter, &info) else {
+            return info;
+        };
+
+        if let Err(e) = self.field.set(&mut info, Some(&value)) {
+            warn!(
+                "Failed to set {} to {value} ({}): {e}",
+                self.field,
+                chapter.path.display()
+            );
+        }
+        info
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(title: &str) -> ComicInfo {
+        ComicInfo {
+            title: title.to_string(),
+            translator: Some(String::from("Scans")),
+            ..Default::default()
+        }
+    }
+
+    fn transform(kind: TransformKind, text: &str, with: &str) -> Transform {
+        Transform::new(ComicInfoField::Title, kind, text, with).unwrap()
+    }
+
+    #[test]
+    fn test_transforms() {
+        let chapter = Chapter::default();
+        let apply =
+            |transform: Transform, title: &str| transform.apply(&chapter, info(title)).title;
+
+        let strip = transform(TransformKind::Regex, r"^Ch\. [\d.]+ - ", "");
+        assert_eq!(apply(strip.clone(), "Ch. 12 - The Hawk"), "The Hawk");
+        assert_eq!(apply(strip, "The Hawk"), "The Hawk");
+
+        let swap = transform(TransformKind::Regex, r"(\w+) (\w+)", "$2 $1");
+        assert_eq!(apply(swap, "Hawk The"), "The Hawk");
+
+        let replace = transform(TransformKind::Replace, "Hawk", "Falcon");
+        assert_eq!(apply(replace, "The Hawk"), "The Falcon");
+
+        let case = transform(TransformKind::TitleCase, "", "");
+        assert_eq!(apply(case, "the BLACK swordsman"), "The Black Swordsman");
+
+        let trim = transform(TransformKind::Trim, "", "");
+        assert_eq!(apply(trim, "  The   Hawk "), "The Hawk");
+
+        let suffix = transform(TransformKind::Suffix, " (Part 1)", "");
+        assert_eq!(apply(suffix, "The Hawk"), "The Hawk (Part 1)");
+
+        let template = transform(TransformKind::Template, "{title}< [{translator}]>", "");
+        assert_eq!(apply(template, "The Hawk"), "The Hawk [Scans]");
+    }
+
+    #[test]
+    fn test_fields_and_errors() {
+        let chapter = Chapter::default();
+        assert!(Transform::new(ComicInfoField::Title, TransformKind::Regex, "(", "").is_err());
+        assert!(Transform::new(ComicInfoField::Title, TransformKind::Replace, "", "x").is_err());
+        assert!(Transform::new(ComicInfoField::Title, TransformKind::Template, "{x}", "").is_err());
+
+        // Empty fields stay empty
+        let prefix =
+            Transform::new(ComicInfoField::Summary, TransformKind::Prefix, "A ", "").unwrap();
+        assert_eq!(prefix.apply(&chapter, info("")).summary, None);
+
+        // Numbers that no longer parse are kept
+        let volume =
+            Transform::new(ComicInfoField::Volume, TransformKind::Suffix, "x", "").unwrap();
+        let numbered = ComicInfo {
+            volume: Some(3),
+            ..Default::default()
+        };
+        assert_eq!(volume.apply(&chapter, numbered).volume, Some(3));
+    }
+}
@@ -1,6 +1,6 @@
 use ratatui::{
     Frame,
-    layout::{Constraint, Layout},
+    layout::{Constraint, Flex, Layout, Rect},
     style::{
         Color, Modifier, Style,
         palette::tailwind::{CYAN, NEUTRAL},
@@ -8,7 +8,7 @@ use ratatui::{
     widgets::{Scrollbar, ScrollbarOrientation},
 };
 
-use crate::ui::{App, Tab};
+use crate::ui::{App, Popup, Tab};
 
 pub const SELECTED_STYLE: Style = Style::new()
     .fg(CYAN.c600)
@@ -21,6 +21,17 @@ pub const SCROLLBAR: Scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRi
     .track_symbol(None)
     .end_symbol(None);
 
+/// Centered area taking `percent_x` × `percent_y` of `area`
+pub fn popup_area(area: Rect, percent_x: u16, percent_y: u16) -> Rect {
+    let [area] = Layout::vertical([Constraint::Percentage(percent_y)])
+        .flex(Flex::Center)
+        .areas(area);
+    let [area] = Layout::horizontal([Constraint::Percentage(percent_x)])
+        .flex(Flex::Center)
+        .areas(area);
+    area
+}
+
 impl App {
     pub fn render(&mut self, frame: &mut Frame) {
         let [header_area, main_area, footer_area] = Layout::vertical([
@@ -62,5 +73,22 @@ impl App {
         if self.show_help {
             App::render_help(main_area, frame);
         }
+
+        match self.popup {
+            Some(Popup::Preview) => self.render_preview(main_area, frame),
+            Some(Popup::Rename) => self.render_rename(main_area, frame),
+            Some(Popup::Pattern) => self.render_pattern(main_area, frame),
+            Some(Popup::Report) => self.render_report(main_area, frame),
+            Some(Popup::Resolve) => self.render_resolve(main_area, frame),
+            Some(Popup::SeriesJson) => self.render_series_json(main_area, frame),
+            Some(Popup::Stats) => self.render_stats(main_area, frame),
+            Some(Popup::Filter) => self.render_filter(main_area, frame),
+            Some(Popup::Jobs) => self.render_jobs(main_area, frame),
+            Some(Popup::Fields) => self.render_fields(main_area, frame),
+            Some(Popup::Transform) => self.render_transform(main_area, frame),
+            Some(Popup::VolumeMap) => self.render_volume_map(main_area, frame),
+            Some(Popup::Registers) => self.render_registers(main_area, frame),
+            None => {}
+        }
     }
 }
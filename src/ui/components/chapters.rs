@@ -9,11 +9,59 @@ use ratatui::{
     widgets::{Block, Borders, HighlightSpacing, List, ListItem},
 };
 
-use crate::ui::{
-    App, Tab,
-    app::{SCROLLBAR, SELECTED_STYLE, SELECTED_YELLOW},
+use crate::{
+    lint::LintIssue,
+    managers::{chapter_view::ChapterColumn, details::DetailsManager},
+    ui::{
+        App, Tab,
+        app::{SCROLLBAR, SELECTED_STYLE, SELECTED_YELLOW},
+        list::Chapter,
+    },
 };
 
+/// Row of a chapter with the configured columns and a marker for lint issues
+fn chapter_line(
+    chapter: &Chapter,
+    selected: bool,
+    columns: &[ChapterColumn],
+    details: &DetailsManager,
+) -> Line<'static> {
+    let selected = if selected { "▌" } else { " " };
+    let chapter_details = details.get(&chapter.path);
+    let cells: Vec<String> = columns
+        .iter()
+        .enumerate()
+        .map(|(n, column)| column.cell(chapter, chapter_details, n + 1 == columns.len()))
+        .collect();
+    let mut line = Line::raw(format!("{selected}{}", cells.join("  ")));
+
+    let issues = details.issues(&chapter.path);
+    if !issues.is_empty() {
+        line.push_span(Span::styled(
+            format!(" ⚑{}", issues.len()),
+            Style::default().fg(Color::LightRed),
+        ));
+    }
+    line
+}
+
+/// Issues of a chapter as the bottom title of the list
+fn lint_line(issues: &[LintIssue]) -> Option<Line<'static>> {
+    if issues.is_empty() {
+        return None;
+    }
+
+    let issues: Vec<String> = issues
+        .iter()
+        .map(|issue| format!("{}: {}", issue.rule, issue.message))
+        .collect();
+    let line = Line::styled(
+        format!(" {} ", issues.join(" · ")),
+        Style::default().fg(Color::LightRed),
+    );
+    Some(line.left_aligned())
+}
+
 impl App {
     pub fn render_chapters(&mut self, area: Rect, f: &mut Frame) {
         let mut title = Span::raw("Chapters");
@@ -41,22 +89,34 @@ impl App {
             selected_count,
             series.chapters.items_state.len(),
         )));
+        if let Some(sort) = self.chapter_view.sort {
+            title.push_span(Span::raw(format!("[{}] ", sort.label())).fg(Color::Cyan));
+        }
 
-        let block = Block::new()
+        let mut block = Block::new()
             .title(title)
             .borders(Borders::ALL)
             .border_set(symbols::border::ROUNDED);
 
+        // Issues of the chapter under the cursor
+        let current = series
+            .chapters
+            .state
+            .selected()
+            .and_then(|i| series.chapters.items.get(i));
+        if let Some(issues) = current.and_then(|c| lint_line(self.details_manager.issues(&c.path)))
+        {
+            block = block.title_bottom(issues);
+        }
+
         let mut counts: HashMap<(Option<u32>, Option<u32>), usize> = HashMap::new();
 
-        if series.name != self.config.komga.oneshots_dir {
-            for c in &series.chapters.items {
-                let chapter_bits = c.chapter.map(f32::to_bits);
-                let volume_key = c.volume;
+        for c in &series.chapters.items {
+            let chapter_bits = c.chapter.map(f32::to_bits);
+            let volume_key = c.volume;
 
-                let key = (volume_key, chapter_bits);
-                *counts.entry(key).or_insert(0) += 1;
-            }
+            let key = (volume_key, chapter_bits);
+            *counts.entry(key).or_insert(0) += 1;
         }
 
         let duplicates: HashSet<(Option<u32>, Option<u32>)> = counts
@@ -65,14 +125,27 @@ impl App {
             .map(|(k, _)| k)
             .collect();
 
+        let details = &self.details_manager;
+        let columns = &self.config.chapter_columns;
         let items: Vec<ListItem> = series
             .chapters
             .items
             .iter()
             .enumerate()
             .map(|(i, chapter)| {
-                let mut item =
-                    ListItem::new(chapter.get_title(series.chapters.selected.contains(&i)));
+                let line = chapter_line(
+                    chapter,
+                    series.chapters.selected.contains(&i),
+                    columns,
+                    details,
+                );
+                let mut item = ListItem::new(line);
+
+                if chapter.is_uncertain() {
+                    item = item.style(Style::default().fg(Color::Magenta));
+                } else if chapter.overridden {
+                    item = item.style(Style::default().add_modifier(Modifier::ITALIC));
+                }
 
                 if series.chapters.selected.contains(&i) {
                     item = item.style(
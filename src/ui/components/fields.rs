@@ -0,0 +1,111 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table},
+};
+
+use crate::{
+    comic_info::ComicInfoField,
+    managers::comic_form::ComicFormState,
+    ui::{
+        App,
+        app::{SELECTED_STYLE, popup_area},
+    },
+};
+
+impl App {
+    pub fn render_fields(&mut self, area: Rect, f: &mut Frame) {
+        let picker = &self.field_picker;
+        let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
+            return;
+        };
+        let info = comic.to_comic_info();
+
+        let area = popup_area(area, 60, 80);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Fields to save").bold(),
+            Span::raw(format!(" ({}) ", picker.label(&self.config.field_presets))),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let name_height = if picker.name.is_some() { 3 } else { 0 };
+        let [table_area, name_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(name_height),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let rows: Vec<Row> = ComicInfoField::ALL
+            .into_iter()
+            .map(|field| {
+                let (mark, style) = if picker.fields.contains(&field) {
+                    ("[x]", Style::default().fg(Color::Green))
+                } else {
+                    ("[ ]", Style::default().fg(Color::DarkGray))
+                };
+                Row::new(vec![
+                    Cell::from(mark).style(style),
+                    Cell::from(field.name()),
+                    Cell::from(field.get(&info).unwrap_or_default()),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(vec!["", "Field", "Form value"]).bold())
+        .column_spacing(1)
+        .row_highlight_style(SELECTED_STYLE);
+
+        f.render_stateful_widget(table, table_area, &mut self.field_picker.state);
+
+        if let Some(name) = &self.field_picker.name {
+            let input_block = Block::new()
+                .title(" Preset name ")
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+                .border_set(symbols::border::ROUNDED)
+                .border_style(Style::default().fg(Color::Cyan));
+
+            let width = name_area.width.max(4) - 4;
+            let scroll = name.visual_scroll(width as usize);
+            #[allow(clippy::cast_possible_truncation)]
+            let input = Paragraph::new(name.value())
+                .scroll((0, scroll as u16))
+                .block(input_block);
+            f.render_widget(input, name_area);
+
+            #[allow(clippy::cast_possible_truncation)]
+            let x = name_area.x + 2 + (name.visual_cursor().max(scroll) - scroll) as u16;
+            f.set_cursor_position((x.min(name_area.right().saturating_sub(2)), name_area.y + 1));
+        }
+
+        let hint = if self.field_picker.name.is_some() {
+            "<enter> save preset  <esc> cancel"
+        } else {
+            "<space> tick  <a> all  <p> next preset  <s> save preset  <D> delete preset  <enter> preview  <esc> close"
+        };
+        let hint = Paragraph::new(hint).style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
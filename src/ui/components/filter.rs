@@ -0,0 +1,81 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::ui::{App, app::popup_area};
+
+impl App {
+    pub fn render_filter(&mut self, area: Rect, f: &mut Frame) {
+        let view = &self.chapter_view;
+
+        let area = popup_area(area, 60, 30);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Select chapters").bold(),
+            Span::raw(" "),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [help_area, input_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let mut help = vec![
+            Line::raw("missing summary"),
+            Line::raw("translator = Scans and volume = 3"),
+            Line::raw("language != en"),
+            Line::raw("title ~ side story"),
+            Line::raw("Columns: volume number title translator language pages size status")
+                .fg(Color::DarkGray),
+            Line::raw("ComicInfo fields: Writer, Genre, LanguageISO, ...").fg(Color::DarkGray),
+        ];
+        if let Some(error) = &view.error {
+            help.push(Line::default());
+            help.push(Line::raw(error.as_str()).fg(Color::Red));
+        }
+        f.render_widget(Paragraph::new(help), help_area);
+
+        let input_block = Block::new()
+            .title(" Filter ")
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_set(symbols::border::ROUNDED)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let width = input_area.width.max(4) - 4;
+        let scroll = view.filter.visual_scroll(width as usize);
+        #[allow(clippy::cast_possible_truncation)]
+        let input = Paragraph::new(view.filter.value())
+            .scroll((0, scroll as u16))
+            .block(input_block);
+        f.render_widget(input, input_area);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let x = input_area.x + 2 + (view.filter.visual_cursor().max(scroll) - scroll) as u16;
+        f.set_cursor_position((
+            x.min(input_area.right().saturating_sub(2)),
+            input_area.y + 1,
+        ));
+
+        let hint = Paragraph::new("<enter> select matches, empty clears  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
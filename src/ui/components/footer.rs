@@ -1,11 +1,36 @@
-use ratatui::{Frame, layout::Rect, widgets::Paragraph};
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style},
+    widgets::Paragraph,
+};
 
 use crate::ui::App;
 
 impl App {
     pub fn render_footer(&self, area: Rect, f: &mut Frame) {
         let status = self.status_rx.borrow().clone();
+
+        let progress = if self.library_scan.is_running() {
+            self.library_scan.progress()
+        } else if let Some(job) = self.jobs_manager.running() {
+            format!("Job {} {}: {}", job.id, job.title, job.progress())
+        } else {
+            String::new()
+        };
+        #[allow(clippy::cast_possible_truncation)]
+        let [status_area, progress_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Length(progress.chars().count() as u16),
+        ])
+        .areas(area);
+
         let footer = Paragraph::new(status).left_aligned();
-        f.render_widget(footer, area);
+        f.render_widget(footer, status_area);
+
+        let progress = Paragraph::new(progress)
+            .right_aligned()
+            .style(Style::default().fg(Color::Yellow));
+        f.render_widget(progress, progress_area);
     }
 }
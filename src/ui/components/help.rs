@@ -12,11 +12,32 @@ impl App {
             ("g", "Go to top"),
             ("G", "Go to bottom"),
             ("<space>", "Toggle selection"),
+            ("R", "Refresh chapters"),
+            ("E", "Repair legacy encoded entry names"),
+            ("P", "Test filename patterns"),
+            ("N", "Preview & rename files from template"),
+            ("m/M", "Missing & duplicate chapters in series/library"),
+            ("a", "Choose or type the reading of a filename"),
+            ("J", "Edit series.json of the series"),
+            ("S", "Library statistics"),
+            ("L", "Preview & apply lint fixes"),
+            ("o/O", "Sort chapters by the next column/reverse"),
+            ("f", "Select chapters with a filter"),
+            ("b", "Jobs, cancel & retry failed writes"),
+            ("t", "Preview & apply a field transform"),
+            ("V", "Edit the chapter to volume map of the series"),
+            ("y/Y", "Yank all/ticked fields of the form"),
+            ("p", "Preview pasting a register to the selected chapters"),
+            ("\"a", "Name the register of the next yank or paste"),
+            ("C", "Registers & metadata presets"),
             ("?", "Toggle help"),
             ("Ctrl+c", "Close"),
-            ("Ctrl+f", "Save chapter numberings"),
+            ("Ctrl+f", "Preview & save chapter numberings"),
+            ("Ctrl+g", "Preview & save volume numbering"),
             ("Ctrl+s", "Save chapter info"),
-            ("Ctrl+d", "Save series info"),
+            ("Ctrl+d", "Preview & save series info"),
+            ("Ctrl+e", "Pick fields & presets to save to chapters"),
+            ("Ctrl+y/p", "Yank the form/paste a register"),
         ]);
 
         let popup_area = Rect {
@@ -0,0 +1,152 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::{
+    managers::jobs::{Job, JobState},
+    ui::{
+        App,
+        app::{SELECTED_STYLE, popup_area},
+    },
+};
+
+/// Width of the progress bars
+const BAR_WIDTH: usize = 20;
+
+/// Done archives in green and failed ones in red
+fn progress_bar(job: &Job) -> Line<'static> {
+    let width = |count: usize| {
+        (count * BAR_WIDTH)
+            .checked_div(job.total)
+            .unwrap_or_default()
+    };
+    let done = width(job.done);
+    let failed = width(job.processed()) - done;
+    let left = BAR_WIDTH - done - failed;
+
+    Line::from(vec![
+        Span::raw("█".repeat(done)).fg(Color::Green),
+        Span::raw("█".repeat(failed)).fg(Color::Red),
+        Span::raw("░".repeat(left)).fg(Color::DarkGray),
+    ])
+}
+
+fn state_style(state: JobState) -> Style {
+    let color = match state {
+        JobState::Queued => Color::DarkGray,
+        JobState::Running => Color::Yellow,
+        JobState::Cancelled => Color::Magenta,
+        JobState::Finished => Color::Green,
+    };
+    Style::default().fg(color)
+}
+
+impl App {
+    pub fn render_jobs(&mut self, area: Rect, f: &mut Frame) {
+        let jobs = &self.jobs_manager.jobs;
+
+        let area = popup_area(area, 90, 80);
+        f.render_widget(Clear, area);
+
+        let running = jobs.iter().filter(|job| !job.is_over()).count();
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Jobs").bold(),
+            Span::raw(format!(" ({running} going, {} total) ", jobs.len())),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [table_area, failed_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let rows: Vec<Row> = jobs
+            .iter()
+            .map(|job| {
+                let elapsed = job
+                    .elapsed
+                    .map(|elapsed| format!("{elapsed:.2?}"))
+                    .unwrap_or_default();
+                Row::new(vec![
+                    Cell::from(job.id.to_string()),
+                    Cell::from(job.title.as_str()),
+                    Cell::from(job.state.label()).style(state_style(job.state)),
+                    Cell::from(progress_bar(job)),
+                    Cell::from(job.progress()),
+                    Cell::from(elapsed),
+                ])
+            })
+            .collect();
+
+        if rows.is_empty() {
+            let empty = Paragraph::new("No jobs yet").centered();
+            f.render_widget(empty, table_area);
+        } else {
+            #[allow(clippy::cast_possible_truncation)]
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Length(4),
+                    Constraint::Fill(1),
+                    Constraint::Length(10),
+                    Constraint::Length(BAR_WIDTH as u16),
+                    Constraint::Length(24),
+                    Constraint::Length(10),
+                ],
+            )
+            .header(Row::new(vec!["Id", "Job", "State", "Progress", "", "Took"]).bold())
+            .column_spacing(1)
+            .row_highlight_style(SELECTED_STYLE);
+
+            f.render_stateful_widget(table, table_area, &mut self.jobs_manager.state);
+        }
+
+        // Errors of the selected job
+        let failures = self
+            .jobs_manager
+            .selected()
+            .map(|job| job.failed.as_slice())
+            .unwrap_or_default();
+        let failed_block = Block::new()
+            .title(format!(" Failed ({}) ", failures.len()))
+            .borders(Borders::TOP);
+        let lines: Vec<Line> = failures
+            .iter()
+            .map(|failure| {
+                let file = failure
+                    .task
+                    .chapter()
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                Line::from(vec![
+                    Span::raw(file),
+                    Span::raw("  "),
+                    Span::raw(failure.error.as_str()).fg(Color::Red),
+                ])
+            })
+            .collect();
+        f.render_widget(Paragraph::new(lines).block(failed_block), failed_area);
+
+        let hint = Paragraph::new("<c> cancel  <r> retry failed  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -1,8 +1,21 @@
 pub mod chapters;
 pub mod data_input;
+pub mod fields;
+pub mod filter;
 pub mod footer;
 pub mod header;
 pub mod help;
 pub mod info;
+pub mod jobs;
+pub mod pattern;
+pub mod preview;
+pub mod registers;
+pub mod rename;
+pub mod report;
+pub mod resolve;
 pub mod search;
 pub mod series;
+pub mod series_json;
+pub mod stats;
+pub mod transform;
+pub mod volume_map;
@@ -0,0 +1,133 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table},
+};
+
+use crate::ui::{
+    App,
+    app::{SELECTED_STYLE, popup_area},
+};
+
+impl App {
+    pub fn render_pattern(&mut self, area: Rect, f: &mut Frame) {
+        let area = popup_area(area, 90, 80);
+        f.render_widget(Clear, area);
+
+        let block = Block::new()
+            .title(
+                Line::from(vec![
+                    Span::raw(" "),
+                    Span::raw("Test filename pattern").bold(),
+                    Span::raw(" "),
+                ])
+                .left_aligned(),
+            )
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [input_area, table_area, hint_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        self.render_pattern_input(input_area, f);
+
+        let tester = &mut self.pattern_tester;
+        let rows: Vec<Row> = tester
+            .results
+            .iter()
+            .map(|(chapter, matched)| {
+                let filename = chapter
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let matched = matched.as_ref().map_or_else(
+                    || Cell::from("built-in").style(Style::default().fg(Color::DarkGray)),
+                    |name| Cell::from(name.clone()).style(Style::default().fg(Color::Green)),
+                );
+
+                Row::new(vec![
+                    Cell::from(filename),
+                    matched,
+                    Cell::from(chapter.volume.map(|v| v.to_string()).unwrap_or_default()),
+                    Cell::from(chapter.chapter.map(|c| c.to_string()).unwrap_or_default()),
+                    Cell::from(chapter.title.clone().unwrap_or_default()),
+                    Cell::from(chapter.translators.join(", ")),
+                    Cell::from(chapter.language.clone().unwrap_or_default()),
+                    Cell::from(chapter.year.map(|y| y.to_string()).unwrap_or_default()),
+                    Cell::from(chapter.series.clone().unwrap_or_default()),
+                ])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Percentage(25),
+                Constraint::Length(10),
+                Constraint::Length(4),
+                Constraint::Length(6),
+                Constraint::Fill(1),
+                Constraint::Length(12),
+                Constraint::Length(5),
+                Constraint::Length(5),
+                Constraint::Length(12),
+            ],
+        )
+        .header(
+            Row::new(vec![
+                "File", "Pattern", "Vol", "Ch", "Title", "Group", "Lang", "Year", "Series",
+            ])
+            .bold(),
+        )
+        .column_spacing(1)
+        .row_highlight_style(SELECTED_STYLE);
+
+        f.render_stateful_widget(table, table_area, &mut tester.state);
+
+        let hint = Paragraph::new(
+            "regex with (?P<name>) groups or template with {series} {volume} {chapter} {title} {group} {language} {year} {kind}  ↑/↓ scroll  <enter> save to config  <esc> close",
+        )
+        .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+
+    fn render_pattern_input(&self, area: Rect, f: &mut Frame) {
+        let tester = &self.pattern_tester;
+        let (input_title, border) = match &tester.error {
+            Some(error) => (format!(" {error} "), Color::Red),
+            None if tester.input.value().is_empty() => {
+                (String::from(" Configured patterns "), Color::Cyan)
+            }
+            None => (String::from(" Pattern "), Color::Cyan),
+        };
+        let input_block = Block::new()
+            .title(input_title)
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_set(symbols::border::ROUNDED)
+            .border_style(Style::default().fg(border));
+
+        let width = area.width.max(4) - 4;
+        let scroll = tester.input.visual_scroll(width as usize);
+        #[allow(clippy::cast_possible_truncation)]
+        let input = Paragraph::new(tester.input.value())
+            .scroll((0, scroll as u16))
+            .block(input_block);
+        f.render_widget(input, area);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let x = area.x + 2 + (tester.input.visual_cursor().max(scroll) - scroll) as u16;
+        f.set_cursor_position((x.min(area.right().saturating_sub(2)), area.y + 1));
+    }
+}
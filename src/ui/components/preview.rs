@@ -0,0 +1,105 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::ui::{
+    App,
+    app::{SELECTED_STYLE, popup_area},
+};
+
+impl App {
+    pub fn render_preview(&mut self, area: Rect, f: &mut Frame) {
+        let Some(preview) = &self.preview_manager.preview else {
+            return;
+        };
+
+        let area = popup_area(area, 90, 80);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw(preview.title.as_str()).bold(),
+            Span::raw(format!(
+                " ({}/{} changes in {}/{} chapters) ",
+                preview.applied(),
+                preview.rows.len(),
+                preview.changed_chapters(),
+                preview.chapters.len()
+            )),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [table_area, hint_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        let mut last_chapter = None;
+        let rows: Vec<Row> = preview
+            .rows
+            .iter()
+            .map(|row| {
+                let (old, new) = preview.values(row);
+                let chapter = &preview.chapters[row.chapter].chapter;
+
+                // Only name the chapter on its first row
+                let chapter_name = if last_chapter == Some(row.chapter) {
+                    String::new()
+                } else {
+                    chapter.get_title(false)
+                };
+                last_chapter = Some(row.chapter);
+
+                let check = if row.apply { "[x]" } else { "[ ]" };
+                let style = if row.apply {
+                    Style::default()
+                } else {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT)
+                };
+
+                Row::new(vec![
+                    Cell::from(check),
+                    Cell::from(chapter_name),
+                    Cell::from(row.field.name()).style(Style::default().fg(Color::Cyan)),
+                    Cell::from(old.unwrap_or_default()).style(Style::default().fg(Color::Red)),
+                    Cell::from(new.unwrap_or_default()).style(Style::default().fg(Color::Green)),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Percentage(25),
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(vec!["", "Chapter", "Field", "Old", "New"]).bold())
+        .column_spacing(1)
+        .row_highlight_style(SELECTED_STYLE);
+
+        f.render_stateful_widget(table, table_area, &mut self.preview_manager.state);
+
+        let hint = Paragraph::new(
+            "<space> toggle  c chapter  f field  a all  <enter> apply  <esc> cancel",
+        )
+        .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
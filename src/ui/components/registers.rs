@@ -0,0 +1,126 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table},
+};
+
+use crate::{
+    managers::registers::ClipSource,
+    ui::{
+        App,
+        app::{SELECTED_STYLE, popup_area},
+    },
+};
+
+impl App {
+    pub fn render_registers(&mut self, area: Rect, f: &mut Frame) {
+        let registers = &self.registers;
+
+        let area = popup_area(area, 60, 70);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Registers & presets").bold(),
+            Span::raw(" "),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let name_height = if registers.name.is_some() { 3 } else { 0 };
+        let [table_area, values_area, name_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Fill(1),
+            Constraint::Length(name_height),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let rows: Vec<Row> = registers
+            .rows()
+            .into_iter()
+            .map(|(source, clip)| {
+                let name = match source {
+                    ClipSource::Register(name) => {
+                        Cell::from(format!("\"{name}")).style(Style::default().fg(Color::Cyan))
+                    }
+                    ClipSource::Preset(name) => Cell::from(name),
+                };
+                Row::new(vec![name, Cell::from(clip.summary())])
+            })
+            .collect();
+
+        if rows.is_empty() {
+            let empty = Paragraph::new("Nothing yanked yet, press y on a chapter")
+                .style(Style::default().fg(Color::DarkGray));
+            f.render_widget(empty, table_area);
+        } else {
+            let table = Table::new(rows, [Constraint::Percentage(40), Constraint::Fill(1)])
+                .header(Row::new(vec!["Register / preset", "Fields"]).bold())
+                .column_spacing(1)
+                .row_highlight_style(SELECTED_STYLE);
+            f.render_stateful_widget(table, table_area, &mut self.registers.state);
+        }
+
+        // Values the highlighted row pastes
+        let registers = &self.registers;
+        let values: Vec<Line> = registers
+            .selected()
+            .map(|(_, clip)| {
+                clip.values
+                    .iter()
+                    .map(|(field, value)| {
+                        let value = if value.is_empty() {
+                            Span::raw("(cleared)").fg(Color::DarkGray)
+                        } else {
+                            Span::raw(value.clone())
+                        };
+                        Line::from(vec![Span::raw(format!("{:<12}", field.name())), value])
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let values_block = Block::new()
+            .borders(Borders::TOP)
+            .border_style(Style::default().fg(Color::DarkGray));
+        f.render_widget(Paragraph::new(values).block(values_block), values_area);
+
+        if let Some(name) = &registers.name {
+            let input_block = Block::new()
+                .title(" Preset name ")
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+                .border_set(symbols::border::ROUNDED)
+                .border_style(Style::default().fg(Color::Cyan));
+
+            let width = name_area.width.max(4) - 4;
+            let scroll = name.visual_scroll(width as usize);
+            #[allow(clippy::cast_possible_truncation)]
+            let input = Paragraph::new(name.value())
+                .scroll((0, scroll as u16))
+                .block(input_block);
+            f.render_widget(input, name_area);
+
+            #[allow(clippy::cast_possible_truncation)]
+            let x = name_area.x + 2 + (name.visual_cursor().max(scroll) - scroll) as u16;
+            f.set_cursor_position((x.min(name_area.right().saturating_sub(2)), name_area.y + 1));
+        }
+
+        let hint = if registers.name.is_some() {
+            "<enter> save preset  <esc> cancel"
+        } else {
+            "<enter> preview paste  <s> save as preset  <D> delete preset  <esc> close"
+        };
+        let hint = Paragraph::new(hint).style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
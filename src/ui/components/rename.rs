@@ -0,0 +1,106 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Modifier, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::ui::{
+    App,
+    app::{SELECTED_STYLE, popup_area},
+};
+
+impl App {
+    pub fn render_rename(&mut self, area: Rect, f: &mut Frame) {
+        let Some(preview) = &self.rename_manager.preview else {
+            return;
+        };
+
+        let area = popup_area(area, 90, 80);
+        f.render_widget(Clear, area);
+
+        let mut title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw(preview.title.as_str()).bold(),
+            Span::raw(format!(" ({}) ", preview.rows.len())),
+        ])
+        .left_aligned();
+        if preview.conflicts() > 0 {
+            title
+                .push_span(Span::raw(format!("{} conflicts ", preview.conflicts())).fg(Color::Red));
+        }
+        if preview.ambiguous() > 0 {
+            title.push_span(
+                Span::raw(format!("{} ambiguous ", preview.ambiguous())).fg(Color::Yellow),
+            );
+        }
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [table_area, hint_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        let rows: Vec<Row> = preview
+            .rows
+            .iter()
+            .map(|row| {
+                let chapter = row
+                    .chapter
+                    .path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string();
+                let check = if row.apply { "[x]" } else { "[ ]" };
+                let style = if row.apply {
+                    Style::default()
+                } else {
+                    Style::default()
+                        .fg(Color::DarkGray)
+                        .add_modifier(Modifier::CROSSED_OUT)
+                };
+                let new_style = if row.conflict {
+                    Style::default().fg(Color::Red)
+                } else if row.ambiguous {
+                    Style::default().fg(Color::Yellow)
+                } else {
+                    Style::default().fg(Color::Green)
+                };
+
+                Row::new(vec![
+                    Cell::from(check),
+                    Cell::from(chapter),
+                    Cell::from(row.old.as_str()).style(Style::default().fg(Color::Red)),
+                    Cell::from(row.new.as_str()).style(new_style),
+                ])
+                .style(style)
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(3),
+                Constraint::Percentage(30),
+                Constraint::Fill(1),
+                Constraint::Fill(1),
+            ],
+        )
+        .header(Row::new(vec!["", "Chapter", "Old", "New"]).bold())
+        .column_spacing(1)
+        .row_highlight_style(SELECTED_STYLE);
+
+        f.render_stateful_widget(table, table_area, &mut self.rename_manager.state);
+
+        let hint = Paragraph::new("<space> toggle  <enter> apply ticked  <esc> cancel")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
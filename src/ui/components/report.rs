@@ -0,0 +1,101 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Paragraph, Row, Table},
+};
+
+use crate::{
+    managers::report::ReportIssue,
+    ui::{
+        App,
+        app::{SELECTED_STYLE, popup_area},
+    },
+};
+
+impl App {
+    pub fn render_report(&mut self, area: Rect, f: &mut Frame) {
+        let issues = self.report_manager.issues();
+        let reports = &self.report_manager.reports;
+
+        let area = popup_area(area, 90, 80);
+        f.render_widget(Clear, area);
+
+        let name = if self.report_manager.library {
+            String::from("Library report")
+        } else {
+            reports
+                .first()
+                .map(|r| format!("Report: {}", r.series))
+                .unwrap_or_default()
+        };
+        let with_issues = reports.iter().filter(|r| !r.issues.is_empty()).count();
+        let chapters: usize = reports.iter().map(|r| r.chapters).sum();
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw(name).bold(),
+            Span::raw(format!(
+                " ({issues} issues in {with_issues}/{} series, {chapters} chapters) ",
+                reports.len()
+            )),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [table_area, hint_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+
+        let rows: Vec<Row> = reports
+            .iter()
+            .flat_map(|report| {
+                report.issues.iter().enumerate().map(|(i, issue)| {
+                    // Only name the series on its first row
+                    let series = if i == 0 { report.series.as_str() } else { "" };
+                    let color = match issue {
+                        ReportIssue::Gap { .. } => Color::Red,
+                        ReportIssue::Duplicate { .. } => Color::Yellow,
+                        ReportIssue::Unnumbered { .. } | ReportIssue::VolumeBackwards { .. } => {
+                            Color::Magenta
+                        }
+                    };
+
+                    Row::new(vec![
+                        Cell::from(series),
+                        Cell::from(issue.kind()).style(Style::default().fg(color)),
+                        Cell::from(issue.detail()),
+                    ])
+                })
+            })
+            .collect();
+
+        if rows.is_empty() {
+            let empty = Paragraph::new("No issues found 🎉").centered();
+            f.render_widget(empty, table_area);
+        } else {
+            let table = Table::new(
+                rows,
+                [
+                    Constraint::Percentage(30),
+                    Constraint::Length(12),
+                    Constraint::Fill(1),
+                ],
+            )
+            .header(Row::new(vec!["Series", "Issue", "Detail"]).bold())
+            .column_spacing(1)
+            .row_highlight_style(SELECTED_STYLE);
+
+            f.render_stateful_widget(table, table_area, &mut self.report_manager.state);
+        }
+
+        let hint = Paragraph::new("<esc> close").style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -0,0 +1,126 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Cell, Clear, Padding, Paragraph, Row, Table},
+};
+
+use crate::{
+    managers::resolve::ReadingSource,
+    ui::{
+        App,
+        app::{SELECTED_STYLE, popup_area},
+        list::LOW_CONFIDENCE,
+    },
+};
+
+impl App {
+    pub fn render_resolve(&mut self, area: Rect, f: &mut Frame) {
+        let resolver = &mut self.parse_resolver;
+        let Some(chapter) = &resolver.chapter else {
+            return;
+        };
+
+        let area = popup_area(area, 70, 50);
+        f.render_widget(Clear, area);
+
+        let filename = chapter
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let mut title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw(filename).bold(),
+            Span::raw(" "),
+        ])
+        .left_aligned();
+        if chapter.overridden {
+            title.push_span(Span::raw("overridden ").fg(Color::Cyan));
+        }
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [table_area, input_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(3),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let rows: Vec<Row> = resolver
+            .readings
+            .iter()
+            .map(|(source, reading, confidence)| {
+                let (source, confidence) = match source {
+                    ReadingSource::Typed => (Cell::from("typed"), Cell::from("")),
+                    ReadingSource::Parsed => {
+                        let color = if *confidence < LOW_CONFIDENCE {
+                            Color::Yellow
+                        } else {
+                            Color::Green
+                        };
+                        (
+                            Cell::from("parsed"),
+                            Cell::from(format!("{confidence}%")).style(Style::default().fg(color)),
+                        )
+                    }
+                    ReadingSource::Alternative => (
+                        Cell::from("alternative").style(Style::default().fg(Color::DarkGray)),
+                        Cell::from(""),
+                    ),
+                };
+
+                Row::new(vec![source, Cell::from(reading.label()), confidence])
+            })
+            .collect();
+
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(12),
+                Constraint::Fill(1),
+                Constraint::Length(5),
+            ],
+        )
+        .header(Row::new(vec!["Source", "Reading", "Conf"]).bold())
+        .column_spacing(1)
+        .row_highlight_style(SELECTED_STYLE);
+
+        f.render_stateful_widget(table, table_area, &mut resolver.state);
+
+        let input_block = Block::new()
+            .title(" Type a reading like Vol.3 Ch.12 - Title ")
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_set(symbols::border::ROUNDED)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let width = input_area.width.max(4) - 4;
+        let scroll = resolver.input.visual_scroll(width as usize);
+        #[allow(clippy::cast_possible_truncation)]
+        let input = Paragraph::new(resolver.input.value())
+            .scroll((0, scroll as u16))
+            .block(input_block);
+        f.render_widget(input, input_area);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let x = input_area.x + 2 + (resolver.input.visual_cursor().max(scroll) - scroll) as u16;
+        f.set_cursor_position((
+            x.min(input_area.right().saturating_sub(2)),
+            input_area.y + 1,
+        ));
+
+        let hint = Paragraph::new("↑/↓ choose  <enter> keep reading  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -0,0 +1,82 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph},
+};
+
+use crate::ui::{
+    App,
+    app::{SELECTED_STYLE, popup_area},
+};
+
+/// Width of the field labels
+const LABEL_WIDTH: u16 = 14;
+
+impl App {
+    pub fn render_series_json(&mut self, area: Rect, f: &mut Frame) {
+        let editor = &self.series_json_editor;
+        let area = popup_area(area, 70, 50);
+        f.render_widget(Clear, area);
+
+        let mut title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("series.json").bold(),
+            Span::raw(" "),
+        ])
+        .left_aligned();
+        if !editor.exists {
+            title.push_span(Span::raw("new ").fg(Color::Cyan));
+        }
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [fields_area, error_area, hint_area] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let items: Vec<ListItem> = editor
+            .fields
+            .iter()
+            .map(|(label, input)| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{label:<width$}", width = LABEL_WIDTH as usize)).bold(),
+                    Span::raw(input.value().to_string()),
+                ]))
+            })
+            .collect();
+        let mut state = ListState::default().with_selected(Some(editor.active));
+        f.render_stateful_widget(
+            List::new(items).highlight_style(SELECTED_STYLE),
+            fields_area,
+            &mut state,
+        );
+
+        if let Some((_, input)) = editor.fields.get(editor.active) {
+            #[allow(clippy::cast_possible_truncation)]
+            let x = fields_area.x + LABEL_WIDTH + input.visual_cursor() as u16;
+            #[allow(clippy::cast_possible_truncation)]
+            let y = fields_area.y + (editor.active - state.offset()) as u16;
+            f.set_cursor_position((x.min(fields_area.right().saturating_sub(1)), y));
+        }
+
+        if let Some(error) = &editor.error {
+            let error = Paragraph::new(error.as_str()).style(Style::default().fg(Color::Red));
+            f.render_widget(error, error_area);
+        }
+
+        let hint = Paragraph::new("↑/↓ field  <enter> save  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
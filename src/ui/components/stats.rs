@@ -0,0 +1,144 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Paragraph},
+};
+
+use crate::{
+    managers::stats::{LibraryStats, SeriesStats, format_size},
+    ui::{App, app::popup_area},
+};
+
+/// Heading of a dashboard section
+fn heading(text: &str) -> Line<'static> {
+    Line::from(text.to_string()).bold().fg(Color::Cyan)
+}
+
+fn series_lines(series: &[SeriesStats], value: impl Fn(&SeriesStats) -> String) -> Vec<Line<'_>> {
+    series
+        .iter()
+        .map(|s| {
+            Line::from(vec![
+                Span::raw(format!("  {:>10}  ", value(s))),
+                Span::raw(&s.series),
+            ])
+        })
+        .collect()
+}
+
+fn overview_lines(stats: &LibraryStats) -> Vec<Line<'_>> {
+    let mut lines = vec![
+        heading("Overview"),
+        Line::from(format!("  Series    {}", stats.series)),
+        Line::from(format!("  Chapters  {}", stats.chapters)),
+        Line::from(format!("  Size      {}", format_size(stats.total_size))),
+        Line::from(format!("  Pages     {}", stats.total_pages)),
+        Line::default(),
+        heading("Formats"),
+    ];
+    lines.extend(
+        stats
+            .formats
+            .iter()
+            .map(|(format, count)| Line::from(format!("  {count:>6}  {format}"))),
+    );
+
+    lines.push(Line::default());
+    lines.push(heading("Languages"));
+    lines.extend(
+        stats
+            .languages
+            .iter()
+            .map(|(language, count)| Line::from(format!("  {count:>6}  {language}"))),
+    );
+
+    lines.push(Line::default());
+    lines.push(heading("Top translators"));
+    lines.extend(
+        stats
+            .top_translators
+            .iter()
+            .map(|(name, count)| Line::from(format!("  {count:>6}  {name}"))),
+    );
+
+    lines.push(Line::default());
+    lines.push(heading("Largest series"));
+    lines.extend(series_lines(&stats.largest_series, |s| format_size(s.size)));
+
+    lines.push(Line::default());
+    lines.push(heading("Most incomplete series"));
+    lines.extend(series_lines(&stats.most_incomplete_series, |s| {
+        format!("{:.0}% empty", s.missing_percent)
+    }));
+    lines
+}
+
+fn coverage_lines(stats: &LibraryStats) -> Vec<Line<'_>> {
+    let mut lines = vec![heading("Metadata coverage")];
+    lines.extend(stats.coverage.iter().map(|coverage| {
+        let color = match coverage.missing_percent {
+            p if p >= 50.0 => Color::Red,
+            p if p > 0.0 => Color::Yellow,
+            _ => Color::Green,
+        };
+        Line::from(vec![
+            Span::styled(
+                format!("  {:>5.1}%", coverage.missing_percent),
+                Style::default().fg(color),
+            ),
+            Span::raw(format!(
+                " of chapters lack {} ({})",
+                coverage.field, coverage.missing
+            )),
+        ])
+    }));
+    lines
+}
+
+impl App {
+    pub fn render_stats(&mut self, area: Rect, f: &mut Frame) {
+        let Some(stats) = &self.stats_manager.stats else {
+            return;
+        };
+
+        let area = popup_area(area, 90, 85);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Library statistics").bold(),
+            Span::raw(" "),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [columns_area, hint_area] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(inner);
+        let [overview_area, coverage_area] =
+            Layout::horizontal([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .areas(columns_area);
+
+        let scroll = (self.stats_manager.scroll, 0);
+        f.render_widget(
+            Paragraph::new(overview_lines(stats)).scroll(scroll),
+            overview_area,
+        );
+        f.render_widget(
+            Paragraph::new(coverage_lines(stats)).scroll(scroll),
+            coverage_area,
+        );
+
+        let hint = Paragraph::new("<j/k> scroll  <e> export JSON  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -0,0 +1,137 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::{
+    managers::{comic_form::ComicFormState, transform::TransformFocus},
+    ui::{App, app::popup_area},
+};
+
+/// Line of a value cycled with the arrows
+fn choice_line(label: &str, value: &str, focused: bool) -> Line<'static> {
+    let style = if focused {
+        Style::default().fg(Color::Cyan).bold()
+    } else {
+        Style::default()
+    };
+    Line::from(vec![
+        Span::raw(format!("{label:<10}")),
+        Span::styled(format!("‹ {value} ›"), style),
+    ])
+}
+
+impl App {
+    pub fn render_transform(&mut self, area: Rect, f: &mut Frame) {
+        let dialog = &self.transform_dialog;
+        let kind = dialog.kind();
+
+        let area = popup_area(area, 60, 50);
+        f.render_widget(Clear, area);
+
+        let chapters = self.get_chapters_in_series().len();
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Transform").bold(),
+            Span::raw(format!(" ({chapters} chapters) ")),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let labels = kind.inputs();
+        let [
+            choices_area,
+            first_area,
+            second_area,
+            sample_area,
+            hint_area,
+        ] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Length(if labels.is_empty() { 0 } else { 3 }),
+            Constraint::Length(if labels.len() > 1 { 3 } else { 0 }),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let choices = vec![
+            choice_line(
+                "Field",
+                dialog.field().name(),
+                dialog.focus == TransformFocus::Field,
+            ),
+            choice_line(
+                "Transform",
+                kind.name(),
+                dialog.focus == TransformFocus::Kind,
+            ),
+        ];
+        f.render_widget(Paragraph::new(choices), choices_area);
+
+        for (i, (label, input_area)) in labels.iter().zip([first_area, second_area]).enumerate() {
+            let input = &dialog.inputs[i];
+            let focused = dialog.focus == TransformFocus::Input(i);
+            let border = if focused {
+                Color::Cyan
+            } else {
+                Color::DarkGray
+            };
+            let input_block = Block::new()
+                .title(format!(" {label} "))
+                .borders(Borders::ALL)
+                .padding(Padding::horizontal(1))
+                .border_set(symbols::border::ROUNDED)
+                .border_style(Style::default().fg(border));
+
+            let width = input_area.width.max(4) - 4;
+            let scroll = input.visual_scroll(width as usize);
+            #[allow(clippy::cast_possible_truncation)]
+            let paragraph = Paragraph::new(input.value())
+                .scroll((0, scroll as u16))
+                .block(input_block);
+            f.render_widget(paragraph, input_area);
+
+            if focused {
+                #[allow(clippy::cast_possible_truncation)]
+                let x = input_area.x + 2 + (input.visual_cursor().max(scroll) - scroll) as u16;
+                f.set_cursor_position((
+                    x.min(input_area.right().saturating_sub(2)),
+                    input_area.y + 1,
+                ));
+            }
+        }
+
+        // The transform applied to the chapter under the cursor
+        let mut sample = Vec::new();
+        if let Some(error) = &dialog.error {
+            sample.push(Line::raw(error.as_str()).fg(Color::Red));
+        } else if let ComicFormState::Ready(comic) = &self.comic_manager.comic
+            && let Ok(transform) = dialog.transform()
+        {
+            let info = comic.to_comic_info();
+            let chapter = self.get_current_chapter();
+            let old = transform.field.get(&info).unwrap_or_default();
+            let new = transform
+                .value(&chapter, &info)
+                .unwrap_or_else(|| old.clone());
+            sample.push(Line::raw("Current chapter").fg(Color::DarkGray));
+            sample.push(Line::raw(old).fg(Color::Red));
+            sample.push(Line::raw(new).fg(Color::Green));
+        }
+        f.render_widget(Paragraph::new(sample), sample_area);
+
+        let hint = Paragraph::new("<tab> next  <←/→> change  <enter> preview  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
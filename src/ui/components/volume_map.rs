@@ -0,0 +1,109 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    symbols,
+    text::{Line, Span},
+    widgets::{Block, Borders, Clear, Padding, Paragraph},
+};
+
+use crate::ui::{App, app::popup_area};
+
+impl App {
+    pub fn render_volume_map(&mut self, area: Rect, f: &mut Frame) {
+        let editor = &self.volume_map_editor;
+        let series = self.get_current_series();
+
+        let area = popup_area(area, 60, 50);
+        f.render_widget(Clear, area);
+
+        let title = Line::from(vec![
+            Span::raw(" "),
+            Span::raw("Volume map").bold(),
+            Span::raw(format!(" ({}) ", series.title())),
+        ])
+        .left_aligned();
+
+        let block = Block::new()
+            .title(title)
+            .borders(Borders::ALL)
+            .border_set(symbols::border::ROUNDED);
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        let [input_area, volumes_area, hint_area] = Layout::vertical([
+            Constraint::Length(3),
+            Constraint::Fill(1),
+            Constraint::Length(1),
+        ])
+        .areas(inner);
+
+        let input_block = Block::new()
+            .title(" Volume: first-last, … ")
+            .borders(Borders::ALL)
+            .padding(Padding::horizontal(1))
+            .border_set(symbols::border::ROUNDED)
+            .border_style(Style::default().fg(Color::Cyan));
+
+        let width = input_area.width.max(4) - 4;
+        let scroll = editor.input.visual_scroll(width as usize);
+        #[allow(clippy::cast_possible_truncation)]
+        let paragraph = Paragraph::new(editor.input.value())
+            .scroll((0, scroll as u16))
+            .block(input_block);
+        f.render_widget(paragraph, input_area);
+
+        #[allow(clippy::cast_possible_truncation)]
+        let x = input_area.x + 2 + (editor.input.visual_cursor().max(scroll) - scroll) as u16;
+        f.set_cursor_position((
+            x.min(input_area.right().saturating_sub(2)),
+            input_area.y + 1,
+        ));
+
+        // Chapters each volume of the typed table gets
+        let mut lines = Vec::new();
+        let map = match &editor.error {
+            Some(error) => Err(anyhow::anyhow!("{error}")),
+            None => editor.map(),
+        };
+        match map {
+            Err(e) => lines.push(Line::raw(e.to_string()).fg(Color::Red)),
+            Ok(None) => {
+                lines.push(Line::raw("No table, saving removes it").fg(Color::DarkGray));
+            }
+            Ok(Some(map)) => {
+                let volumes: Vec<Option<u32>> = series
+                    .chapters
+                    .items_state
+                    .iter()
+                    .map(|chapter| map.volume_of_chapter(chapter))
+                    .collect();
+                let mut seen = Vec::new();
+                for range in &map.ranges {
+                    if seen.contains(&range.volume) {
+                        continue;
+                    }
+                    seen.push(range.volume);
+                    let count = volumes.iter().filter(|v| **v == Some(range.volume)).count();
+                    lines.push(Line::raw(format!(
+                        "Volume {:<4} {count} chapters",
+                        range.volume
+                    )));
+                }
+
+                let uncovered = volumes.iter().filter(|v| v.is_none()).count();
+                if uncovered > 0 {
+                    lines.push(
+                        Line::raw(format!("{uncovered} archives not in any volume"))
+                            .fg(Color::Yellow),
+                    );
+                }
+            }
+        }
+        f.render_widget(Paragraph::new(lines), volumes_area);
+
+        let hint = Paragraph::new("<enter> save & preview  <ctrl+r> import volumes  <esc> close")
+            .style(Style::default().fg(Color::DarkGray));
+        f.render_widget(hint, hint_area);
+    }
+}
@@ -1,61 +1,245 @@
-use std::time::Instant;
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::Instant,
+};
 
 use crate::{
     chapter_manager::{
-        save_chapter_info, save_series_info, update_chapter_numbering, update_volume_numbering,
+        build_reports, build_stats, preview_chapter_numbering, preview_entry_names,
+        preview_field_update, preview_file_names, preview_lint_fixes, preview_series_info,
+        preview_transform, preview_volume_map, preview_volume_numbering, rename_files, run_job,
+    },
+    config::Config,
+    data::{FilenameParser, scan_folder},
+    lint::LintContext,
+    managers::{
+        chapter_view::ChapterFilter,
+        comic_form::{ComicFormState, ComicInfoForm},
+        jobs::JobTask,
+        preview::BatchPreview,
+        registers::{ClipSource, MetadataClip},
+        rename::{RenameKind, RenameRow, RenamedFiles},
+        resolve::ReadingSource,
     },
-    managers::comic_form::{ComicFormState, ComicInfoForm},
+    series_json::{self, SeriesJson},
     ui::{
-        App, InputMode, Tab,
-        list::{ChapterList, Series},
+        App, InputMode, Popup, Tab,
+        list::{ParseReading, Series},
     },
 };
 
 /// Handles keybindings in metadata tab
 impl App {
-    pub fn handle_ctrl_d(&self) {
+    pub fn handle_ctrl_d(&mut self) {
         let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
             return;
         };
         let chapters = self.get_chapters_in_series();
         let comic_info = comic.to_comic_info();
+        let series = self.get_current_series();
         let status_tx = self.status_tx.clone();
 
-        tokio::spawn(async move {
-            if let Err(e) = save_series_info(chapters, comic_info, status_tx).await {
-                error!("Failed to save series info: {e}");
+        self.start_preview(preview_series_info(
+            chapters,
+            comic_info,
+            series.folder,
+            series.series_json,
+            status_tx,
+        ));
+    }
+
+    /// Opens the picker of fields to copy to the selected chapters
+    pub fn handle_open_fields(&mut self) {
+        if let ComicFormState::Ready(_) = &self.comic_manager.comic {
+            if self.field_picker.state.selected().is_none() {
+                self.field_picker.state.select_first();
             }
-        });
+            self.popup = Some(Popup::Fields);
+        }
     }
 
-    pub fn handle_ctrl_s(&self) {
+    /// Previews copying the ticked fields of the form to the selected chapters
+    pub fn handle_apply_fields(&mut self) {
         let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
             return;
         };
+        let fields = self.field_picker.chosen();
+        if fields.is_empty() {
+            let _ = self.status_tx.send("Tick at least one field".to_string());
+            return;
+        }
 
-        let chapter = self.get_current_chapter();
+        self.popup = None;
+        let title = format!(
+            "Save {}",
+            self.field_picker.label(&self.config.field_presets)
+        );
+        let chapters = self.get_chapters_in_series();
         let comic_info = comic.to_comic_info();
         let status_tx = self.status_tx.clone();
-        tokio::spawn(async move {
-            if let Err(e) = save_chapter_info(chapter, comic_info, status_tx).await {
-                error!("Failed to save chapter info: {e}");
-            }
-        });
+        self.start_preview(preview_field_update(
+            chapters, comic_info, fields, title, status_tx,
+        ));
+    }
+
+    /// Saves the ticked fields as a preset in the config
+    pub fn handle_save_field_preset(&mut self) {
+        let Some(name) = self
+            .field_picker
+            .save_preset(&mut self.config.field_presets)
+        else {
+            return;
+        };
+
+        if let Err(e) = self.config.save() {
+            error!("Failed to save config: {e}");
+            return;
+        }
+        let _ = self.status_tx.send(format!("Saved field preset {name}"));
+    }
+
+    /// Removes the loaded preset from the config
+    pub fn handle_delete_field_preset(&mut self) {
+        let Some(name) = self
+            .field_picker
+            .delete_preset(&mut self.config.field_presets)
+        else {
+            return;
+        };
+
+        if let Err(e) = self.config.save() {
+            error!("Failed to save config: {e}");
+            return;
+        }
+        let _ = self.status_tx.send(format!("Deleted field preset {name}"));
+    }
+
+    /// Yanks the form into the named register, every field or only the ones ticked in the
+    /// field picker
+    pub fn handle_yank(&mut self, picked: bool) {
+        let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
+            let _ = self
+                .status_tx
+                .send("Nothing to yank, the chapter info isn't loaded".to_string());
+            return;
+        };
+        let info = comic.to_comic_info();
+        let clip = if picked {
+            MetadataClip::new(&info, &self.field_picker.chosen())
+        } else {
+            MetadataClip::whole(&info)
+        };
+        if clip.values.is_empty() {
+            let _ = self.status_tx.send("Tick at least one field".to_string());
+            return;
+        }
+
+        let count = clip.values.len();
+        let name = self.registers.yank(clip);
+        let _ = self
+            .status_tx
+            .send(format!("Yanked {count} fields into register {name}"));
+    }
+
+    /// Previews pasting the named register to the selected chapters
+    pub fn handle_paste(&mut self) {
+        let Some((source, clip)) = self.registers.paste() else {
+            let _ = self
+                .status_tx
+                .send("Nothing to paste, register is empty".to_string());
+            return;
+        };
+        self.paste_clip(&source, &clip);
+    }
+
+    fn paste_clip(&mut self, source: &ClipSource, clip: &MetadataClip) {
+        if self.get_current_series().path.as_os_str().is_empty() {
+            return;
+        }
+        let chapters = self.get_chapters_in_series();
+        let status_tx = self.status_tx.clone();
+        self.start_preview(preview_field_update(
+            chapters,
+            clip.to_comic_info(),
+            clip.fields(),
+            source.label(),
+            status_tx,
+        ));
+    }
+
+    pub fn handle_open_registers(&mut self) {
+        if self.registers.state.selected().is_none() {
+            self.registers.state.select_first();
+        }
+        self.popup = Some(Popup::Registers);
+    }
+
+    /// Previews pasting the register or preset under the cursor
+    pub fn handle_paste_selected(&mut self) {
+        let Some((source, clip)) = self.registers.selected() else {
+            return;
+        };
+        self.popup = None;
+        self.paste_clip(&source, &clip);
     }
 
-    pub fn handle_ctrl_f(&self) {
+    /// Saves the selected register or preset as a preset in `presets.toml`
+    pub fn handle_save_clip_preset(&mut self) {
+        let Some(name) = self.registers.save_preset() else {
+            return;
+        };
+
+        if let Err(e) = self.registers.presets.save() {
+            error!("Failed to save presets: {e}");
+            return;
+        }
+        let _ = self.status_tx.send(format!("Saved preset {name}"));
+    }
+
+    /// Removes the selected preset from `presets.toml`
+    pub fn handle_delete_clip_preset(&mut self) {
+        let Some(name) = self.registers.delete_preset() else {
+            return;
+        };
+
+        if let Err(e) = self.registers.presets.save() {
+            error!("Failed to save presets: {e}");
+            return;
+        }
+        let _ = self.status_tx.send(format!("Deleted preset {name}"));
+    }
+
+    pub fn handle_ctrl_s(&mut self) {
+        let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
+            return;
+        };
+
+        let task = JobTask::WriteComicInfo {
+            chapter: self.get_current_chapter(),
+            info: Box::new(comic.to_comic_info()),
+            fields: comic.changed_fields(),
+        };
+        self.start_job("Save chapter info", vec![task]);
+    }
+
+    pub fn handle_ctrl_f(&mut self) {
         if let ComicFormState::Ready(_) = &self.comic_manager.comic {
             let chapters = self.get_chapters_in_series();
             let status_tx = self.status_tx.clone();
-            tokio::spawn(async move {
-                if let Err(e) = update_chapter_numbering(chapters, status_tx).await {
-                    error!("Failed to save series info: {e}");
-                }
-            });
+            let specials = self.config.specials;
+            let warn_mixed_languages = self.config.warn_mixed_languages;
+            self.start_preview(preview_chapter_numbering(
+                chapters,
+                specials,
+                warn_mixed_languages,
+                status_tx,
+            ));
         }
     }
 
-    pub fn handle_ctrl_g(&self) {
+    pub fn handle_ctrl_g(&mut self) {
         let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
             return;
         };
@@ -63,13 +247,114 @@ impl App {
         let chapters = self.get_chapters_in_series();
         let comic_info = comic.to_comic_info();
         let status_tx = self.status_tx.clone();
+        self.start_preview(preview_volume_numbering(chapters, comic_info, status_tx));
+    }
+
+    /// Computes a batch preview in the background, it's shown once ready
+    fn start_preview<F>(&mut self, preview: F)
+    where
+        F: Future<Output = anyhow::Result<BatchPreview>> + Send + 'static,
+    {
+        let (preview_tx, preview_rx) = std::sync::mpsc::channel();
+        self.preview_manager.preview_rx = Some(preview_rx);
+
         tokio::spawn(async move {
-            if let Err(e) = update_volume_numbering(chapters, comic_info, status_tx).await {
-                error!("Failed to save series info: {e}");
+            match preview.await {
+                Ok(preview) => {
+                    let _ = preview_tx.send(preview);
+                }
+                Err(e) => error!("Failed to preview changes: {e}"),
             }
         });
     }
 
+    /// Queues a batch write, its progress shows in the jobs panel
+    fn start_job(&mut self, title: &str, tasks: Vec<JobTask>) {
+        if tasks.is_empty() {
+            return;
+        }
+
+        let runner = self.jobs_manager.add(title, tasks);
+        let _ = self
+            .status_tx
+            .send(format!("Queued job {}: {title}", runner.id));
+        tokio::spawn(run_job(runner, self.status_tx.clone()));
+    }
+
+    /// Writes the accepted changes of the current preview
+    pub fn handle_apply_preview(&mut self) {
+        self.popup = None;
+        let Some(preview) = self.preview_manager.preview.take() else {
+            return;
+        };
+
+        let title = preview.title.clone();
+        let tasks = preview
+            .into_targets()
+            .into_iter()
+            .map(|(chapter, info, fields)| JobTask::WriteComicInfo {
+                chapter,
+                info: Box::new(info),
+                fields,
+            })
+            .collect();
+        self.start_job(&title, tasks);
+    }
+
+    /// Opens the jobs panel
+    pub fn handle_open_jobs(&mut self) {
+        if self.jobs_manager.state.selected().is_none() {
+            self.jobs_manager.state.select_last();
+        }
+        self.popup = Some(Popup::Jobs);
+    }
+
+    /// Opens the transform dialog for the selected chapters
+    pub fn handle_open_transform(&mut self) {
+        if self.get_current_series().path.as_os_str().is_empty() {
+            return;
+        }
+        self.transform_dialog.error = None;
+        self.popup = Some(Popup::Transform);
+    }
+
+    /// Previews the typed transform on the selected chapters
+    pub fn handle_apply_transform(&mut self) {
+        let transform = match self.transform_dialog.transform() {
+            Ok(transform) => transform,
+            Err(e) => {
+                self.transform_dialog.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        self.popup = None;
+        let chapters = self.get_chapters_in_series();
+        let status_tx = self.status_tx.clone();
+        self.start_preview(preview_transform(chapters, transform, status_tx));
+    }
+
+    /// Cancels the selected job
+    pub fn handle_cancel_job(&mut self) {
+        if self.jobs_manager.cancel_selected() {
+            let _ = self
+                .status_tx
+                .send("Cancelling job, archives being written are finished first".to_string());
+        }
+    }
+
+    /// Queues the failed archives of the selected job again
+    pub fn handle_retry_job(&mut self) {
+        let Some((title, tasks)) = self.jobs_manager.retry_selected() else {
+            let _ = self
+                .status_tx
+                .send("Nothing to retry, the job is still going or had no failures".to_string());
+            return;
+        };
+        self.start_job(&title, tasks);
+        self.jobs_manager.state.select_last();
+    }
+
     pub fn handle_ctrl_u(&mut self) {
         let ComicFormState::Ready(comic) = &self.comic_manager.comic else {
             error!("Comic is not ready");
@@ -79,11 +364,6 @@ impl App {
 
         let series_path = self.get_current_series().path;
         let chapter_path = self.get_current_chapter().path;
-        let series_path = if series_path.ends_with(&self.config.komga.oneshots_dir) {
-            chapter_path.clone()
-        } else {
-            series_path
-        };
 
         let (comic_tx, comic_rx) = std::sync::mpsc::channel();
         self.comic_manager.comic_rx = Some(comic_rx);
@@ -137,11 +417,6 @@ impl App {
         };
 
         let series_path = self.get_current_series().path;
-        let series_path = if series_path.ends_with(&self.config.komga.oneshots_dir) {
-            self.get_current_chapter().path
-        } else {
-            series_path
-        };
 
         let komga_manager = self.komga_manager.clone();
         let status_tx = self.status_tx.clone();
@@ -177,11 +452,6 @@ impl App {
         };
 
         let series_path = self.get_current_series().path;
-        let series_path = if series_path.ends_with(&self.config.komga.oneshots_dir) {
-            self.get_current_chapter().path
-        } else {
-            series_path
-        };
 
         let komga_manager = self.komga_manager.clone();
         let komf_manager = self.komf_manager.clone();
@@ -211,6 +481,470 @@ impl App {
         });
     }
 
+    /// Looks for legacy encoded entry names to rewrite as UTF-8
+    pub fn handle_repair_names(&mut self) {
+        let chapters = self.get_chapters_in_series();
+        let encoding = self.config.archive_encoding.clone();
+        let status_tx = self.status_tx.clone();
+
+        let (preview_tx, preview_rx) = std::sync::mpsc::channel();
+        self.rename_manager.preview_rx = Some(preview_rx);
+
+        tokio::spawn(async move {
+            match preview_entry_names(chapters, encoding, status_tx).await {
+                Ok(preview) => {
+                    let _ = preview_tx.send(preview);
+                }
+                Err(e) => error!("Failed to check entry names: {e}"),
+            }
+        });
+    }
+
+    /// Applies the ticked rows of the current rename preview
+    pub fn handle_apply_rename(&mut self) {
+        if let Some(preview) = &self.rename_manager.preview
+            && preview.ticked_conflicts() > 0
+        {
+            let _ = self.status_tx.send(format!(
+                "Untick {} conflicting rows before renaming",
+                preview.ticked_conflicts()
+            ));
+            return;
+        }
+
+        self.popup = None;
+        let Some(preview) = self.rename_manager.preview.take() else {
+            return;
+        };
+
+        let (rows, skipped): (Vec<RenameRow>, Vec<RenameRow>) =
+            preview.rows.into_iter().partition(|row| row.apply);
+        if !skipped.is_empty() {
+            let _ = self
+                .status_tx
+                .send(format!("Skipped {} unticked renames", skipped.len()));
+        }
+        if rows.is_empty() {
+            return;
+        }
+
+        match preview.kind {
+            RenameKind::EntryNames => {
+                let mut tasks: Vec<JobTask> = Vec::new();
+                for row in rows {
+                    let task = tasks
+                        .iter_mut()
+                        .find(|t| t.chapter().path == row.chapter.path);
+                    match task {
+                        Some(JobTask::RepairEntryNames { names, .. }) => {
+                            names.insert(row.old, row.new);
+                        }
+                        _ => tasks.push(JobTask::RepairEntryNames {
+                            chapter: row.chapter,
+                            names: HashMap::from([(row.old, row.new)]),
+                        }),
+                    }
+                }
+                self.start_job(&preview.title, tasks);
+            }
+            RenameKind::Files => {
+                let status_tx = self.status_tx.clone();
+                let series_path = self.get_current_series().path;
+                let renames = rows
+                    .into_iter()
+                    .map(|row| {
+                        let to = row.chapter.path.with_file_name(&row.new);
+                        (row.chapter.path, to)
+                    })
+                    .collect();
+
+                let (renamed_tx, renamed_rx) = std::sync::mpsc::channel();
+                self.rename_manager.renamed_rx = Some(renamed_rx);
+                tokio::spawn(async move {
+                    let paths = rename_files(renames, status_tx).await;
+                    // Rebuild the list even after a rollback
+                    let _ = renamed_tx.send(RenamedFiles {
+                        series: series_path,
+                        paths,
+                    });
+                });
+            }
+        }
+    }
+
+    /// Moves the filename overrides and the index entries of renamed files to their new paths
+    pub fn move_renamed(&mut self, renamed: &[(PathBuf, PathBuf)]) {
+        let mut overrides_moved = false;
+        for (from, to) in renamed {
+            overrides_moved |= self.filename_parser.overrides.rename(from, to);
+        }
+        if overrides_moved && let Err(e) = self.filename_parser.overrides.save() {
+            error!("Failed to save filename overrides: {e}");
+        }
+
+        if let Ok(mut index) = self.index.lock() {
+            for (from, to) in renamed {
+                index.rename(from, to);
+            }
+        }
+    }
+
+    /// Opens the readings of the current chapter
+    pub fn handle_open_resolver(&mut self) {
+        let chapter = self.get_current_chapter();
+        self.parse_resolver.open(chapter, &self.filename_parser);
+        self.popup = Some(Popup::Resolve);
+    }
+
+    /// Stores the chosen reading as an override, or removes the override when the parsed
+    /// reading is chosen
+    pub fn handle_apply_reading(&mut self) {
+        self.popup = None;
+        let (Some(chapter), Some((source, reading, _))) =
+            (&self.parse_resolver.chapter, self.parse_resolver.selected())
+        else {
+            return;
+        };
+
+        let reading = (*source != ReadingSource::Parsed).then(|| reading.clone());
+        let label = reading
+            .as_ref()
+            .map_or_else(|| String::from("parsed reading"), ParseReading::label);
+        self.filename_parser.overrides.set(&chapter.path, reading);
+        if let Err(e) = self.filename_parser.overrides.save() {
+            error!("Failed to save filename overrides: {e}");
+        }
+
+        let _ = self.status_tx.send(format!("Using {label}"));
+        let series_path = self.get_current_series().path;
+        self.refresh_series(&series_path);
+    }
+
+    /// Analyzes the numbering of the current series, or of the whole library
+    pub fn handle_report(&mut self, library: bool) {
+        // Oneshots have no numbering to check
+        let series = if library {
+            self.series_list
+                .items_state
+                .iter()
+                .filter(|series| !series.oneshot)
+                .cloned()
+                .collect()
+        } else {
+            vec![self.get_current_series()]
+        };
+        let status_tx = self.status_tx.clone();
+
+        let (reports_tx, reports_rx) = std::sync::mpsc::channel();
+        self.report_manager.reports_rx = Some(reports_rx);
+        self.report_manager.library = library;
+
+        tokio::spawn(async move {
+            match build_reports(series, status_tx).await {
+                Ok(reports) => {
+                    let _ = reports_tx.send(reports);
+                }
+                Err(e) => error!("Failed to build report: {e}"),
+            }
+        });
+    }
+
+    /// Previews fixing the chapters of the current series with the lint rules
+    pub fn handle_lint_fixes(&mut self) {
+        let series = self.get_current_series();
+        let context = LintContext::of(&series);
+        let chapters = self.get_chapters_in_series();
+        let rules = self.config.lint_rules.clone();
+        let status_tx = self.status_tx.clone();
+        self.start_preview(preview_lint_fixes(chapters, rules, context, status_tx));
+    }
+
+    /// Sorts the chapters of the current series with the chapter view sorting
+    pub fn apply_chapter_sort(&mut self) {
+        let sort = self.chapter_view.sort;
+        let details = &self.details_manager;
+        let Some(series) = self
+            .series_list
+            .items_state
+            .get_mut(self.series_list.state.selected().unwrap_or(0))
+        else {
+            return;
+        };
+
+        series.chapters.sort_by(|a, b| match sort {
+            Some(sort) => sort.compare((a, details.get(&a.path)), (b, details.get(&b.path))),
+            None => a.cmp(b),
+        });
+        self.series_list.items = self.series_list.items_state.clone();
+    }
+
+    pub fn handle_open_filter(&mut self) {
+        self.chapter_view.error = None;
+        self.popup = Some(Popup::Filter);
+    }
+
+    /// Selects the chapters of the current series matching the filter, an empty filter clears
+    /// the selection
+    pub fn handle_apply_filter(&mut self) {
+        let filter: ChapterFilter = match self.chapter_view.filter.value().parse() {
+            Ok(filter) => filter,
+            Err(e) => {
+                self.chapter_view.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        let current = self.get_current_series().path;
+        if self.details_manager.series.as_ref() != Some(&current) {
+            self.chapter_view.error = Some(String::from("Chapter details are still loading"));
+            return;
+        }
+
+        let details = &self.details_manager;
+        let Some(series) = self
+            .series_list
+            .items_state
+            .get_mut(self.series_list.state.selected().unwrap_or(0))
+        else {
+            return;
+        };
+
+        let empty = self.chapter_view.filter.value().trim().is_empty();
+        series.chapters.selected = if empty {
+            HashSet::new()
+        } else {
+            series
+                .chapters
+                .items_state
+                .iter()
+                .enumerate()
+                .filter(|(_, c)| filter.matches(c, details.get(&c.path)))
+                .map(|(i, _)| i)
+                .collect()
+        };
+
+        let message = format!(
+            "Selected {}/{} chapters",
+            series.chapters.selected.len(),
+            series.chapters.items_state.len()
+        );
+        self.series_list.items = self.series_list.items_state.clone();
+        self.popup = None;
+        let _ = self.status_tx.send(message);
+    }
+
+    /// Counts the whole library for the stats dashboard
+    pub fn handle_stats(&mut self) {
+        let series = self.series_list.items_state.clone();
+        let encoding = self.config.archive_encoding.clone();
+        let index = self.index.clone();
+        let status_tx = self.status_tx.clone();
+
+        let (stats_tx, stats_rx) = std::sync::mpsc::channel();
+        self.stats_manager.stats_rx = Some(stats_rx);
+
+        tokio::spawn(async move {
+            match build_stats(series, encoding, index.clone(), status_tx).await {
+                Ok(stats) => {
+                    let _ = stats_tx.send(stats);
+                }
+                Err(e) => error!("Failed to build library stats: {e}"),
+            }
+            if let Ok(mut index) = index.lock()
+                && let Err(e) = index.save()
+            {
+                error!("Failed to save library index: {e}");
+            }
+        });
+    }
+
+    /// Writes the shown stats as JSON next to the config
+    pub fn handle_export_stats(&self) {
+        let Some(stats) = &self.stats_manager.stats else {
+            return;
+        };
+
+        let result = Config::get_stats_path().and_then(|path| {
+            fs::write(&path, serde_json::to_string_pretty(stats)?)?;
+            Ok(path)
+        });
+        let message = match result {
+            Ok(path) => format!("Exported stats to {}", path.display()),
+            Err(e) => {
+                error!("Failed to export stats: {e}");
+                format!("Failed to export stats: {e}")
+            }
+        };
+        let _ = self.status_tx.send(message);
+    }
+
+    /// Previews renaming the chapters of the current series with the rename template
+    pub fn handle_rename_files(&mut self) {
+        let chapters = self.get_chapters_in_series();
+        let template = self.config.rename_template.clone();
+        let status_tx = self.status_tx.clone();
+
+        let (preview_tx, preview_rx) = std::sync::mpsc::channel();
+        self.rename_manager.preview_rx = Some(preview_rx);
+
+        tokio::spawn(async move {
+            match preview_file_names(chapters, template, status_tx).await {
+                Ok(preview) => {
+                    let _ = preview_tx.send(preview);
+                }
+                Err(e) => error!("Failed to preview file names: {e}"),
+            }
+        });
+    }
+
+    /// Opens the filename pattern tester on the current series
+    pub fn handle_open_pattern_tester(&mut self) {
+        self.pattern_tester.chapters = self.get_current_series().chapters.items_state;
+        self.pattern_tester.state.select_first();
+        self.pattern_tester.test(&self.filename_parser);
+        self.popup = Some(Popup::Pattern);
+    }
+
+    /// Saves the tested pattern to the config with the highest priority
+    pub fn handle_save_pattern(&mut self) {
+        if self.pattern_tester.input.value().is_empty() || self.pattern_tester.error.is_some() {
+            return;
+        }
+
+        let mut pattern = self.pattern_tester.pattern();
+        pattern.name = format!("pattern {}", self.config.filename_patterns.len() + 1);
+        self.config.filename_patterns.insert(0, pattern);
+        let overrides = std::mem::take(&mut self.filename_parser.overrides);
+        self.filename_parser = FilenameParser::new(&self.config.filename_patterns, overrides);
+
+        if let Err(e) = self.config.save() {
+            error!("Failed to save config: {e}");
+            return;
+        }
+
+        self.pattern_tester.input.reset();
+        self.pattern_tester.test(&self.filename_parser);
+        let _ = self
+            .status_tx
+            .send("Saved filename pattern, press R to reparse the series".to_string());
+    }
+
+    /// Opens the `series.json` editor for the current series
+    pub fn handle_open_series_json(&mut self) {
+        let series = self.get_current_series();
+        if series.path.as_os_str().is_empty() {
+            return;
+        }
+        if series.oneshot {
+            let _ = self
+                .status_tx
+                .send("Oneshots have no series.json, their series info is in ComicInfo.xml".into());
+            return;
+        }
+        self.series_json_editor.open(&series);
+        self.popup = Some(Popup::SeriesJson);
+    }
+
+    /// Writes the edited `series.json` and shows it in the series list
+    pub fn handle_save_series_json(&mut self) {
+        let editor = &mut self.series_json_editor;
+        let Some(folder) = editor.folder.clone() else {
+            return;
+        };
+        let json = match editor.to_series_json() {
+            Ok(json) => json,
+            Err(e) => {
+                editor.error = Some(e);
+                return;
+            }
+        };
+
+        if let Err(e) = json.write(&folder) {
+            error!("Failed to write series.json ({}): {e}", folder.display());
+            editor.error = Some(format!("Failed to write: {e}"));
+            return;
+        }
+
+        if let Some(series) = self
+            .series_list
+            .items_state
+            .iter_mut()
+            .find(|s| s.path == folder)
+        {
+            series.series_json = Some(json);
+        }
+        // Re-sorts in case the name changed
+        self.series_list.update(Vec::new(), |_| true);
+
+        self.popup = None;
+        let _ = self.status_tx.send(format!(
+            "Saved {}",
+            folder.join(series_json::FILE_NAME).display()
+        ));
+    }
+
+    pub fn handle_open_volume_map(&mut self) {
+        let series = self.get_current_series();
+        if series.path.as_os_str().is_empty() {
+            return;
+        }
+        if series.oneshot {
+            let _ = self
+                .status_tx
+                .send("Oneshots have no volumes to map".to_string());
+            return;
+        }
+        self.volume_map_editor.open(&series);
+        self.popup = Some(Popup::VolumeMap);
+    }
+
+    /// Saves the volume map in `series.json`, gives the chapters their volumes and previews
+    /// writing them
+    pub fn handle_save_volume_map(&mut self) {
+        let editor = &mut self.volume_map_editor;
+        let Some(folder) = editor.folder.clone() else {
+            return;
+        };
+        let map = match editor.map() {
+            Ok(map) => map,
+            Err(e) => {
+                editor.error = Some(e.to_string());
+                return;
+            }
+        };
+
+        let series = self.get_current_series();
+        let mut json = series
+            .series_json
+            .unwrap_or_else(|| SeriesJson::from_folder(&series.folder));
+        json.volume_map.clone_from(&map);
+        if let Err(e) = json.write(&folder) {
+            error!("Failed to write series.json ({}): {e}", folder.display());
+            self.volume_map_editor.error = Some(format!("Failed to write: {e}"));
+            return;
+        }
+
+        if let Some(series) = self
+            .series_list
+            .items_state
+            .iter_mut()
+            .find(|s| s.path == folder)
+        {
+            series.series_json = Some(json);
+        }
+        self.popup = None;
+        // Chapters whose volume came from the old map are read again
+        self.refresh_series(&folder);
+
+        let Some(map) = map else {
+            let _ = self.status_tx.send("Removed the volume map".to_string());
+            return;
+        };
+        let chapters = self.get_chapters_in_series();
+        let status_tx = self.status_tx.clone();
+        self.start_preview(preview_volume_map(chapters, map, status_tx));
+    }
+
     /// Clears the chapter selection
     pub fn handle_esc_selection(&mut self) {
         let current = self.series_list.state.selected().unwrap_or_default();
@@ -231,6 +965,23 @@ impl App {
     /// Refreshes the chapter list
     pub fn handle_refresh(&mut self) {
         let series_path = self.get_current_series().path;
+        self.refresh_series(&series_path);
+    }
+
+    /// Reads the chapters of a series from disk again
+    pub fn refresh_series(&mut self, series_path: &Path) {
+        // A oneshot may have been renamed, so its whole folder is scanned again
+        if let Some(series) = self
+            .series_list
+            .items_state
+            .iter()
+            .find(|v| v.path == series_path && v.oneshot)
+            && let Some(folder) = series.path.parent()
+        {
+            self.apply_library_changes(vec![folder.to_path_buf()]);
+            let _ = self.status_tx.send("Refreshed oneshots".to_string());
+            return;
+        }
 
         if let Some(series) = self
             .series_list
@@ -238,7 +989,15 @@ impl App {
             .iter_mut()
             .find(|v| v.path == series_path)
         {
-            let Ok(mut new_chapters) = crate::data::get_cbz_list(&series_path) else {
+            let Ok(mut index) = self.index.lock() else {
+                return;
+            };
+            let Ok(mut new_chapters) = crate::data::get_cbz_list(
+                series_path,
+                &self.filename_parser,
+                series.language.as_deref(),
+                &mut index,
+            ) else {
                 error!(
                     "Failed to get cbz list for series ({})",
                     series_path.display()
@@ -246,15 +1005,90 @@ impl App {
                 return;
             };
 
+            drop(index);
+
+            if let Some(map) = series.volume_map() {
+                map.assign(&mut new_chapters);
+            }
             new_chapters.sort();
-            series.chapters = ChapterList::from_iter(new_chapters);
+            series.chapters.replace(new_chapters);
             self.series_list.items = self.series_list.items_state.clone();
+            self.details_manager.invalidate();
+            self.apply_chapter_sort();
 
             let _ = self
                 .status_tx
                 .send("Refreshed chapters list in series".to_string());
         }
     }
+
+    /// Updates the lists after files changed on disk. Changed archives and new folders are
+    /// scanned, series that are gone or lost their archives are dropped.
+    pub fn apply_library_changes(&mut self, paths: Vec<PathBuf>) {
+        let is_series_file = |path: &Path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("cbz"))
+                || path.ends_with(series_json::FILE_NAME)
+        };
+
+        // An archive or series.json changes its folder, a new folder may hold whole series
+        let mut folders: HashSet<PathBuf> = HashSet::new();
+        for path in paths {
+            if path.is_dir() {
+                folders.insert(path);
+            } else if is_series_file(&path)
+                && let Some(parent) = path.parent()
+                && parent.is_dir()
+            {
+                folders.insert(parent.to_path_buf());
+            }
+        }
+
+        let roots = self.config.libraries();
+        let Ok(mut index) = self.index.lock() else {
+            return;
+        };
+        let mut changed = Vec::new();
+        let mut scanned = Vec::new();
+        for folder in folders {
+            // The innermost root wins when roots are nested
+            let Some(root) = roots
+                .iter()
+                .filter(|root| folder.starts_with(&root.path))
+                .max_by_key(|root| root.path.len())
+            else {
+                continue;
+            };
+            changed.extend(scan_folder(
+                root,
+                &folder,
+                &self.filename_parser,
+                &self.folder_parser,
+                &mut index,
+            ));
+            scanned.push(folder);
+        }
+        drop(index);
+
+        let found: HashSet<PathBuf> = changed.iter().map(|s| s.path.clone()).collect();
+        let before = self.series_list.items_state.len();
+        let updated = changed.len();
+        self.series_list.update(changed, |series| {
+            series.path.exists()
+                && (found.contains(&series.path)
+                    || !scanned.iter().any(|folder| series.path.starts_with(folder)))
+        });
+        self.details_manager.invalidate();
+        self.apply_chapter_sort();
+
+        debug!(
+            "Library changed: {updated} series scanned, {before} -> {} series",
+            self.series_list.items_state.len()
+        );
+        let _ = self
+            .status_tx
+            .send("Updated the library after changes on disk".to_string());
+    }
 }
 
 impl App {
@@ -1,9 +1,169 @@
 use std::{collections::HashSet, path::PathBuf};
 
 use ratatui::widgets::{ListState, ScrollbarState};
+use serde::{Deserialize, Serialize};
+
+/// Chapters parsed with a lower confidence are highlighted for review
+pub const LOW_CONFIDENCE: u8 = 70;
+
+/// Kind of chapter, anything but [`ChapterKind::Regular`] usually has no chapter number
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum ChapterKind {
+    Prologue,
+    #[default]
+    Regular,
+    Special,
+    Extra,
+    Omake,
+    Bonus,
+    SideStory,
+    Oneshot,
+    Epilogue,
+}
+
+/// CJK words marking a kind, glued to chapter markers as often as not: "第12話番外編"
+const CJK_KINDS: [(&str, ChapterKind); 19] = [
+    ("番外編", ChapterKind::Extra),
+    ("番外篇", ChapterKind::Extra),
+    ("番外", ChapterKind::Extra),
+    ("번외편", ChapterKind::Extra),
+    ("外伝", ChapterKind::SideStory),
+    ("外传", ChapterKind::SideStory),
+    ("외전", ChapterKind::SideStory),
+    ("特別編", ChapterKind::Special),
+    ("特别篇", ChapterKind::Special),
+    ("특별편", ChapterKind::Special),
+    ("おまけ", ChapterKind::Omake),
+    ("序章", ChapterKind::Prologue),
+    ("プロローグ", ChapterKind::Prologue),
+    ("프롤로그", ChapterKind::Prologue),
+    ("終章", ChapterKind::Epilogue),
+    ("エピローグ", ChapterKind::Epilogue),
+    ("에필로그", ChapterKind::Epilogue),
+    ("読み切り", ChapterKind::Oneshot),
+    ("読切", ChapterKind::Oneshot),
+];
+
+impl ChapterKind {
+    /// Kind marked by a CJK word at the start of `text`, with the rest of the text
+    pub fn strip_cjk(text: &str) -> Option<(Self, &str)> {
+        CJK_KINDS
+            .iter()
+            .find_map(|(word, kind)| text.strip_prefix(word).map(|rest| (*kind, rest)))
+    }
+
+    /// Kind marked by a single filename token
+    pub fn from_token(token: &str) -> Option<Self> {
+        if let Some((kind, "")) = Self::strip_cjk(token) {
+            return Some(kind);
+        }
+
+        match token.to_lowercase().trim_end_matches('.') {
+            "special" | "specials" | "sp" => Some(Self::Special),
+            "extra" | "extras" => Some(Self::Extra),
+            "omake" => Some(Self::Omake),
+            "bonus" => Some(Self::Bonus),
+            "sidestory" => Some(Self::SideStory),
+            "prologue" => Some(Self::Prologue),
+            "epilogue" => Some(Self::Epilogue),
+            "oneshot" => Some(Self::Oneshot),
+            _ => None,
+        }
+    }
+
+    /// Kind marked by two filename tokens like "Side Story"
+    pub fn from_tokens(first: &str, second: &str) -> Option<Self> {
+        match (
+            first.to_lowercase().as_str(),
+            second.to_lowercase().as_str(),
+        ) {
+            ("side", "story") => Some(Self::SideStory),
+            ("one", "shot") => Some(Self::Oneshot),
+            _ => None,
+        }
+    }
+
+    /// Short label shown instead of the chapter number
+    pub fn short(self) -> &'static str {
+        match self {
+            Self::Prologue => "PRO",
+            Self::Regular => "",
+            Self::Special => "SP",
+            Self::Extra => "EX",
+            Self::Omake => "OM",
+            Self::Bonus => "BN",
+            Self::SideStory => "SS",
+            Self::Oneshot => "OS",
+            Self::Epilogue => "EPI",
+        }
+    }
+
+    /// Value for the `Format` field of `ComicInfo.xml`
+    pub fn format(self) -> Option<&'static str> {
+        match self {
+            Self::Prologue => Some("Prologue"),
+            Self::Regular => None,
+            Self::Special => Some("Special"),
+            Self::Extra => Some("Extra"),
+            Self::Omake => Some("Omake"),
+            Self::Bonus => Some("Bonus"),
+            Self::SideStory => Some("Side Story"),
+            Self::Oneshot => Some("One-Shot"),
+            Self::Epilogue => Some("Epilogue"),
+        }
+    }
+}
+
+/// One way to read a filename, also stored as a manual override
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParseReading {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub volume: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chapter_end: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+}
+
+impl ParseReading {
+    pub fn of(chapter: &Chapter) -> Self {
+        Self {
+            volume: chapter.volume,
+            chapter: chapter.chapter,
+            chapter_end: chapter.chapter_end,
+            title: chapter.title.clone(),
+        }
+    }
+
+    pub fn apply(&self, chapter: &mut Chapter) {
+        chapter.volume = self.volume;
+        chapter.chapter = self.chapter;
+        chapter.chapter_end = self.chapter_end;
+        chapter.title.clone_from(&self.title);
+    }
+
+    /// Short description like "Vol.3 Ch.12-14 - Title"
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if let Some(volume) = self.volume {
+            parts.push(format!("Vol.{volume}"));
+        }
+        match (self.chapter, self.chapter_end) {
+            (Some(start), Some(end)) => parts.push(format!("Ch.{start}-{end}")),
+            (Some(start), None) => parts.push(format!("Ch.{start}")),
+            (None, _) => parts.push(String::from("No chapter")),
+        }
+        if let Some(title) = &self.title {
+            parts.push(format!("- {title}"));
+        }
+        parts.join(" ")
+    }
+}
 
 /// Chapter of a series from disk
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Chapter {
     /// Path to the chapter (cbz file)
     pub path: PathBuf,
@@ -15,11 +175,36 @@ pub struct Chapter {
     #[allow(clippy::struct_field_names)]
     pub chapter: Option<f32>,
 
+    /// Last chapter of a range like "Ch. 001-005"
+    #[allow(clippy::struct_field_names)]
+    pub chapter_end: Option<f32>,
+
     /// Title of the chapter
     pub title: Option<String>,
 
     /// Translators
     pub translators: Vec<String>,
+
+    /// Series name found in the filename
+    pub series: Option<String>,
+
+    /// Release year found in the filename
+    pub year: Option<u16>,
+
+    /// Language tag found in the filename
+    pub language: Option<String>,
+
+    /// Kind of the chapter
+    pub kind: ChapterKind,
+
+    /// How sure the parser is about the reading, from 0 to 100
+    pub confidence: u8,
+
+    /// Other ways to read the filename
+    pub alternatives: Vec<ParseReading>,
+
+    /// Whether the reading comes from a manual override
+    pub overridden: bool,
 }
 
 impl Default for Chapter {
@@ -28,15 +213,26 @@ impl Default for Chapter {
             path: PathBuf::new(),
             volume: None,
             chapter: None,
+            chapter_end: None,
             title: None,
             translators: vec![],
+            series: None,
+            year: None,
+            language: None,
+            kind: ChapterKind::Regular,
+            confidence: 100,
+            alternatives: Vec::new(),
+            overridden: false,
         }
     }
 }
 
 impl PartialEq for Chapter {
     fn eq(&self, other: &Self) -> bool {
-        self.volume == other.volume && self.chapter == other.chapter
+        self.volume == other.volume
+            && self.chapter == other.chapter
+            && self.chapter_end == other.chapter_end
+            && self.kind == other.kind
     }
 }
 impl Eq for Chapter {}
@@ -49,12 +245,17 @@ impl PartialOrd for Chapter {
 
 impl Ord for Chapter {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let c1 = self.chapter.unwrap_or(0.0);
-        let c2 = other.chapter.unwrap_or(0.0);
-        match c1.partial_cmp(&c2).unwrap_or(std::cmp::Ordering::Equal) {
-            std::cmp::Ordering::Equal => self.path.cmp(&other.path),
-            non_eq => non_eq,
-        }
+        self.sort_bucket()
+            .cmp(&other.sort_bucket())
+            .then(self.sort_volume().cmp(&other.sort_volume()))
+            .then(self.sort_number().total_cmp(&other.sort_number()))
+            .then(
+                self.chapter_end
+                    .unwrap_or_default()
+                    .total_cmp(&other.chapter_end.unwrap_or_default()),
+            )
+            .then(self.kind.cmp(&other.kind))
+            .then_with(|| self.path.cmp(&other.path))
     }
 }
 
@@ -95,6 +296,38 @@ impl FromIterator<Chapter> for ChapterList {
 }
 
 impl ChapterList {
+    /// Swaps in a new listing, keeping the cursor and the multi-selection on the same files
+    pub fn replace(&mut self, chapters: Vec<Chapter>) {
+        let path_at = |i: usize| self.items_state.get(i).map(|c| c.path.clone());
+        let cursor = self.state.selected().and_then(path_at);
+        let selected: HashSet<PathBuf> = self.selected.iter().filter_map(|&i| path_at(i)).collect();
+
+        self.selected = chapters
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| selected.contains(&c.path))
+            .map(|(i, _)| i)
+            .collect();
+
+        // A removed chapter leaves the cursor where it was
+        let last = chapters.len().saturating_sub(1);
+        let position = cursor
+            .and_then(|path| chapters.iter().position(|c| c.path == path))
+            .or_else(|| self.state.selected().map(|i| i.min(last)));
+
+        self.scroll_state = self.scroll_state.content_length(chapters.len());
+        self.items.clone_from(&chapters);
+        self.items_state = chapters;
+        self.state.select(position);
+    }
+
+    /// Reorders the chapters, keeping the cursor and the multi-selection on the same files
+    pub fn sort_by(&mut self, compare: impl FnMut(&Chapter, &Chapter) -> std::cmp::Ordering) {
+        let mut chapters = self.items_state.clone();
+        chapters.sort_by(compare);
+        self.replace(chapters);
+    }
+
     pub fn toggle_selected(&mut self) {
         if let Some(index) = self.state.selected()
             && !self.selected.insert(index)
@@ -105,6 +338,64 @@ impl ChapterList {
 }
 
 impl Chapter {
+    /// Whether the filename should be reviewed
+    pub fn is_uncertain(&self) -> bool {
+        self.confidence < LOW_CONFIDENCE
+    }
+
+    /// Whether this is a whole volume archive like "Vol. 05.cbz" without a chapter number
+    pub fn is_volume(&self) -> bool {
+        self.kind == ChapterKind::Regular && self.chapter.is_none() && self.volume.is_some()
+    }
+
+    /// Coarse position in the series: unnumbered prologues without a volume first, then volume
+    /// archives, then the chapters and specials, and unnumbered epilogues without a volume last
+    fn sort_bucket(&self) -> u8 {
+        if self.is_volume() {
+            return 1;
+        }
+
+        match (self.kind, self.chapter, self.volume) {
+            (ChapterKind::Prologue, None, None) => 0,
+            (ChapterKind::Epilogue, None, None) => 3,
+            _ => 2,
+        }
+    }
+
+    /// Volume the chapter is sorted in, chapters without one come after every volume
+    pub fn sort_volume(&self) -> u32 {
+        self.volume.unwrap_or(u32::MAX)
+    }
+
+    /// Position inside the volume. Unnumbered prologues come before its first chapter and the
+    /// other unnumbered specials after its last one.
+    fn sort_number(&self) -> f64 {
+        if self.is_volume() {
+            return self.volume.map(f64::from).unwrap_or_default();
+        }
+
+        match (self.kind, self.chapter) {
+            (_, Some(number)) => f64::from(number),
+            (ChapterKind::Regular, None) => 0.0,
+            (ChapterKind::Prologue, None) => f64::NEG_INFINITY,
+            (_, None) => f64::INFINITY,
+        }
+    }
+
+    /// Chapter number, range, volume or kind padded to 5 characters
+    pub fn number_label(&self) -> String {
+        match (self.kind, self.chapter, self.chapter_end) {
+            _ if self.is_volume() => {
+                format!("{:>5}", format!("v{}", self.volume.unwrap_or_default()))
+            }
+            (_, Some(start), Some(end)) => format!("{:>5}", format!("{start}-{end}")),
+            (ChapterKind::Regular, _, _) | (_, Some(_), _) => {
+                format!("{:#5.}", self.chapter.unwrap_or_default())
+            }
+            (kind, None, _) => format!("{:>5}", kind.short()),
+        }
+    }
+
     pub fn get_title(&self, selected: bool) -> String {
         let selected_char = if selected {
             String::from("▌")
@@ -113,9 +404,9 @@ impl Chapter {
         };
 
         format!(
-            "{}{:#5.}: {}",
+            "{}{}: {}",
             selected_char,
-            self.chapter.unwrap_or_default(),
+            self.number_label(),
             self.title.clone().unwrap_or(
                 self.path
                     .file_name()
@@ -126,3 +417,47 @@ impl Chapter {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapters(names: &[&str]) -> Vec<Chapter> {
+        names
+            .iter()
+            .map(|name| Chapter {
+                path: PathBuf::from(name),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_replace_keeps_selection() {
+        let mut list = ChapterList::from_iter(chapters(&["a", "b", "c"]));
+        list.state.select(Some(1));
+        list.selected.extend([1, 2]);
+
+        // A new file lands in front of the selected ones
+        list.replace(chapters(&["0", "a", "b", "c"]));
+        assert_eq!(list.state.selected(), Some(2));
+        assert_eq!(list.selected, HashSet::from([2, 3]));
+
+        // The chapter under the cursor is gone
+        list.replace(chapters(&["0", "a", "c"]));
+        assert_eq!(list.state.selected(), Some(2));
+        assert_eq!(list.selected, HashSet::from([2]));
+    }
+
+    #[test]
+    fn test_sort_keeps_selection() {
+        let mut list = ChapterList::from_iter(chapters(&["a", "b", "c"]));
+        list.state.select(Some(0));
+        list.selected.insert(2);
+
+        list.sort_by(|a, b| b.path.cmp(&a.path));
+        assert_eq!(list.items[0].path, PathBuf::from("c"));
+        assert_eq!(list.state.selected(), Some(2));
+        assert_eq!(list.selected, HashSet::from([0]));
+    }
+}
@@ -1,5 +1,5 @@
 mod chapter;
 mod series;
 
-pub use chapter::{Chapter, ChapterList};
-pub use series::{Series, SeriesList};
+pub use chapter::{Chapter, ChapterKind, ChapterList, LOW_CONFIDENCE, ParseReading};
+pub use series::{Series, SeriesFolder, SeriesList};
@@ -4,18 +4,76 @@ use ratatui::widgets::{ListItem, ListState, ScrollbarState};
 use tui_input::Input;
 
 use super::ChapterList;
+use crate::{series_json::SeriesJson, volume_map::VolumeMap};
+
+/// Data found in a series folder name like "Berserk (1989) [Dark Horse] {anilist-30002}"
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeriesFolder {
+    /// Name without the parsed parts
+    pub title: String,
+
+    /// Year the series started
+    pub year: Option<u16>,
+
+    /// Publisher of the series
+    pub publisher: Option<String>,
+
+    /// Year used as the volume of US comics, like "v2016"
+    pub volume_year: Option<u32>,
+
+    /// External ids as (source, id), like ("anilist", "30002")
+    pub ids: Vec<(String, String)>,
+}
+
+impl SeriesFolder {
+    /// Links to the known external ids
+    pub fn web(&self) -> Option<String> {
+        let links: Vec<String> = self
+            .ids
+            .iter()
+            .filter_map(|(source, id)| {
+                let url = match source.to_lowercase().as_str() {
+                    "anilist" | "al" => "https://anilist.co/manga/",
+                    "mal" | "myanimelist" => "https://myanimelist.net/manga/",
+                    "mangadex" | "md" => "https://mangadex.org/title/",
+                    "mangaupdates" | "mu" => "https://www.mangaupdates.com/series/",
+                    "kitsu" => "https://kitsu.app/manga/",
+                    "comicvine" | "cv" => "https://comicvine.gamespot.com/volume/4050-",
+                    _ => return None,
+                };
+                Some(format!("{url}{id}"))
+            })
+            .collect();
+
+        (!links.is_empty()).then(|| links.join(" "))
+    }
+}
 
 /// Series from disk
 #[derive(Debug, Clone, Default)]
 pub struct Series {
-    /// Path to the series
+    /// Path to the series folder, or to the archive of a oneshot. Komga uses the same path as
+    /// the url of the series.
     pub path: PathBuf,
 
-    /// Name of the series
+    /// Name of the series folder, or file stem of a oneshot
     pub name: String,
 
+    /// Data parsed from the folder name
+    pub folder: SeriesFolder,
+
     /// Chapters of the series
     pub chapters: ChapterList,
+
+    /// Default language of the library root
+    pub language: Option<String>,
+
+    /// Single archive of the oneshots folder
+    pub oneshot: bool,
+
+    /// Contents of `series.json` in the folder
+    #[allow(clippy::struct_field_names)]
+    pub series_json: Option<SeriesJson>,
 }
 
 impl PartialEq for Series {
@@ -33,12 +91,28 @@ impl PartialOrd for Series {
 
 impl Ord for Series {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        let c1 = &self.name.to_lowercase();
-        let c2 = &other.name.to_lowercase();
+        let c1 = &self.title().to_lowercase();
+        let c2 = &other.title().to_lowercase();
         c1.partial_cmp(c2).unwrap_or(std::cmp::Ordering::Equal)
     }
 }
 
+impl Series {
+    /// Name from `series.json`, else the clean name falling back to the folder name
+    pub fn title(&self) -> &str {
+        match &self.series_json {
+            Some(json) if !json.metadata.name.trim().is_empty() => &json.metadata.name,
+            _ if self.folder.title.is_empty() => &self.name,
+            _ => &self.folder.title,
+        }
+    }
+
+    /// Chapter to volume table from `series.json`
+    pub fn volume_map(&self) -> Option<&VolumeMap> {
+        self.series_json.as_ref()?.volume_map.as_ref()
+    }
+}
+
 /// List of series
 pub struct SeriesList {
     /// Shown state of projects
@@ -61,6 +135,41 @@ pub struct SeriesList {
 }
 
 impl SeriesList {
+    /// Adds or updates `changed` series and drops the ones `keep` rejects, keeping the cursor and
+    /// chapter selections on the same series
+    pub fn update(&mut self, changed: Vec<Series>, keep: impl Fn(&Series) -> bool) {
+        let cursor = self
+            .state
+            .selected()
+            .and_then(|i| self.items_state.get(i))
+            .map(|s| s.path.clone());
+
+        self.items_state.retain(keep);
+        for series in changed {
+            match self.items_state.iter_mut().find(|s| s.path == series.path) {
+                Some(existing) => {
+                    existing.chapters.replace(series.chapters.items_state);
+                    existing.folder = series.folder;
+                    existing.language = series.language;
+                    existing.oneshot = series.oneshot;
+                    existing.series_json = series.series_json;
+                }
+                None => self.items_state.push(series),
+            }
+        }
+        self.items_state.sort();
+
+        let last = self.items_state.len().saturating_sub(1);
+        let position = cursor
+            .and_then(|path| self.items_state.iter().position(|s| s.path == path))
+            .or_else(|| self.state.selected().map(|i| i.min(last)));
+
+        self.scroll_state = self.scroll_state.content_length(self.items_state.len());
+        self.items.clone_from(&self.items_state);
+        self.state.select(position);
+        self.found = (0, Vec::new());
+    }
+
     pub fn search(&mut self) {
         let Some(search_text) = &self.search_text else {
             return;
@@ -71,7 +180,7 @@ impl SeriesList {
             .iter()
             .enumerate()
             .filter(|(_, p)| {
-                p.name
+                p.title()
                     .to_lowercase()
                     .contains(&search_text.value().to_lowercase())
             })
@@ -145,6 +254,6 @@ impl FromIterator<Series> for SeriesList {
 
 impl From<&Series> for ListItem<'_> {
     fn from(value: &Series) -> Self {
-        ListItem::new(value.name.clone())
+        ListItem::new(value.title().to_string())
     }
 }
@@ -1,7 +1,9 @@
 //! UI for the application
 
 use std::{
+    fs,
     path::PathBuf,
+    sync::{Arc, Mutex, PoisonError},
     time::{Duration, Instant},
 };
 
@@ -15,12 +17,31 @@ use tokio::sync::watch;
 use tui_input::{Input, backend::crossterm::EventHandler};
 
 use crate::{
-    config::Config,
+    chapter_manager::read_series_details,
+    config::{Config, MetadataPresets},
+    data::{FilenameParser, ScanEvent, SeriesFolderParser, scan_library},
+    index::{FileKey, LibraryIndex, write_thumbnail},
     managers::{
+        chapter_view::ChapterView,
         comic_form::{ComicFormState, ComicInfoForm, ComicInfoManager},
+        details::DetailsManager,
+        fields::FieldPicker,
         image::{ImageManager, ImagesState},
+        jobs::{JobEvent, JobState, JobsManager},
         komf::KomfManager,
         komga::KomgaManager,
+        pattern::PatternTester,
+        preview::PreviewManager,
+        registers::Registers,
+        rename::RenameManager,
+        report::ReportManager,
+        resolve::ParseResolver,
+        scan::LibraryScan,
+        series_json::SeriesJsonEditor,
+        stats::StatsManager,
+        transform::{TransformDialog, TransformFocus},
+        volume_map::VolumeMapEditor,
+        watcher::LibraryWatcher,
     },
     ui::list::{Chapter, Series, SeriesList},
     zip_util::get_comic_from_zip,
@@ -44,6 +65,37 @@ pub enum Tab {
     Search,
 }
 
+/// Popup capturing the keyboard until closed
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Popup {
+    /// Dry-run preview of a batch write
+    Preview,
+    /// Old → new names before renaming
+    Rename,
+    /// Filename pattern tester
+    Pattern,
+    /// Missing and duplicate chapters
+    Report,
+    /// Readings of an ambiguous filename
+    Resolve,
+    /// Editor of `series.json`
+    SeriesJson,
+    /// Library statistics
+    Stats,
+    /// Filter selecting chapters
+    Filter,
+    /// Queued and finished batch writes
+    Jobs,
+    /// Fields copied to the selected chapters
+    Fields,
+    /// Transform of a field of the selected chapters
+    Transform,
+    /// Chapter to volume table of the series
+    VolumeMap,
+    /// Yank registers and metadata presets
+    Registers,
+}
+
 /// Current input mode
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
 enum InputMode {
@@ -75,9 +127,57 @@ pub struct App {
     /// Comic form state
     comic_manager: ComicInfoManager,
 
+    /// Batch write preview state
+    preview_manager: PreviewManager,
+
+    /// Rename preview state
+    rename_manager: RenameManager,
+
+    /// Filename pattern tester state
+    pattern_tester: PatternTester,
+
+    /// Numbering report state
+    report_manager: ReportManager,
+
+    /// Filename reading resolver state
+    parse_resolver: ParseResolver,
+
+    /// Background library scan progress
+    library_scan: LibraryScan,
+
+    /// `series.json` editor state
+    series_json_editor: SeriesJsonEditor,
+
+    /// Library statistics state
+    stats_manager: StatsManager,
+
+    /// Archive data and lint issues of the current series
+    details_manager: DetailsManager,
+
+    /// Sorting and filtering of the chapters list
+    chapter_view: ChapterView,
+
+    /// Batch writes running in the background
+    jobs_manager: JobsManager,
+
+    /// Fields chosen for batch updates
+    field_picker: FieldPicker,
+
+    /// Field transform dialog state
+    transform_dialog: TransformDialog,
+
+    /// Chapter to volume table editor state
+    volume_map_editor: VolumeMapEditor,
+
+    /// Yanked metadata and presets
+    registers: Registers,
+
     /// Help flag
     show_help: bool,
 
+    /// Currently open popup
+    popup: Option<Popup>,
+
     /// Current input mode
     input_mode: InputMode,
 
@@ -93,18 +193,41 @@ pub struct App {
     /// Sender channel for status
     status_tx: watch::Sender<String>,
 
+    /// Parser for chapter filenames
+    filename_parser: FilenameParser,
+
+    /// Parser for series folder names
+    folder_parser: SeriesFolderParser,
+
+    /// Watcher of the library roots, if it could be started
+    library_watcher: Option<LibraryWatcher>,
+
+    /// Cached archive data, shared with background loads
+    index: Arc<Mutex<LibraryIndex>>,
+
     config: Config,
 }
 
 impl Default for App {
     fn default() -> Self {
-        Self::new(vec![], Config::default()).expect("Failed to create app")
+        Self::new(
+            Config::default(),
+            FilenameParser::default(),
+            SeriesFolderParser::default(),
+            LibraryIndex::default(),
+        )
+        .expect("Failed to create app")
     }
 }
 
 impl App {
     /// Create a new application
-    pub fn new(series_list: Vec<Series>, config: Config) -> anyhow::Result<Self> {
+    pub fn new(
+        config: Config,
+        filename_parser: FilenameParser,
+        folder_parser: SeriesFolderParser,
+        index: LibraryIndex,
+    ) -> anyhow::Result<Self> {
         let picker = Picker::from_query_stdio()?;
 
         let mut fields_state = ListState::default();
@@ -112,24 +235,75 @@ impl App {
 
         let (status_tx, status_rx) = watch::channel("Idle".to_string());
 
+        let library_watcher = LibraryWatcher::new(&config.libraries())
+            .inspect_err(|e| error!("Failed to watch the library: {e}"))
+            .ok();
+
+        let presets = MetadataPresets::read().unwrap_or_else(|e| {
+            error!("Failed to read metadata presets: {e}");
+            MetadataPresets::default()
+        });
+
         Ok(Self {
             should_exit: false,
             current_tab: Tab::SeriesList,
-            series_list: SeriesList::from_iter(series_list),
+            series_list: SeriesList::from_iter(Vec::new()),
             image_manager: ImageManager::new(picker),
             komga_manager: KomgaManager::new(&config.komga.url, &config.komga.api_key)?,
             komf_manager: KomfManager::new(&config.komf.url)?,
             comic_manager: ComicInfoManager::new(),
+            preview_manager: PreviewManager::new(),
+            rename_manager: RenameManager::new(),
+            pattern_tester: PatternTester::new(),
+            report_manager: ReportManager::new(),
+            parse_resolver: ParseResolver::new(),
+            library_scan: LibraryScan::new(),
+            series_json_editor: SeriesJsonEditor::new(),
+            stats_manager: StatsManager::new(),
+            details_manager: DetailsManager::new(),
+            chapter_view: ChapterView::new(),
+            jobs_manager: JobsManager::new(),
+            field_picker: FieldPicker::new(),
+            transform_dialog: TransformDialog::new(),
+            volume_map_editor: VolumeMapEditor::new(),
+            registers: Registers::new(presets),
             show_help: false,
+            popup: None,
             input_mode: InputMode::Normal,
             last_selection_change: None,
             pending_selection: None,
             status_rx,
             status_tx,
+            filename_parser,
+            folder_parser,
+            library_watcher,
+            index: Arc::new(Mutex::new(index)),
             config,
         })
     }
 
+    /// Scans the library in the background, series show up in the list as they're found
+    pub fn start_scan(&mut self) {
+        let (scan_tx, scan_rx) = std::sync::mpsc::channel();
+        self.library_scan = LibraryScan::new();
+        self.library_scan.scan_rx = Some(scan_rx);
+
+        let roots = self.config.libraries();
+        let parser = self.filename_parser.clone();
+        let folder_parser = self.folder_parser.clone();
+        let index = self.index.clone();
+        tokio::task::spawn_blocking(move || {
+            scan_library(&roots, &parser, &folder_parser, &index, &scan_tx);
+
+            let mut index = index.lock().unwrap_or_else(PoisonError::into_inner);
+            index.prune();
+            if let Err(e) = index.save() {
+                error!("Failed to save library index: {e}");
+            }
+            debug!("Indexed {} archives", index.len());
+        });
+    }
+
     /// Run the application
     pub fn run(mut self, mut terminal: DefaultTerminal) -> anyhow::Result<()> {
         let mut last_tick = std::time::Instant::now();
@@ -148,6 +322,12 @@ impl App {
             }
         }
 
+        if let Ok(mut index) = self.index.lock()
+            && let Err(e) = index.save()
+        {
+            error!("Failed to save library index: {e}");
+        }
+
         Ok(())
     }
 
@@ -155,6 +335,14 @@ impl App {
         // check for finished async loads
         self.poll_comic_info();
         self.poll_images();
+        self.poll_preview();
+        self.poll_rename();
+        self.poll_report();
+        self.poll_stats();
+        self.poll_scan();
+        self.poll_watcher();
+        self.poll_details();
+        self.poll_jobs();
         self.image_manager.poll_image_updates();
 
         // debounce loading
@@ -174,7 +362,11 @@ impl App {
             return;
         }
 
-        if self.current_tab == Tab::Metadata {
+        if let Some(popup) = self.popup {
+            self.handle_key_popup(popup, key);
+        } else if self.registers.naming {
+            self.handle_key_register_name(key);
+        } else if self.current_tab == Tab::Metadata {
             self.handle_key_metadata(key);
         } else if self.current_tab == Tab::Search {
             self.handle_key_search(key);
@@ -194,6 +386,34 @@ impl App {
                 KeyCode::Char('l') | KeyCode::Enter => self.next_tab(),
                 KeyCode::Char('h') => self.previous_tab(),
                 KeyCode::Char('R') => self.handle_refresh(),
+                KeyCode::Char('E') => self.handle_repair_names(),
+                KeyCode::Char('P') => self.handle_open_pattern_tester(),
+                KeyCode::Char('N') => self.handle_rename_files(),
+                KeyCode::Char('m') => self.handle_report(false),
+                KeyCode::Char('M') => self.handle_report(true),
+                KeyCode::Char('J') => self.handle_open_series_json(),
+                KeyCode::Char('S') => self.handle_stats(),
+                KeyCode::Char('L') => self.handle_lint_fixes(),
+                KeyCode::Char('o') => {
+                    self.chapter_view.next_sort();
+                    self.apply_chapter_sort();
+                }
+                KeyCode::Char('O') => {
+                    self.chapter_view.reverse_sort();
+                    self.apply_chapter_sort();
+                }
+                KeyCode::Char('f') => self.handle_open_filter(),
+                KeyCode::Char('b') => self.handle_open_jobs(),
+                KeyCode::Char('t') => self.handle_open_transform(),
+                KeyCode::Char('V') => self.handle_open_volume_map(),
+                KeyCode::Char('"') => self.registers.naming = true,
+                KeyCode::Char('y') => self.handle_yank(false),
+                KeyCode::Char('Y') => self.handle_yank(true),
+                KeyCode::Char('p') => self.handle_paste(),
+                KeyCode::Char('C') => self.handle_open_registers(),
+                KeyCode::Char('a') if self.current_tab == Tab::ChaptersList => {
+                    self.handle_open_resolver();
+                }
                 KeyCode::Char(' ') if self.current_tab == Tab::ChaptersList => self.toggle_select(),
                 KeyCode::Char('?') => self.toggle_help(),
                 KeyCode::Char('=' | '+') => self.image_manager.next(),
@@ -234,6 +454,15 @@ impl App {
             KeyCode::Char('q') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 self.handle_ctrl_q();
             }
+            KeyCode::Char('e') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.handle_open_fields();
+            }
+            KeyCode::Char('y') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.handle_yank(false);
+            }
+            KeyCode::Char('p') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.handle_paste();
+            }
             KeyCode::Enter if self.input_mode == InputMode::Normal => {
                 self.input_mode = InputMode::Editing;
             }
@@ -267,6 +496,251 @@ impl App {
         }
     }
 
+    fn handle_key_popup(&mut self, popup: Popup, key: KeyEvent) {
+        match popup {
+            Popup::Preview => self.handle_key_preview(key),
+            Popup::Rename => self.handle_key_rename(key),
+            Popup::Pattern => self.handle_key_pattern(key),
+            Popup::Report => self.handle_key_report(key),
+            Popup::Resolve => self.handle_key_resolve(key),
+            Popup::SeriesJson => self.handle_key_series_json(key),
+            Popup::Stats => self.handle_key_stats(key),
+            Popup::Filter => self.handle_key_filter(key),
+            Popup::Jobs => self.handle_key_jobs(key),
+            Popup::Fields => self.handle_key_fields(key),
+            Popup::Transform => self.handle_key_transform(key),
+            Popup::VolumeMap => self.handle_key_volume_map(key),
+            Popup::Registers => self.handle_key_registers(key),
+        }
+    }
+
+    fn handle_key_transform(&mut self, key: KeyEvent) {
+        let dialog = &mut self.transform_dialog;
+        let on_input = matches!(dialog.focus, TransformFocus::Input(_));
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Down | KeyCode::Tab => dialog.next(),
+            KeyCode::Up | KeyCode::BackTab => dialog.prev(),
+            KeyCode::Right if !on_input => dialog.cycle(true),
+            KeyCode::Left if !on_input => dialog.cycle(false),
+            KeyCode::Enter => self.handle_apply_transform(),
+            _ => {
+                if let Some(input) = dialog.active_input_mut() {
+                    input.handle_event(&Event::Key(key));
+                }
+                dialog.error = None;
+            }
+        }
+    }
+
+    fn handle_key_volume_map(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Enter => self.handle_save_volume_map(),
+            KeyCode::Char('r') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let series = self.get_current_series();
+                self.volume_map_editor.import(&series);
+            }
+            _ => {
+                self.volume_map_editor.input.handle_event(&Event::Key(key));
+                self.volume_map_editor.error = None;
+            }
+        }
+    }
+
+    /// Key after `"`, naming the register of the next yank or paste
+    fn handle_key_register_name(&mut self, key: KeyEvent) {
+        self.registers.naming = false;
+        let named = if let KeyCode::Char(c) = key.code {
+            self.registers.name_register(c)
+        } else {
+            false
+        };
+        let status = match self.registers.target {
+            Some(name) if named => format!("\"{name}"),
+            _ => "Registers are named a-z or 0-9".to_string(),
+        };
+        let _ = self.status_tx.send(status);
+    }
+
+    fn handle_key_registers(&mut self, key: KeyEvent) {
+        if let Some(name) = &mut self.registers.name {
+            match key.code {
+                KeyCode::Esc => self.registers.name = None,
+                KeyCode::Enter => self.handle_save_clip_preset(),
+                _ => {
+                    name.handle_event(&Event::Key(key));
+                }
+            }
+            return;
+        }
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.registers.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.registers.prev(),
+            KeyCode::Char('s') => self.registers.start_naming(),
+            KeyCode::Char('D') => self.handle_delete_clip_preset(),
+            KeyCode::Enter => self.handle_paste_selected(),
+            KeyCode::Esc | KeyCode::Char('q') => self.popup = None,
+            _ => {}
+        }
+    }
+
+    fn handle_key_fields(&mut self, key: KeyEvent) {
+        if let Some(name) = &mut self.field_picker.name {
+            match key.code {
+                KeyCode::Esc => self.field_picker.name = None,
+                KeyCode::Enter => self.handle_save_field_preset(),
+                _ => {
+                    name.handle_event(&Event::Key(key));
+                }
+            }
+            return;
+        }
+
+        let presets = &self.config.field_presets;
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.field_picker.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.field_picker.prev(),
+            KeyCode::Char(' ') => self.field_picker.toggle(),
+            KeyCode::Char('a') => self.field_picker.toggle_all(),
+            KeyCode::Char('p') | KeyCode::Tab => self.field_picker.next_preset(presets),
+            KeyCode::Char('s') => self.field_picker.start_naming(presets),
+            KeyCode::Char('D') => self.handle_delete_field_preset(),
+            KeyCode::Enter => self.handle_apply_fields(),
+            KeyCode::Esc | KeyCode::Char('q') => self.popup = None,
+            _ => {}
+        }
+    }
+
+    fn handle_key_jobs(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.jobs_manager.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.jobs_manager.prev(),
+            KeyCode::Char('c') => self.handle_cancel_job(),
+            KeyCode::Char('r') => self.handle_retry_job(),
+            KeyCode::Esc | KeyCode::Char('q') => self.popup = None,
+            _ => {}
+        }
+    }
+
+    fn handle_key_series_json(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Down | KeyCode::Tab => self.series_json_editor.next(),
+            KeyCode::Up | KeyCode::BackTab => self.series_json_editor.prev(),
+            KeyCode::Enter => self.handle_save_series_json(),
+            _ => {
+                if let Some(input) = self.series_json_editor.active_input_mut() {
+                    input.handle_event(&Event::Key(key));
+                }
+                self.series_json_editor.error = None;
+            }
+        }
+    }
+
+    fn handle_key_filter(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Enter => self.handle_apply_filter(),
+            _ => {
+                self.chapter_view.filter.handle_event(&Event::Key(key));
+                self.chapter_view.error = None;
+            }
+        }
+    }
+
+    fn handle_key_stats(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.stats_manager.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.stats_manager.prev(),
+            KeyCode::Char('e') => self.handle_export_stats(),
+            KeyCode::Esc | KeyCode::Char('q') => self.popup = None,
+            _ => {}
+        }
+    }
+
+    fn handle_key_report(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.report_manager.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.report_manager.prev(),
+            KeyCode::Esc | KeyCode::Char('q') => self.popup = None,
+            _ => {}
+        }
+    }
+
+    fn handle_key_resolve(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Down => self.parse_resolver.next(),
+            KeyCode::Up => self.parse_resolver.prev(),
+            KeyCode::Enter => self.handle_apply_reading(),
+            _ => {
+                self.parse_resolver.input.handle_event(&Event::Key(key));
+                self.parse_resolver.update(&self.filename_parser);
+                // Typing selects the typed reading
+                self.parse_resolver.state.select_first();
+            }
+        }
+    }
+
+    fn handle_key_pattern(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Esc => self.popup = None,
+            KeyCode::Down => self.pattern_tester.next(),
+            KeyCode::Up => self.pattern_tester.prev(),
+            KeyCode::Enter => self.handle_save_pattern(),
+            _ => {
+                self.pattern_tester.input.handle_event(&Event::Key(key));
+                self.pattern_tester.test(&self.filename_parser);
+            }
+        }
+    }
+
+    fn handle_key_rename(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.rename_manager.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.rename_manager.prev(),
+            KeyCode::Char(' ') => {
+                let selected = self.rename_manager.state.selected().unwrap_or_default();
+                if let Some(preview) = &mut self.rename_manager.preview {
+                    preview.toggle_row(selected);
+                }
+            }
+            KeyCode::Enter => self.handle_apply_rename(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.rename_manager.preview = None;
+                self.popup = None;
+                let _ = self.status_tx.send("Discarded rename".to_string());
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_key_preview(&mut self, key: KeyEvent) {
+        let selected = self.preview_manager.state.selected().unwrap_or_default();
+        let Some(preview) = &mut self.preview_manager.preview else {
+            self.popup = None;
+            return;
+        };
+
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => self.preview_manager.next(),
+            KeyCode::Char('k') | KeyCode::Up => self.preview_manager.prev(),
+            KeyCode::Char(' ') => preview.toggle_row(selected),
+            KeyCode::Char('c') => preview.toggle_chapter(selected),
+            KeyCode::Char('f') => preview.toggle_field(selected),
+            KeyCode::Char('a') => preview.toggle_all(),
+            KeyCode::Enter => self.handle_apply_preview(),
+            KeyCode::Esc | KeyCode::Char('q') => {
+                self.preview_manager.preview = None;
+                self.popup = None;
+                let _ = self.status_tx.send("Discarded preview".to_string());
+            }
+            _ => {}
+        }
+    }
+
     fn handle_key_search(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Esc => {
@@ -306,13 +780,43 @@ impl App {
             self.image_manager.raw_images_rx = Some(images_rx);
             self.image_manager.images = ImagesState::Loading;
 
-            #[allow(clippy::cast_possible_truncation)]
+            // Unchanged archives show the cached info and cover until the pages are read
+            let cached = self.index.lock().ok().and_then(|index| index.comic(&path));
+            let from_cache = cached.is_some();
+            if let Some((info, thumbnail)) = cached {
+                self.comic_manager.comic = ComicFormState::Ready(ComicInfoForm::new(&info));
+                self.comic_manager.comic_rx = None;
+                if let Some(thumbnail) = thumbnail {
+                    self.image_manager.replace_images(vec![thumbnail]);
+                }
+            }
+
+            let encoding = self.config.archive_encoding.clone();
+            let index = self.index.clone();
             tokio::spawn(async move {
-                let (mut info, images, images_count) =
-                    get_comic_from_zip(&path).unwrap_or_default();
-                info.page_count = Some(images_count);
-                let form = ComicInfoForm::new(&info);
-                let _ = comic_tx.send(form);
+                // Taken before reading so a write in between isn't cached as this read
+                let key = fs::metadata(&path).map(|metadata| FileKey::of(&metadata));
+                let (mut info, images, images_count) = match get_comic_from_zip(&path, &encoding) {
+                    Ok(comic) => comic,
+                    Err(e) => {
+                        error!("Failed to read archive ({}): {e}", path.display());
+                        Default::default()
+                    }
+                };
+                if !from_cache {
+                    if let Ok(key) = key {
+                        let thumbnail = images.first().and_then(|cover| {
+                            write_thumbnail(&path, key, cover)
+                                .inspect_err(|e| warn!("Failed to write thumbnail: {e}"))
+                                .ok()
+                        });
+                        if let Ok(mut index) = index.lock() {
+                            index.store_comic(&path, key, &info, images_count, thumbnail);
+                        }
+                    }
+                    info.page_count = Some(images_count);
+                    let _ = comic_tx.send(ComicInfoForm::new(&info));
+                }
                 let _ = images_tx.send(images);
             });
         }
@@ -327,6 +831,177 @@ impl App {
         }
     }
 
+    fn poll_preview(&mut self) {
+        if let Some(rx) = &self.preview_manager.preview_rx
+            && let Ok(preview) = rx.try_recv()
+        {
+            self.preview_manager.preview = Some(preview);
+            self.preview_manager.preview_rx = None;
+            self.preview_manager.state.select_first();
+            self.popup = Some(Popup::Preview);
+        }
+    }
+
+    fn poll_rename(&mut self) {
+        if let Some(rx) = &self.rename_manager.renamed_rx
+            && let Ok(renamed) = rx.try_recv()
+        {
+            self.rename_manager.renamed_rx = None;
+            self.move_renamed(&renamed.paths);
+            self.refresh_series(&renamed.series);
+        }
+
+        if let Some(rx) = &self.rename_manager.preview_rx
+            && let Ok(preview) = rx.try_recv()
+        {
+            self.rename_manager.preview_rx = None;
+            if preview.rows.is_empty() {
+                return;
+            }
+            self.rename_manager.preview = Some(preview);
+            self.rename_manager.state.select_first();
+            self.popup = Some(Popup::Rename);
+        }
+    }
+
+    fn poll_report(&mut self) {
+        if let Some(rx) = &self.report_manager.reports_rx
+            && let Ok(reports) = rx.try_recv()
+        {
+            self.report_manager.reports = reports;
+            self.report_manager.reports_rx = None;
+            self.report_manager.state.select_first();
+            self.popup = Some(Popup::Report);
+        }
+    }
+
+    fn poll_stats(&mut self) {
+        if let Some(rx) = &self.stats_manager.stats_rx
+            && let Ok(stats) = rx.try_recv()
+        {
+            self.stats_manager.stats = Some(stats);
+            self.stats_manager.stats_rx = None;
+            self.stats_manager.scroll = 0;
+            self.popup = Some(Popup::Stats);
+        }
+    }
+
+    /// Reads the details of the current series once the previous read is in
+    fn poll_details(&mut self) {
+        if let Some(rx) = &self.details_manager.details_rx {
+            match rx.try_recv() {
+                Ok((series, chapters)) => {
+                    self.details_manager.series = Some(series);
+                    self.details_manager.chapters = chapters;
+                    self.details_manager.details_rx = None;
+                    self.apply_chapter_sort();
+                }
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.details_manager.details_rx = None;
+                }
+                Err(std::sync::mpsc::TryRecvError::Empty) => return,
+            }
+        }
+
+        let series = self.get_current_series();
+        if series.path.as_os_str().is_empty()
+            || self.details_manager.series.as_ref() == Some(&series.path)
+        {
+            return;
+        }
+
+        let (details_tx, details_rx) = std::sync::mpsc::channel();
+        self.details_manager.details_rx = Some(details_rx);
+        let rules = self.config.lint_rules.clone();
+        let encoding = self.config.archive_encoding.clone();
+        let index = self.index.clone();
+        tokio::spawn(async move {
+            let path = series.path.clone();
+            match read_series_details(series, rules, encoding, index).await {
+                Ok(chapters) => {
+                    let _ = details_tx.send((path, chapters));
+                }
+                Err(e) => error!("Failed to read series details ({}): {e}", path.display()),
+            }
+        });
+    }
+
+    fn poll_scan(&mut self) {
+        let Some(rx) = &self.library_scan.scan_rx else {
+            return;
+        };
+        let events: Vec<ScanEvent> = rx.try_iter().collect();
+
+        let mut found = Vec::new();
+        for event in events {
+            match event {
+                ScanEvent::Folder(series) => {
+                    self.library_scan.folders += 1;
+                    found.extend(series);
+                }
+                ScanEvent::Warning(warning) => {
+                    warn!("Failed to scan library: {warning}");
+                    self.library_scan.warnings.push(warning);
+                }
+                ScanEvent::Done => {
+                    self.library_scan.scan_rx = None;
+                    let unreadable = match self.library_scan.warnings.len() {
+                        0 => String::new(),
+                        n => format!(", {n} paths couldn't be read (see log)"),
+                    };
+                    let _ = self.status_tx.send(format!(
+                        "Found {} series in {} folders{unreadable}",
+                        self.library_scan.series + found.len(),
+                        self.library_scan.folders
+                    ));
+                }
+            }
+        }
+
+        if !found.is_empty() {
+            self.library_scan.series += found.len();
+            self.series_list.update(found, |_| true);
+        }
+    }
+
+    /// Applies the progress of the jobs and reports the ones that ended
+    fn poll_jobs(&mut self) {
+        let events: Vec<JobEvent> = self.jobs_manager.events_rx.try_iter().collect();
+        for event in events {
+            let Some(job) = self
+                .jobs_manager
+                .apply(event)
+                .and_then(|id| self.jobs_manager.get(id))
+            else {
+                continue;
+            };
+
+            let elapsed = job.elapsed.unwrap_or_default();
+            let status = match (job.state, job.failed.len()) {
+                (JobState::Cancelled, _) => format!("Cancelled {}: {}", job.title, job.progress()),
+                (_, 0) => format!(
+                    "All done~ processed {} chapters in {elapsed:.2?} 🎉",
+                    job.total
+                ),
+                (_, failed) => format!(
+                    "{}: {failed}/{} chapters failed, see the jobs panel (b)",
+                    job.title, job.total
+                ),
+            };
+            let _ = self.status_tx.send(status);
+            // Written archives have new metadata
+            self.details_manager.invalidate();
+        }
+    }
+
+    fn poll_watcher(&mut self) {
+        if let Some(watcher) = &mut self.library_watcher
+            && let Some(paths) = watcher.poll()
+        {
+            self.apply_library_changes(paths);
+        }
+    }
+
     fn poll_images(&mut self) {
         if let Some(rx) = &self.image_manager.raw_images_rx
             && let Ok(images) = rx.try_recv()
@@ -336,15 +1011,25 @@ impl App {
         }
     }
 
+    /// Selected series, empty while the library is still being scanned
     fn get_current_series(&self) -> Series {
         let current = self.series_list.state.selected().unwrap_or_default();
-        self.series_list.items_state[current].clone()
+        self.series_list
+            .items_state
+            .get(current)
+            .cloned()
+            .unwrap_or_default()
     }
 
     fn get_current_chapter(&self) -> Chapter {
         let series = self.get_current_series();
         let current = series.chapters.state.selected().unwrap_or_default();
-        series.chapters.items_state[current].clone()
+        series
+            .chapters
+            .items_state
+            .get(current)
+            .cloned()
+            .unwrap_or_default()
     }
 
     fn get_chapters_in_series(&self) -> Vec<Chapter> {
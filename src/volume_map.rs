@@ -0,0 +1,205 @@
+//! Chapter to volume tables like `1: 1-8, 2: 9-17, 3: 18-26.5`, kept in `series.json`
+//!
+//! A chapter is in a volume when its number is in the range, extras numbered after the last
+//! chapter of a volume like `8.5` stay in it unless another range has them.
+
+use std::{fmt::Display, str::FromStr};
+
+use anyhow::{Context, bail};
+
+use crate::ui::list::{Chapter, ChapterKind};
+
+/// Chapters `start` to `end` of a volume
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VolumeRange {
+    pub volume: u32,
+    pub start: f32,
+    pub end: f32,
+}
+
+impl VolumeRange {
+    fn contains(&self, number: f32) -> bool {
+        number >= self.start && number <= self.end
+    }
+
+    /// Whether `number` is an extra after the last chapter, like `8.5` after `1-8`
+    fn extends_to(&self, number: f32) -> bool {
+        number > self.end && number.floor() <= self.end
+    }
+}
+
+/// Ranges of every volume, a volume may have more than one
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VolumeMap {
+    pub ranges: Vec<VolumeRange>,
+}
+
+impl VolumeMap {
+    /// Builds a map from the volumes chapters already have, one range per volume
+    pub fn from_chapters(chapters: &[Chapter]) -> Self {
+        let mut ranges: Vec<VolumeRange> = Vec::new();
+        for chapter in chapters.iter().filter(|c| !c.is_volume()) {
+            let (Some(volume), Some(start)) = (chapter.volume, chapter.chapter) else {
+                continue;
+            };
+            let end = chapter.chapter_end.unwrap_or(start);
+
+            match ranges.iter_mut().find(|r| r.volume == volume) {
+                Some(range) => {
+                    range.start = range.start.min(start);
+                    range.end = range.end.max(end);
+                }
+                None => ranges.push(VolumeRange { volume, start, end }),
+            }
+        }
+
+        ranges.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Self { ranges }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// Volume of a chapter number, explicit ranges win over the extras of another volume
+    pub fn volume_of(&self, number: f32) -> Option<u32> {
+        self.ranges
+            .iter()
+            .find(|range| range.contains(number))
+            .or_else(|| self.ranges.iter().find(|range| range.extends_to(number)))
+            .map(|range| range.volume)
+    }
+
+    /// Volume of a regular numbered chapter, volume archives and specials without a number have
+    /// none
+    pub fn volume_of_chapter(&self, chapter: &Chapter) -> Option<u32> {
+        if chapter.is_volume() || chapter.kind != ChapterKind::Regular {
+            return None;
+        }
+        self.volume_of(chapter.chapter?)
+    }
+
+    /// Fills in the volume of chapters whose filename doesn't have one
+    pub fn assign(&self, chapters: &mut [Chapter]) {
+        for chapter in chapters.iter_mut().filter(|c| c.volume.is_none()) {
+            chapter.volume = self.volume_of_chapter(chapter);
+        }
+    }
+}
+
+fn parse_number(text: &str) -> anyhow::Result<f32> {
+    text.trim()
+        .parse()
+        .with_context(|| format!("Invalid chapter number {}", text.trim()))
+}
+
+impl FromStr for VolumeMap {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ranges: Vec<VolumeRange> = Vec::new();
+        for entry in s.split([',', ';', '\n']).filter(|e| !e.trim().is_empty()) {
+            let Some((volume, chapters)) = entry.split_once(':') else {
+                bail!("Expected `volume: first-last`, got {}", entry.trim());
+            };
+            let volume = volume
+                .trim()
+                .trim_start_matches(['v', 'V'])
+                .parse()
+                .with_context(|| format!("Invalid volume {}", volume.trim()))?;
+            let (start, end) = match chapters.split_once('-') {
+                Some((start, end)) => (parse_number(start)?, parse_number(end)?),
+                None => (parse_number(chapters)?, parse_number(chapters)?),
+            };
+            if start > end {
+                bail!("Volume {volume} ends before it starts");
+            }
+
+            let range = VolumeRange { volume, start, end };
+            if let Some(other) = ranges
+                .iter()
+                .find(|r| r.start <= range.end && range.start <= r.end)
+            {
+                bail!("Volumes {} and {volume} overlap", other.volume);
+            }
+            ranges.push(range);
+        }
+
+        ranges.sort_by(|a, b| a.start.total_cmp(&b.start));
+        Ok(Self { ranges })
+    }
+}
+
+impl Display for VolumeMap {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let entries: Vec<String> = self
+            .ranges
+            .iter()
+            .map(|r| {
+                if r.end > r.start {
+                    format!("{}: {}-{}", r.volume, r.start, r.end)
+                } else {
+                    format!("{}: {}", r.volume, r.start)
+                }
+            })
+            .collect();
+        write!(f, "{}", entries.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chapter(volume: Option<u32>, number: f32) -> Chapter {
+        Chapter {
+            volume,
+            chapter: Some(number),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_parse_and_lookup() {
+        let map: VolumeMap = "1: 1-8, 2: 9-17; v3: 18-26.5".parse().unwrap();
+        assert_eq!(map.to_string(), "1: 1-8, 2: 9-17, 3: 18-26.5");
+
+        assert_eq!(map.volume_of(1.0), Some(1));
+        assert_eq!(map.volume_of(8.5), Some(1));
+        assert_eq!(map.volume_of(9.0), Some(2));
+        assert_eq!(map.volume_of(26.5), Some(3));
+        assert_eq!(map.volume_of(27.0), None);
+        assert_eq!(map.volume_of(0.5), None);
+
+        // Extras listed in the next volume belong to it
+        let map: VolumeMap = "1: 1-8, 2: 8.5-17".parse().unwrap();
+        assert_eq!(map.volume_of(8.0), Some(1));
+        assert_eq!(map.volume_of(8.5), Some(2));
+        assert_eq!(map.volume_of(8.2), Some(1));
+        assert_eq!(map.volume_of(17.5), Some(2));
+
+        assert!("1: 1-8, 2: 8-17".parse::<VolumeMap>().is_err());
+        assert!("1: 8-1".parse::<VolumeMap>().is_err());
+        assert!("1 1-8".parse::<VolumeMap>().is_err());
+        assert!("x: 1-8".parse::<VolumeMap>().is_err());
+    }
+
+    #[test]
+    fn test_assign_and_import() {
+        let map: VolumeMap = "1: 1-2, 2: 3-4".parse().unwrap();
+        let mut chapters = vec![
+            chapter(None, 1.0),
+            chapter(None, 3.0),
+            chapter(Some(5), 4.0),
+            chapter(None, 5.0),
+        ];
+        map.assign(&mut chapters);
+
+        let volumes: Vec<Option<u32>> = chapters.iter().map(|c| c.volume).collect();
+        // The filename wins over the map
+        assert_eq!(volumes, [Some(1), Some(2), Some(5), None]);
+
+        let imported = VolumeMap::from_chapters(&chapters);
+        assert_eq!(imported.to_string(), "1: 1, 2: 3, 5: 4");
+    }
+}
@@ -1,4 +1,5 @@
 use std::{
+    collections::{HashMap, HashSet},
     fs,
     io::{Cursor, Read, Seek, Write},
     path::{Path, PathBuf},
@@ -10,16 +11,13 @@ use quick_xml::{
     events::{BytesDecl, BytesText, Event},
     se::to_string,
 };
-use zip::{ZipArchive, ZipWriter, write::SimpleFileOptions};
+use zip::{HasZipMetadata, ZipArchive, ZipWriter, write::SimpleFileOptions};
 
-use crate::comic_info::ComicInfo;
+use crate::comic_info::{ComicInfo, ComicInfoField};
 
 /// Comment to add to `ComicInfo.xml`
 const COMMENT: &str = " Modified by cbz-edit ";
 
-/// Type alias for `ComicInfo` update callback
-pub type ComicInfoUpdater = fn(old: ComicInfo, new: &ComicInfo) -> ComicInfo;
-
 /// Modify a flat ZIP (no subdirectories) in-memory by replacing the file at `target_path` with
 /// `new_comic_info`.
 /// If `replace_all` is true, the contents of `new_comic_info` will be fully written to the file
@@ -27,7 +25,7 @@ pub type ComicInfoUpdater = fn(old: ComicInfo, new: &ComicInfo) -> ComicInfo;
 fn modify_zip(
     input_path: &PathBuf,
     new_comic_info: &ComicInfo,
-    updater: ComicInfoUpdater,
+    updater: impl Fn(ComicInfo, &ComicInfo) -> ComicInfo,
 ) -> anyhow::Result<()> {
     let input_zip = fs::read(input_path)?;
     let reader = Cursor::new(&input_zip);
@@ -38,6 +36,7 @@ fn modify_zip(
         let cursor = Cursor::new(&mut out_buf);
         let mut writer = ZipWriter::new(cursor);
         let mut found_comic_info = false;
+        let mut raw_names = Vec::new();
 
         for i in 0..zip.len() {
             let mut src = zip.by_index(i)?;
@@ -46,18 +45,19 @@ fn modify_zip(
 
             if name == "ComicInfo.xml" {
                 found_comic_info = true;
-                write_comic_info(&mut writer, &mut src, new_comic_info, updater, &opts)?;
+                write_comic_info(&mut writer, &mut src, new_comic_info, &updater, &opts)?;
                 continue;
             }
 
-            copy_file(&mut writer, &mut src, &name, &opts)?;
+            raw_names.push(copy_file(&mut writer, src, i)?);
         }
 
         if !found_comic_info {
-            add_new_comic_info(&mut writer, new_comic_info, updater)?;
+            add_new_comic_info(&mut writer, new_comic_info, &updater)?;
         }
 
         writer.finish()?;
+        restore_raw_names(&mut out_buf, &raw_names)?;
     }
 
     fs::write(input_path, &out_buf)?;
@@ -82,7 +82,7 @@ fn write_comic_info<W, R>(
     writer: &mut ZipWriter<W>,
     src: &mut zip::read::ZipFile<R>,
     new_info: &ComicInfo,
-    updater: ComicInfoUpdater,
+    updater: &impl Fn(ComicInfo, &ComicInfo) -> ComicInfo,
     opts: &SimpleFileOptions,
 ) -> anyhow::Result<()>
 where
@@ -106,7 +106,7 @@ where
 fn add_new_comic_info<W>(
     writer: &mut ZipWriter<W>,
     new_info: &ComicInfo,
-    updater: ComicInfoUpdater,
+    updater: &impl Fn(ComicInfo, &ComicInfo) -> ComicInfo,
 ) -> anyhow::Result<()>
 where
     W: Seek + Write,
@@ -121,24 +121,329 @@ where
     Ok(())
 }
 
-/// Copies a file from `src` to `writer`
+/// Name of a copied entry as it was written and as it was stored in the source archive
+struct RawName {
+    written: String,
+    raw: Vec<u8>,
+    utf8: bool,
+}
+
+/// Copies a file from `src` to `writer` without recompressing it.
+///
+/// Names that aren't UTF-8 (CP437, Shift-JIS, ...) can't be written as is, so they're written
+/// under a placeholder of the same length which [`restore_raw_names`] swaps back.
 fn copy_file<W, R>(
     writer: &mut ZipWriter<W>,
-    src: &mut zip::read::ZipFile<R>,
-    name: &str,
-    opts: &SimpleFileOptions,
-) -> anyhow::Result<()>
+    src: zip::read::ZipFile<R>,
+    index: usize,
+) -> anyhow::Result<RawName>
 where
     W: Seek + Write,
     R: Read,
 {
-    let mut buf = Vec::with_capacity(usize::try_from(src.size())?);
-    src.read_to_end(&mut buf)?;
-    writer.start_file(name, *opts)?;
-    writer.write_all(&buf)?;
+    let raw = src.name_raw().to_vec();
+    let utf8 = src.get_metadata().is_utf8;
+
+    let written = if utf8 || raw.is_ascii() {
+        src.name().to_string()
+    } else if let Some(placeholder) = placeholder_name(index, raw.len()) {
+        placeholder
+    } else {
+        warn!("No placeholder fits entry name {raw:?}, storing it as UTF-8");
+        src.name().to_string()
+    };
+
+    writer.raw_copy_file_rename(src, &written)?;
+    Ok(RawName { written, raw, utf8 })
+}
+
+/// Unique ASCII name of exactly `len` bytes made of control characters, which real entry names
+/// never contain
+fn placeholder_name(mut index: usize, len: usize) -> Option<String> {
+    const DIGITS: u8 = 0x1F;
+
+    let mut name = vec![1u8; len];
+    for byte in name.iter_mut().rev() {
+        #[allow(clippy::cast_possible_truncation)]
+        let digit = (index % usize::from(DIGITS)) as u8;
+        *byte = 1 + digit;
+        index /= usize::from(DIGITS);
+    }
+
+    if index == 0 {
+        String::from_utf8(name).ok()
+    } else {
+        None
+    }
+}
+
+/// Reads a little-endian `u16` at `offset`
+fn read_u16(buf: &[u8], offset: usize) -> anyhow::Result<u16> {
+    let bytes = buf
+        .get(offset..offset + 2)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected end of archive"))?;
+    Ok(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Reads a little-endian `u32` at `offset`
+fn read_u32(buf: &[u8], offset: usize) -> anyhow::Result<u32> {
+    let bytes = buf
+        .get(offset..offset + 4)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected end of archive"))?;
+    Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Reads a little-endian `u64` at `offset`
+fn read_u64(buf: &[u8], offset: usize) -> anyhow::Result<u64> {
+    let bytes = buf
+        .get(offset..offset + 8)
+        .ok_or_else(|| anyhow::anyhow!("Unexpected end of archive"))?;
+    let mut le = [0u8; 8];
+    le.copy_from_slice(bytes);
+    Ok(u64::from_le_bytes(le))
+}
+
+/// Offset of the central directory of an archive written without a comment
+fn central_directory_offset(buf: &[u8]) -> anyhow::Result<usize> {
+    let eocd = buf
+        .len()
+        .checked_sub(22)
+        .filter(|&i| buf[i..].starts_with(b"PK\x05\x06"))
+        .ok_or_else(|| anyhow::anyhow!("End of central directory not found"))?;
+
+    let offset = read_u32(buf, eocd + 16)?;
+    if offset != u32::MAX {
+        return Ok(usize::try_from(offset)?);
+    }
+
+    // Zip64 locator sits right before the end of central directory
+    let locator = eocd
+        .checked_sub(20)
+        .filter(|&i| buf[i..].starts_with(b"PK\x06\x07"))
+        .ok_or_else(|| anyhow::anyhow!("Zip64 locator not found"))?;
+    let record = usize::try_from(read_u64(buf, locator + 8)?)?;
+    Ok(usize::try_from(read_u64(buf, record + 48)?)?)
+}
+
+/// Offset of the local header of a central directory entry, looking into the zip64 extra field
+/// when it doesn't fit in 32 bits
+fn local_header_offset(buf: &[u8], entry: usize, extra: &[u8]) -> anyhow::Result<usize> {
+    let offset = read_u32(buf, entry + 42)?;
+    if offset != u32::MAX {
+        return Ok(usize::try_from(offset)?);
+    }
+
+    let mut i = 0;
+    while i + 4 <= extra.len() {
+        let id = read_u16(extra, i)?;
+        let len = usize::from(read_u16(extra, i + 2)?);
+        if id == 0x0001 {
+            // Sizes come first, but only when they overflow as well
+            let mut field = i + 4;
+            if read_u32(buf, entry + 24)? == u32::MAX {
+                field += 8;
+            }
+            if read_u32(buf, entry + 20)? == u32::MAX {
+                field += 8;
+            }
+            return Ok(usize::try_from(read_u64(extra, field)?)?);
+        }
+        i += 4 + len;
+    }
+
+    anyhow::bail!("Zip64 extra field not found")
+}
+
+/// Puts back the raw name bytes and UTF-8 flags of copied entries into a finished archive
+fn restore_raw_names(buf: &mut [u8], raw_names: &[RawName]) -> anyhow::Result<()> {
+    const UTF8_FLAG: u16 = 1 << 11;
+
+    let mut entry = central_directory_offset(buf)?;
+    while buf.get(entry..entry + 4) == Some(b"PK\x01\x02") {
+        let name_len = usize::from(read_u16(buf, entry + 28)?);
+        let extra_len = usize::from(read_u16(buf, entry + 30)?);
+        let comment_len = usize::from(read_u16(buf, entry + 32)?);
+        let name_start = entry + 46;
+        let name = &buf[name_start..name_start + name_len];
+
+        if let Some(raw_name) = raw_names.iter().find(|n| n.written.as_bytes() == name)
+            && raw_name.raw.len() == name_len
+        {
+            let extra = buf[name_start + name_len..name_start + name_len + extra_len].to_vec();
+            let local = local_header_offset(buf, entry, &extra)?;
+
+            for (flags_at, name_at) in [(entry + 8, name_start), (local + 6, local + 30)] {
+                let mut flags = read_u16(buf, flags_at)?;
+                if raw_name.utf8 {
+                    flags |= UTF8_FLAG;
+                } else {
+                    flags &= !UTF8_FLAG;
+                }
+                buf[flags_at..flags_at + 2].copy_from_slice(&flags.to_le_bytes());
+                buf[name_at..name_at + name_len].copy_from_slice(&raw_name.raw);
+            }
+        }
+
+        entry = name_start + name_len + extra_len + comment_len;
+    }
+
     Ok(())
 }
 
+/// Whether a Shift-JIS reading of a name is likely right: it has kana or kanji, and no control
+/// or half-width katakana characters, which CP437 accented letters and symbols decode to
+fn looks_japanese(name: &str) -> bool {
+    let is_kana_or_kanji = |c: char| {
+        matches!(c,
+            '\u{3040}'..='\u{30ff}' | '\u{3400}'..='\u{4dbf}' | '\u{4e00}'..='\u{9fff}')
+    };
+    let is_half_width_kana = |c: char| matches!(c, '\u{ff61}'..='\u{ff9f}');
+
+    name.chars().any(is_kana_or_kanji)
+        && !name
+            .chars()
+            .any(|c| c.is_control() || is_half_width_kana(c))
+}
+
+/// Decodes the name of an entry, `true` when the encoding is a guess that may be wrong.
+///
+/// Names flagged as UTF-8 or which happen to be valid UTF-8 are used as is, the rest is decoded
+/// with `encoding` (an encoding label like `shift_jis`, `cp437` or `auto` to guess). `auto`
+/// reads Shift-JIS only when the result looks Japanese, else CP437.
+fn decode_name<R: Read>(src: &zip::read::ZipFile<R>, encoding: &str) -> (String, bool) {
+    let raw = src.name_raw();
+    if src.get_metadata().is_utf8 || raw.is_ascii() {
+        return (src.name().to_string(), false);
+    }
+
+    if let Ok(name) = std::str::from_utf8(raw) {
+        return (name.to_string(), false);
+    }
+
+    let decode = |encoding: &'static encoding_rs::Encoding| {
+        encoding
+            .decode_without_bom_handling_and_without_replacement(raw)
+            .map(std::borrow::Cow::into_owned)
+    };
+    // zip already decodes names without the UTF-8 flag as CP437
+    let cp437 = src.name().to_string();
+
+    match encoding.to_lowercase().as_str() {
+        "cp437" | "ibm437" => (cp437, false),
+        "auto" => match decode(encoding_rs::SHIFT_JIS) {
+            Some(name) if looks_japanese(&name) => (name, false),
+            // Valid Shift-JIS that doesn't read like Japanese, CP437 is only a better guess
+            Some(_) => (cp437, true),
+            None => (cp437, false),
+        },
+        label => match encoding_rs::Encoding::for_label(label.as_bytes()).and_then(decode) {
+            Some(name) => (name, false),
+            None => (cp437, true),
+        },
+    }
+}
+
+/// Decodes the name of an entry for display, see [`decode_name`]
+pub fn decode_entry_name<R: Read>(src: &zip::read::ZipFile<R>, encoding: &str) -> String {
+    decode_name(src, encoding).0
+}
+
+/// Entry stored with a legacy (non UTF-8) name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LegacyName {
+    /// Name as zip reads it, in CP437
+    pub current: String,
+    pub decoded: String,
+    /// The encoding was guessed and the decoded name may be wrong
+    pub ambiguous: bool,
+    /// Another entry of the archive has or would get the decoded name
+    pub conflict: bool,
+}
+
+/// List entries stored with legacy (non UTF-8) names with what they decode to
+pub fn legacy_entry_names(path: &PathBuf, encoding: &str) -> anyhow::Result<Vec<LegacyName>> {
+    let input_zip = fs::File::open(path)?;
+    let mut archive = ZipArchive::new(input_zip)?;
+    let mut names = Vec::new();
+    let mut kept = HashSet::new();
+
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        if file.get_metadata().is_utf8 || file.name_raw().is_ascii() {
+            kept.insert(file.name().to_string());
+            continue;
+        }
+
+        let (decoded, ambiguous) = decode_name(&file, encoding);
+        names.push(LegacyName {
+            current: file.name().to_string(),
+            decoded,
+            ambiguous,
+            conflict: false,
+        });
+    }
+
+    let mut targets: HashMap<String, usize> = HashMap::new();
+    for name in &names {
+        *targets.entry(name.decoded.clone()).or_default() += 1;
+    }
+    for name in &mut names {
+        name.conflict = targets[&name.decoded] > 1 || kept.contains(&name.decoded);
+    }
+
+    Ok(names)
+}
+
+/// Rewrite legacy encoded entry names as UTF-8, `renames` maps the current names (as zip reads
+/// them) to the new ones. Returns the number of renamed entries.
+pub fn repair_entry_names(
+    path: &PathBuf,
+    renames: &HashMap<String, String>,
+) -> anyhow::Result<usize> {
+    let input_zip = fs::read(path)?;
+    let reader = Cursor::new(&input_zip);
+    let mut zip = ZipArchive::new(reader)?;
+    let mut out_buf = Vec::with_capacity(input_zip.len());
+    let mut repaired = 0;
+
+    // Duplicate names would corrupt the central directory
+    let mut names = HashSet::new();
+    for i in 0..zip.len() {
+        let name = zip.by_index_raw(i)?.name().to_string();
+        let name = renames.get(&name).cloned().unwrap_or(name);
+        if !names.insert(name.clone()) {
+            anyhow::bail!("Two entries would be named {name}");
+        }
+    }
+
+    {
+        let cursor = Cursor::new(&mut out_buf);
+        let mut writer = ZipWriter::new(cursor);
+        let mut raw_names = Vec::new();
+
+        for i in 0..zip.len() {
+            let src = zip.by_index_raw(i)?;
+
+            if let Some(name) = renames.get(src.name()) {
+                let name = name.clone();
+                writer.raw_copy_file_rename(src, name)?;
+                repaired += 1;
+                continue;
+            }
+
+            raw_names.push(copy_file(&mut writer, src, i)?);
+        }
+
+        writer.finish()?;
+        restore_raw_names(&mut out_buf, &raw_names)?;
+    }
+
+    if repaired > 0 {
+        fs::write(path, &out_buf)?;
+    }
+    Ok(repaired)
+}
+
 /// Inserts an XML comment at the top of an existing XML string using quick-xml.
 fn add_xml_comment(xml: &str, comment: &str) -> anyhow::Result<String> {
     let mut reader = Reader::from_str(xml);
@@ -161,52 +466,90 @@ fn add_xml_comment(xml: &str, comment: &str) -> anyhow::Result<String> {
     Ok(String::from_utf8(writer.into_inner().into_inner())?)
 }
 
-/// Overwrites everything
-fn replace_all_updater(_old: ComicInfo, new: &ComicInfo) -> ComicInfo {
-    new.clone()
-}
-
 /// Updates only shared fields
-fn update_shared_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
+pub fn update_shared_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
     old.update_shared_fields(new);
     old
 }
 
 /// Updates fields derived from filename
-fn derive_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
+pub fn derive_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
     old.update_derived_fields(new);
     old
 }
 
 /// Updates volume
-fn volume_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
+pub fn volume_updater(mut old: ComicInfo, new: &ComicInfo) -> ComicInfo {
     old.update_volume(new);
     old
 }
 
-/// Modify a flat ZIP (no subdirectories) in-memory by replacing the file at `target_path` with
-/// `new_comic_info`.
-pub fn modify_comic_info(path: &PathBuf, new_comic_info: &ComicInfo) -> anyhow::Result<()> {
-    modify_zip(path, new_comic_info, update_shared_updater)
+/// Writes `fields` of `new_comic_info` over the `ComicInfo.xml` the archive has now, so changes
+/// made since `new_comic_info` was read are kept
+pub fn update_comic_info(
+    path: &PathBuf,
+    new_comic_info: &ComicInfo,
+    fields: &[ComicInfoField],
+) -> anyhow::Result<()> {
+    modify_zip(path, new_comic_info, |mut old, new| {
+        old.update_fields(new, fields);
+        old
+    })
 }
 
-/// Replace the file at `target_path` with `new_comic_info`.
-pub fn replace_comic_info(path: &PathBuf, new_comic_info: &ComicInfo) -> anyhow::Result<()> {
-    modify_zip(path, new_comic_info, replace_all_updater)
+/// Read only the `ComicInfo.xml` from a ZIP, defaulting when it's missing
+pub fn read_comic_info(path: &PathBuf) -> anyhow::Result<ComicInfo> {
+    let input_zip = fs::File::open(path)?;
+    let mut archive = ZipArchive::new(input_zip)?;
+
+    let comic_info = match archive.by_name("ComicInfo.xml") {
+        Ok(mut file) => {
+            let mut content = String::new();
+            file.read_to_string(&mut content)?;
+            from_str(&content).unwrap_or_default()
+        }
+        Err(_) => ComicInfo::default(), // file not found
+    };
+
+    Ok(comic_info)
 }
 
-/// Modify the zip with derived info
-pub fn derive_comic_info(path: &PathBuf, new_comic_info: &ComicInfo) -> anyhow::Result<()> {
-    modify_zip(path, new_comic_info, derive_updater)
+/// Number of a page image named like `001.jpg`
+fn page_number(name: &str) -> Option<usize> {
+    let is_ext = |extension: &str| -> bool {
+        Path::new(name)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+    };
+
+    if is_ext("jpg") || is_ext("jpeg") || is_ext("png") || is_ext("webp") {
+        name.split('.').next()?.parse().ok()
+    } else {
+        None
+    }
 }
 
-/// Modify the zip with volume number
-pub fn volume_comic_info(path: &PathBuf, new_comic_info: &ComicInfo) -> anyhow::Result<()> {
-    modify_zip(path, new_comic_info, volume_updater)
+/// Read the `ComicInfo.xml` and count the pages without reading them
+pub fn read_comic_summary(path: &PathBuf, encoding: &str) -> anyhow::Result<(ComicInfo, u32)> {
+    let comic_info = read_comic_info(path)?;
+    let mut archive = ZipArchive::new(fs::File::open(path)?)?;
+
+    let mut pages = 0;
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        if page_number(&decode_entry_name(&file, encoding)).is_some() {
+            pages += 1;
+        }
+    }
+
+    Ok((comic_info, pages))
 }
 
 /// Get the `ComicInfo.xml` from a flat ZIP (no subdirectories)
-pub fn get_comic_from_zip(path: &PathBuf) -> anyhow::Result<(ComicInfo, Vec<Vec<u8>>, u32)> {
+pub fn get_comic_from_zip(
+    path: &PathBuf,
+    encoding: &str,
+) -> anyhow::Result<(ComicInfo, Vec<Vec<u8>>, u32)> {
     let input_zip = fs::File::open(path)?;
     let mut archive = ZipArchive::new(input_zip)?;
 
@@ -224,22 +567,13 @@ pub fn get_comic_from_zip(path: &PathBuf) -> anyhow::Result<(ComicInfo, Vec<Vec<
 
     for i in 0..archive.len() {
         let mut file = archive.by_index(i)?;
-        let name = file.name().to_string();
+        let name = decode_entry_name(&file, encoding);
 
         if name.eq_ignore_ascii_case("ComicInfo.xml") {
             continue;
         }
 
-        let is_ext = |extension: &str| -> bool {
-            Path::new(&name)
-                .extension()
-                .is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
-        };
-
-        if (is_ext("jpg") || is_ext("jpeg") || is_ext("png") || is_ext("webp"))
-            && let Some(number_str) = name.split('.').next()
-            && let Ok(number) = number_str.parse::<usize>()
-        {
+        if let Some(number) = page_number(&name) {
             images_count += 1;
 
             if images.len() < 10 {
@@ -255,3 +589,155 @@ pub fn get_comic_from_zip(path: &PathBuf) -> anyhow::Result<(ComicInfo, Vec<Vec<
 
     Ok((comic_info, images, images_count))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// "表紙.jpg" encoded as Shift-JIS
+    const SJIS_NAME: &[u8] = b"\x95\x5c\x8e\x86.jpg";
+
+    /// Writes an archive with entries named by raw bytes, only valid UTF-8 names get the flag
+    fn archive_with(names: &[&[u8]]) -> tempfile::NamedTempFile {
+        let mut buf = Vec::new();
+        {
+            let mut writer = ZipWriter::new(Cursor::new(&mut buf));
+            let mut raw_names = Vec::new();
+            for (i, raw) in names.iter().enumerate() {
+                let written = placeholder_name(i, raw.len()).unwrap();
+                writer
+                    .start_file(written.as_str(), SimpleFileOptions::default())
+                    .unwrap();
+                writer.write_all(b"image").unwrap();
+                raw_names.push(RawName {
+                    written,
+                    raw: raw.to_vec(),
+                    utf8: std::str::from_utf8(raw).is_ok() && !raw.is_ascii(),
+                });
+            }
+            writer.finish().unwrap();
+            restore_raw_names(&mut buf, &raw_names).unwrap();
+        }
+
+        let file = tempfile::NamedTempFile::new().unwrap();
+        fs::write(file.path(), &buf).unwrap();
+        file
+    }
+
+    /// Archive with a Shift-JIS entry name stored without the UTF-8 flag
+    fn legacy_archive() -> tempfile::NamedTempFile {
+        archive_with(&[SJIS_NAME])
+    }
+
+    fn first_entry(path: &PathBuf) -> (Vec<u8>, bool, String) {
+        let mut archive = ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+        let file = archive.by_index_raw(0).unwrap();
+        (
+            file.name_raw().to_vec(),
+            file.get_metadata().is_utf8,
+            decode_entry_name(&file, "auto"),
+        )
+    }
+
+    #[test]
+    fn test_copy_keeps_raw_names() {
+        let file = legacy_archive();
+        let path = file.path().to_path_buf();
+
+        update_comic_info(&path, &ComicInfo::default(), &ComicInfoField::ALL).unwrap();
+
+        let (raw, utf8, name) = first_entry(&path);
+        assert_eq!(raw, SJIS_NAME);
+        assert!(!utf8);
+        assert_eq!(name, "表紙.jpg");
+    }
+
+    #[test]
+    fn test_update_only_changed_fields() {
+        let file = legacy_archive();
+        let path = file.path().to_path_buf();
+
+        let previewed = ComicInfo {
+            title: String::from("Cover"),
+            ..Default::default()
+        };
+        // Changed after the preview was made
+        let edited = ComicInfo {
+            publisher: Some(String::from("Viz")),
+            ..Default::default()
+        };
+        update_comic_info(&path, &edited, &ComicInfoField::ALL).unwrap();
+
+        update_comic_info(&path, &previewed, &[ComicInfoField::Title]).unwrap();
+        let info = read_comic_info(&path).unwrap();
+        assert_eq!(info.title, "Cover");
+        assert_eq!(info.publisher.as_deref(), Some("Viz"));
+    }
+
+    #[test]
+    fn test_repair_entry_names() {
+        let file = legacy_archive();
+        let path = file.path().to_path_buf();
+
+        let names = legacy_entry_names(&path, "shift_jis").unwrap();
+        assert_eq!(names[0].decoded, "表紙.jpg");
+        assert!(!names[0].ambiguous && !names[0].conflict);
+
+        let renames = HashMap::from([(names[0].current.clone(), names[0].decoded.clone())]);
+        assert_eq!(repair_entry_names(&path, &renames).unwrap(), 1);
+
+        let (raw, utf8, _) = first_entry(&path);
+        assert_eq!(raw, "表紙.jpg".as_bytes());
+        assert!(utf8);
+    }
+
+    #[test]
+    fn test_guess_encoding() {
+        // "café.jpg" in CP437 isn't valid Shift-JIS, "░▒.jpg" is but reads as half-width kana
+        let file = archive_with(&[SJIS_NAME, b"caf\x82.jpg", b"\xb0\xb1.jpg"]);
+        let names = legacy_entry_names(&file.path().to_path_buf(), "auto").unwrap();
+        let decoded: Vec<(&str, bool)> = names
+            .iter()
+            .map(|n| (n.decoded.as_str(), n.ambiguous))
+            .collect();
+        assert_eq!(
+            decoded,
+            [("表紙.jpg", false), ("café.jpg", false), ("░▒.jpg", true)]
+        );
+    }
+
+    #[test]
+    fn test_entry_name_conflicts() {
+        // Two Shift-JIS codes of "ⅰ", and a UTF-8 entry the legacy "表紙.jpg" would clash with
+        let file = archive_with(&[
+            b"\x95\x5c\x8e\x86\xee\xef.jpg",
+            b"\x95\x5c\x8e\x86\xfa\x40.jpg",
+            SJIS_NAME,
+            "表紙.jpg".as_bytes(),
+            b"\x95\x5c.jpg",
+        ]);
+        let path = file.path().to_path_buf();
+        let names = legacy_entry_names(&path, "auto").unwrap();
+        let decoded: Vec<(&str, bool)> = names
+            .iter()
+            .map(|n| (n.decoded.as_str(), n.conflict))
+            .collect();
+        assert_eq!(
+            decoded,
+            [
+                ("表紙ⅰ.jpg", true),
+                ("表紙ⅰ.jpg", true),
+                ("表紙.jpg", true),
+                ("表.jpg", false)
+            ]
+        );
+
+        // Repairing a clash anyway is refused and leaves the archive alone
+        let renames: HashMap<String, String> = names
+            .iter()
+            .map(|n| (n.current.clone(), n.decoded.clone()))
+            .collect();
+        assert!(repair_entry_names(&path, &renames).is_err());
+        assert_eq!(legacy_entry_names(&path, "auto").unwrap().len(), 4);
+    }
+}